@@ -0,0 +1,38 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Tools for analysing the contents of a game board, such as identifying the
+//! objects that a soup leaves behind once it has stabilised.
+//!
+//! The analysis works on an unbounded plane using signed coordinates, so that
+//! objects can be simulated in isolation without worrying about the edges of
+//! the game board.
+
+pub mod apgcode;
+pub mod census;
+pub mod components;
+pub mod life;
+pub mod lifespan;
+pub mod periodicity;
+pub mod stabilisation;
+
+pub use census::Census;
+pub use periodicity::analyze;
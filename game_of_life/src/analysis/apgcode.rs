@@ -0,0 +1,279 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use std::fmt;
+
+use crate::analysis::life::{self, CellSet, Coordinate};
+
+/// Maximum period of the oscillators and spaceships that can be identified.
+pub const MAX_PERIOD: usize = 64;
+
+/// Code used (as in apgsearch) for objects that cannot be identified.
+pub const PATHOLOGICAL: &str = "PATHOLOGICAL";
+
+/// Characters used by the extended Wechsler format. Each character is a
+/// column of five cells, with the top cell as the least significant bit.
+const WECHSLER_CHARS: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+
+/// Characters used after a `y` to give the length of a run of four or more
+/// empty columns.
+const RUN_LENGTH_CHARS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Height of each strip of cells in the extended Wechsler format.
+const STRIP_HEIGHT: i64 = 5;
+
+/// The kind of an object.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, PartialOrd, Ord)]
+pub enum ObjectKind {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    Unidentified,
+}
+
+impl fmt::Display for ObjectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ObjectKind::StillLife => "still life",
+            ObjectKind::Oscillator => "oscillator",
+            ObjectKind::Spaceship => "spaceship",
+            ObjectKind::Unidentified => "unidentified",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The result of identifying an object.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Classification {
+    pub kind: ObjectKind,
+
+    /// Number of generations before the object returns to its original
+    /// shape (one for still lifes, zero if the object was not identified).
+    pub period: usize,
+
+    /// The apgcode of the object (e.g. `xs4_33` for the block), which is the
+    /// same regardless of the phase, position or orientation of the object.
+    pub apgcode: String,
+}
+
+/// Identify an object by simulating it in isolation until it returns to its
+/// original shape.
+///
+/// ## Arguments
+///
+/// * `cells`: The live cells of the object.
+pub fn classify(cells: &CellSet) -> Classification {
+    let initial_shape = life::normalize(cells);
+    let initial_position = life::top_left(cells);
+    let mut phases = vec![initial_shape.clone()];
+    let mut generation = cells.clone();
+
+    for period in 1..=MAX_PERIOD {
+        generation = life::step(&generation);
+        let shape = life::normalize(&generation);
+
+        if shape == initial_shape {
+            let (kind, prefix) = if period == 1 {
+                (ObjectKind::StillLife, format!("xs{}", cells.len()))
+            } else if life::top_left(&generation) == initial_position {
+                (ObjectKind::Oscillator, format!("xp{}", period))
+            } else {
+                (ObjectKind::Spaceship, format!("xq{}", period))
+            };
+
+            let code = phases.iter().flat_map(orientations).map(|phase| encode_wechsler(&phase));
+            let code = code.min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+            return Classification {
+                kind,
+                period,
+                apgcode: format!("{}_{}", prefix, code.unwrap_or_default()),
+            };
+        }
+
+        phases.push(shape);
+    }
+
+    Classification { kind: ObjectKind::Unidentified, period: 0, apgcode: PATHOLOGICAL.to_string() }
+}
+
+/// All eight rotations and reflections of a shape.
+fn orientations(cells: &CellSet) -> Vec<CellSet> {
+    let transforms: [fn(i64, i64) -> Coordinate; 8] = [
+        |x, y| (x, y),
+        |x, y| (-x, y),
+        |x, y| (x, -y),
+        |x, y| (-x, -y),
+        |x, y| (y, x),
+        |x, y| (-y, x),
+        |x, y| (y, -x),
+        |x, y| (-y, -x),
+    ];
+
+    transforms
+        .iter()
+        .map(|transform| life::normalize(&cells.iter().map(|(x, y)| transform(*x, *y)).collect()))
+        .collect()
+}
+
+/// Encode a shape (whose top-left corner is at the origin) in the extended
+/// Wechsler format.
+///
+/// The shape is split into strips five cells high. Each column of a strip is
+/// one character, trailing empty columns are removed, and runs of empty
+/// columns are shortened (`w` is two empty columns, `x` is three, and `y`
+/// followed by a character is four or more). Strips are separated by `z`.
+fn encode_wechsler(cells: &CellSet) -> String {
+    let width = cells.iter().map(|(x, _)| x + 1).max().unwrap_or(0);
+    let height = cells.iter().map(|(_, y)| y + 1).max().unwrap_or(0);
+
+    let strips = (0..height).step_by(STRIP_HEIGHT as usize).map(|strip_top| {
+        let columns = (0..width)
+            .map(|x| {
+                (0..STRIP_HEIGHT)
+                    .filter(|row| cells.contains(&(x, strip_top + row)))
+                    .map(|row| 1 << row)
+                    .sum::<usize>()
+            })
+            .collect::<Vec<_>>();
+        encode_strip(&columns)
+    });
+
+    strips.collect::<Vec<_>>().join("z")
+}
+
+fn encode_strip(columns: &[usize]) -> String {
+    let used_columns = columns.iter().rposition(|column| *column != 0).map_or(0, |i| i + 1);
+    let mut result = String::new();
+    let mut empty_columns = 0;
+
+    for column in &columns[..used_columns] {
+        if *column == 0 {
+            empty_columns += 1;
+            continue;
+        }
+
+        push_empty_columns(&mut result, empty_columns);
+        empty_columns = 0;
+        result.push(WECHSLER_CHARS[*column] as char);
+    }
+
+    result
+}
+
+fn push_empty_columns(result: &mut String, mut count: usize) {
+    while count > 0 {
+        match count {
+            1 => result.push('0'),
+            2 => result.push('w'),
+            3 => result.push('x'),
+            _ => {
+                let run = count.min(4 + RUN_LENGTH_CHARS.len() - 1);
+                result.push('y');
+                result.push(RUN_LENGTH_CHARS[run - 4] as char);
+                count -= run;
+                continue;
+            }
+        }
+        return;
+    }
+}
+
+#[cfg(test)]
+mod apgcode_tests {
+    use super::*;
+    use crate::game::library;
+
+    fn library_cells(name: &str) -> CellSet {
+        let pattern = library::find(name).unwrap().pattern();
+        pattern.cells().iter().map(|cell| (cell.x as i64, cell.y as i64)).collect()
+    }
+
+    fn apgcode(name: &str) -> String {
+        classify(&library_cells(name)).apgcode
+    }
+
+    #[test]
+    fn still_lifes_are_identified() {
+        assert_eq!("xs4_33", apgcode("block"));
+        assert_eq!("xs6_696", apgcode("beehive"));
+        assert_eq!("xs7_2596", apgcode("loaf"));
+        assert_eq!("xs5_253", apgcode("boat"));
+        assert_eq!("xs4_252", apgcode("tub"));
+        assert_eq!(ObjectKind::StillLife, classify(&library_cells("pond")).kind);
+    }
+
+    #[test]
+    fn oscillators_are_identified() {
+        assert_eq!("xp2_7", apgcode("blinker"));
+        assert_eq!("xp2_7e", apgcode("toad"));
+        assert_eq!("xp2_318c", apgcode("beacon"));
+
+        let pulsar = classify(&library_cells("pulsar"));
+        assert_eq!((ObjectKind::Oscillator, 3), (pulsar.kind, pulsar.period));
+    }
+
+    #[test]
+    fn spaceships_are_identified() {
+        assert_eq!("xq4_153", apgcode("glider"));
+        assert_eq!("xq4_6frc", apgcode("lwss"));
+
+        let hwss = classify(&library_cells("hwss"));
+        assert_eq!((ObjectKind::Spaceship, 4), (hwss.kind, hwss.period));
+    }
+
+    #[test]
+    fn codes_do_not_depend_on_phase_or_orientation() {
+        let glider = library_cells("glider");
+        let mut moved = glider.iter().map(|(x, y)| (-y + 50, x - 20)).collect::<CellSet>();
+        for _ in 0..3 {
+            moved = life::step(&moved);
+        }
+
+        assert_eq!(classify(&glider), classify(&moved));
+    }
+
+    #[test]
+    fn unstable_objects_are_pathological() {
+        let r_pentomino = classify(&library_cells("r-pentomino"));
+
+        assert_eq!(ObjectKind::Unidentified, r_pentomino.kind);
+        assert_eq!(PATHOLOGICAL, r_pentomino.apgcode);
+    }
+
+    #[test]
+    fn empty_columns_are_compressed() {
+        assert_eq!("101", encode_strip(&[1, 0, 1]));
+        assert_eq!("1w1", encode_strip(&[1, 0, 0, 1]));
+        assert_eq!("1x1", encode_strip(&[1, 0, 0, 0, 1]));
+        assert_eq!("1y01", encode_strip(&[1, 0, 0, 0, 0, 1]));
+        assert_eq!("1yz01", encode_strip(&[&[1][..], &[0; 40], &[1]].concat()));
+        assert_eq!("1", encode_strip(&[1, 0, 0]));
+    }
+
+    #[test]
+    fn tall_objects_use_several_strips() {
+        // A vertical line of seven cells.
+        let line = (0..7).map(|y| (0, y)).collect::<CellSet>();
+        assert_eq!("vz3", encode_wechsler(&line));
+    }
+}
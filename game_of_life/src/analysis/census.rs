@@ -0,0 +1,207 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use crate::{
+    analysis::{
+        apgcode::{self, Classification, ObjectKind},
+        components,
+        life::CellSet,
+    },
+    game::library::{self, Category},
+};
+
+/// A count of each kind of object on a game board, identified by apgcode.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Census {
+    /// The kind and number of objects for each apgcode.
+    objects: BTreeMap<String, (ObjectKind, usize)>,
+}
+
+impl Census {
+    /// Take a census of a set of live cells. Each object is identified in
+    /// isolation, so the census is most meaningful once a soup has stabilised.
+    ///
+    /// ## Arguments
+    ///
+    /// * `cells`: The live cells to take a census of.
+    pub fn from_cells(cells: &CellSet) -> Census {
+        let mut census = Census::default();
+        for component in components::find_components(cells) {
+            census.add_object(&apgcode::classify(&component));
+        }
+
+        census
+    }
+
+    /// Count an object that has been identified.
+    pub fn add_object(&mut self, classification: &Classification) {
+        self.add_objects(&classification.apgcode, classification.kind, 1);
+    }
+
+    /// Add the counts from another census to this one.
+    pub fn merge(&mut self, other: &Census) {
+        for (apgcode, (kind, count)) in &other.objects {
+            self.add_objects(apgcode, *kind, *count);
+        }
+    }
+
+    fn add_objects(&mut self, apgcode: &str, kind: ObjectKind, count: usize) {
+        self.objects.entry(apgcode.to_string()).or_insert((kind, 0)).1 += count;
+    }
+
+    /// Get the total number of objects.
+    pub fn total(&self) -> usize {
+        self.objects.values().map(|(_, count)| count).sum()
+    }
+
+    /// Get each apgcode with its kind and count. The most common objects are
+    /// listed first.
+    pub fn entries(&self) -> Vec<(&str, ObjectKind, usize)> {
+        let mut entries = self
+            .objects
+            .iter()
+            .map(|(apgcode, (kind, count))| (apgcode.as_str(), *kind, *count))
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+        entries
+    }
+}
+
+impl fmt::Display for Census {
+    /// Format the census as a table, one object per line (e.g.
+    /// `xs4_33: 12 (block)`). Objects from the pattern library are named.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = library_names();
+        for (apgcode, kind, count) in self.entries() {
+            write!(f, "{}: {} ({}", apgcode, count, kind)?;
+            if let Some(name) = names.get(apgcode) {
+                write!(f, ", {}", name)?;
+            }
+            writeln!(f, ")")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Map the apgcodes of the stable patterns in the library to their names.
+fn library_names() -> HashMap<String, &'static str> {
+    library::LIBRARY
+        .iter()
+        .filter(|pattern| {
+            matches!(
+                pattern.category,
+                Category::StillLife | Category::Oscillator | Category::Spaceship
+            )
+        })
+        .map(|pattern| {
+            let cells =
+                pattern.pattern().cells().iter().map(|c| (c.x as i64, c.y as i64)).collect();
+            (apgcode::classify(&cells).apgcode, pattern.name)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod census_tests {
+    use super::*;
+    use crate::analysis::life::Coordinate;
+
+    /// Place a library pattern on a board at the given offset.
+    fn place(board: &mut CellSet, name: &str, offset: Coordinate) {
+        let pattern = library::find(name).unwrap().pattern();
+        board.extend(
+            pattern.cells().iter().map(|cell| (cell.x as i64 + offset.0, cell.y as i64 + offset.1)),
+        );
+    }
+
+    fn count(census: &Census, apgcode: &str) -> usize {
+        census.objects.get(apgcode).map_or(0, |(_, count)| *count)
+    }
+
+    fn create_ash() -> CellSet {
+        let mut board = CellSet::default();
+        place(&mut board, "block", (0, 0));
+        place(&mut board, "block", (10, 0));
+        place(&mut board, "block", (20, 0));
+        place(&mut board, "blinker", (0, 10));
+        place(&mut board, "glider", (10, 10));
+        place(&mut board, "beehive", (20, 10));
+        board
+    }
+
+    #[test]
+    fn objects_are_counted_by_apgcode() {
+        let census = Census::from_cells(&create_ash());
+
+        assert_eq!(3, count(&census, "xs4_33"));
+        assert_eq!(1, count(&census, "xp2_7"));
+        assert_eq!(1, count(&census, "xq4_153"));
+        assert_eq!(1, count(&census, "xs6_696"));
+        assert_eq!(0, count(&census, "xs7_2596"));
+        assert_eq!(6, census.total());
+    }
+
+    #[test]
+    fn most_common_objects_are_listed_first() {
+        let census = Census::from_cells(&create_ash());
+        let codes = census.entries().iter().map(|(code, _, _)| *code).collect::<Vec<_>>();
+
+        assert_eq!(vec!["xs4_33", "xp2_7", "xq4_153", "xs6_696"], codes);
+    }
+
+    #[test]
+    fn census_is_printed_as_a_table() {
+        let mut board = CellSet::default();
+        place(&mut board, "block", (0, 0));
+        place(&mut board, "block", (10, 0));
+        place(&mut board, "blinker", (0, 10));
+
+        let expected = "xs4_33: 2 (still life, block)\nxp2_7: 1 (oscillator, blinker)\n";
+        assert_eq!(expected, Census::from_cells(&board).to_string());
+    }
+
+    #[test]
+    fn censuses_are_merged() {
+        let mut census = Census::from_cells(&create_ash());
+        let mut board = CellSet::default();
+        place(&mut board, "block", (0, 0));
+        place(&mut board, "loaf", (10, 0));
+        census.merge(&Census::from_cells(&board));
+
+        assert_eq!(4, count(&census, "xs4_33"));
+        assert_eq!(1, count(&census, "xs7_2596"));
+        assert_eq!(8, census.total());
+    }
+
+    #[test]
+    fn empty_boards_have_an_empty_census() {
+        let census = Census::from_cells(&CellSet::default());
+
+        assert_eq!(0, census.total());
+        assert_eq!("", census.to_string());
+    }
+}
@@ -0,0 +1,131 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::analysis::life::{self, CellSet, Coordinate};
+
+/// Number of generations that are considered when grouping cells into
+/// objects. Oscillators such as the beacon are only connected in some of their
+/// phases, so cells that touch in any of these generations are grouped.
+const GROUPING_GENERATIONS: usize = 4;
+
+/// Separate a set of live cells into objects.
+///
+/// Two cells belong to the same object if they are neighbours (including
+/// diagonally) in the current generation or any of the next few generations.
+/// Objects that sit very close to each other (e.g. a pseudo still life such as
+/// two adjacent blocks) are reported as a single object.
+///
+/// ## Arguments
+///
+/// * `cells`: The live cells to separate.
+///
+/// ## Returns
+///
+/// The live cells of each object in the current generation. Objects are
+/// ordered by their top-left-most cell (in row-major order).
+pub fn find_components(cells: &CellSet) -> Vec<CellSet> {
+    // Every cell that is alive in any of the generations considered. Cells
+    // are connected through this envelope.
+    let mut envelope = cells.clone();
+    let mut generation = cells.clone();
+    for _ in 1..GROUPING_GENERATIONS {
+        generation = life::step(&generation);
+        envelope.extend(generation.iter().copied());
+    }
+
+    let mut unvisited = envelope;
+    let mut components = vec![];
+    let mut seeds = cells.iter().copied().collect::<Vec<_>>();
+    seeds.sort_by_key(|(x, y)| (*y, *x));
+
+    for seed in seeds {
+        if !unvisited.remove(&seed) {
+            continue;
+        }
+
+        let group = flood_fill(seed, &mut unvisited);
+        components.push(group.intersection(cells).copied().collect());
+    }
+
+    components
+}
+
+/// Collect every cell connected to `start`, removing them from `unvisited`.
+fn flood_fill(start: Coordinate, unvisited: &mut CellSet) -> CellSet {
+    let mut group = CellSet::from_iter([start]);
+    let mut stack = vec![start];
+
+    while let Some((x, y)) = stack.pop() {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let neighbour = (x + dx, y + dy);
+                if unvisited.remove(&neighbour) {
+                    group.insert(neighbour);
+                    stack.push(neighbour);
+                }
+            }
+        }
+    }
+
+    group
+}
+
+#[cfg(test)]
+mod components_tests {
+    use super::*;
+
+    fn cells(list: &[Coordinate]) -> CellSet {
+        list.iter().copied().collect()
+    }
+
+    fn offset(cells: &CellSet, dx: i64, dy: i64) -> CellSet {
+        cells.iter().map(|(x, y)| (x + dx, y + dy)).collect()
+    }
+
+    #[test]
+    fn separate_objects_are_separate_components() {
+        let block = cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let blinker = cells(&[(0, 1), (1, 1), (2, 1)]);
+        let mut board = block.clone();
+        board.extend(offset(&blinker, 10, 10));
+
+        assert_eq!(vec![block, offset(&blinker, 10, 10)], find_components(&board));
+    }
+
+    #[test]
+    fn diagonal_neighbours_are_connected() {
+        let boat = cells(&[(0, 0), (1, 0), (0, 1), (2, 1), (1, 2)]);
+        assert_eq!(vec![boat.clone()], find_components(&boat));
+    }
+
+    #[test]
+    fn oscillators_are_a_single_component() {
+        // The beacon is two blocks which only touch in one of their phases.
+        let beacon = cells(&[(0, 0), (1, 0), (0, 1), (3, 2), (2, 3), (3, 3)]);
+        assert_eq!(1, find_components(&beacon).len());
+    }
+
+    #[test]
+    fn empty_boards_have_no_components() {
+        assert!(find_components(&CellSet::default()).is_empty());
+    }
+}
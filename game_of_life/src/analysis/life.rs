@@ -0,0 +1,229 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasherDefault, Hasher},
+};
+
+use xy_utils::{Dimensions, Point};
+
+/// Coordinates of a cell on an unbounded plane.
+pub type Coordinate = (i64, i64);
+
+/// A set of live cells on an unbounded plane.
+pub type CellSet = HashSet<Coordinate, BuildCoordinateHasher>;
+
+/// Builds hashers for `CellSet`s.
+pub type BuildCoordinateHasher = BuildHasherDefault<CoordinateHasher>;
+
+/// A fast hasher for coordinates (based on the "Fx" hash used by the Rust
+/// compiler). The standard library's default hasher protects against
+/// denial-of-service attacks, which are not a concern here, and is several
+/// times slower. Simulating a methuselah spends most of its time hashing.
+#[derive(Default)]
+pub struct CoordinateHasher {
+    hash: u64,
+}
+
+impl CoordinateHasher {
+    fn add(&mut self, value: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ value).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+}
+
+impl Hasher for CoordinateHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.add(*byte as u64);
+        }
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.add(value as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Convert cells from a game board into a set of live cells.
+///
+/// ## Arguments
+///
+/// * `cells`: The live cells on the game board.
+pub fn from_points(cells: &[Point]) -> CellSet {
+    cells.iter().map(|cell| (cell.x as i64, cell.y as i64)).collect()
+}
+
+/// Convert cells from a game board whose edges wrap around (a torus) into a
+/// set of live cells. The board is rotated so that its edges fall on an empty
+/// row and an empty column, so that objects that straddle the edges are kept
+/// in one piece.
+///
+/// ## Arguments
+///
+/// * `cells`: The live cells on the game board.
+/// * `size`: The size of the game board.
+///
+/// ## Returns
+///
+/// The live cells, or `None` if every row or every column of the board has a
+/// live cell, as objects could then wrap all of the way around the board.
+pub fn from_torus_points(cells: &[Point], size: Dimensions) -> Option<CellSet> {
+    if cells.is_empty() {
+        return Some(CellSet::default());
+    }
+
+    // The first empty column (or row) becomes the last one, so that nothing
+    // is adjacent across the new edges.
+    let first_empty = |length: usize, coordinate: fn(&Point) -> usize| {
+        let mut is_occupied = vec![false; length];
+        for cell in cells {
+            is_occupied[coordinate(cell)] = true;
+        }
+        is_occupied.iter().position(|occupied| !occupied)
+    };
+    let empty_column = first_empty(size.width, |cell| cell.x)?;
+    let empty_row = first_empty(size.height, |cell| cell.y)?;
+
+    let unwrap = |coordinate: usize, empty: usize, length: usize| {
+        ((coordinate + length - empty - 1) % length) as i64
+    };
+    Some(
+        cells
+            .iter()
+            .map(|cell| {
+                (unwrap(cell.x, empty_column, size.width), unwrap(cell.y, empty_row, size.height))
+            })
+            .collect(),
+    )
+}
+
+/// Calculate the next generation of a set of live cells, using the rules of
+/// Conway's Game of Life.
+///
+/// ## Arguments
+///
+/// * `cells`: The live cells of the current generation.
+pub fn step(cells: &CellSet) -> CellSet {
+    let mut neighbour_counts =
+        HashMap::<Coordinate, u8, BuildCoordinateHasher>::with_capacity_and_hasher(
+            cells.len() * 4,
+            BuildCoordinateHasher::default(),
+        );
+    for (x, y) in cells {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if (dx, dy) != (0, 0) {
+                    *neighbour_counts.entry((x + dx, y + dy)).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    neighbour_counts
+        .into_iter()
+        .filter(|(cell, count)| (*count == 3) || ((*count == 2) && cells.contains(cell)))
+        .map(|(cell, _)| cell)
+        .collect()
+}
+
+/// Get the top-left corner of the smallest rectangle containing every cell.
+/// Returns `None` if there are no cells.
+pub fn top_left(cells: &CellSet) -> Option<Coordinate> {
+    let min_x = cells.iter().map(|(x, _)| *x).min()?;
+    let min_y = cells.iter().map(|(_, y)| *y).min()?;
+    Some((min_x, min_y))
+}
+
+/// Move a set of cells so that its top-left corner is at (0, 0).
+pub fn normalize(cells: &CellSet) -> CellSet {
+    match top_left(cells) {
+        Some((min_x, min_y)) => cells.iter().map(|(x, y)| (x - min_x, y - min_y)).collect(),
+        None => CellSet::default(),
+    }
+}
+
+#[cfg(test)]
+mod life_tests {
+    use super::*;
+
+    fn cells(list: &[Coordinate]) -> CellSet {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn blinkers_oscillate() {
+        let horizontal = cells(&[(-1, 0), (0, 0), (1, 0)]);
+        let vertical = cells(&[(0, -1), (0, 0), (0, 1)]);
+
+        assert_eq!(vertical, step(&horizontal));
+        assert_eq!(horizontal, step(&vertical));
+    }
+
+    #[test]
+    fn cells_are_not_bounded() {
+        // A glider travelling up and to the left, past the origin.
+        let mut glider = cells(&[(0, 0), (1, 0), (2, 0), (0, 1), (1, 2)]);
+        for _ in 0..8 {
+            glider = step(&glider);
+        }
+
+        assert_eq!(Some((-2, -2)), top_left(&glider));
+        assert_eq!(cells(&[(0, 0), (1, 0), (2, 0), (0, 1), (1, 2)]), normalize(&glider));
+    }
+
+    #[test]
+    fn board_cells_are_converted() {
+        let points = [Point { x: 3, y: 4 }, Point { x: 0, y: 1 }];
+        assert_eq!(cells(&[(3, 4), (0, 1)]), from_points(&points));
+    }
+
+    #[test]
+    fn torus_cells_are_unwrapped_across_the_edges() {
+        // A block split across all four corners of the board.
+        let size = Dimensions { width: 5, height: 4 };
+        let points = [
+            Point { x: 0, y: 0 },
+            Point { x: 4, y: 0 },
+            Point { x: 0, y: 3 },
+            Point { x: 4, y: 3 },
+        ];
+        let unwrapped = from_torus_points(&points, size).unwrap();
+        assert_eq!(cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]), normalize(&unwrapped));
+
+        // Cells that don't touch the edges are only moved.
+        let points = [Point { x: 1, y: 1 }, Point { x: 2, y: 1 }];
+        let unwrapped = from_torus_points(&points, size).unwrap();
+        assert_eq!(cells(&[(0, 0), (1, 0)]), normalize(&unwrapped));
+
+        assert_eq!(Some(CellSet::default()), from_torus_points(&[], size));
+    }
+
+    #[test]
+    fn torus_cells_in_every_column_are_not_unwrapped() {
+        let size = Dimensions { width: 3, height: 3 };
+        let points = [Point { x: 0, y: 1 }, Point { x: 1, y: 1 }, Point { x: 2, y: 1 }];
+        assert_eq!(None, from_torus_points(&points, size));
+    }
+}
@@ -0,0 +1,147 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use std::fmt;
+
+use crate::analysis::{life::CellSet, stabilisation::run_until_stable};
+
+/// How long a pattern lives before it stabilises, and how large it grows.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Lifespan {
+    /// The generation that the pattern stabilised at, or `None` if it did
+    /// not stabilise within the generation limit.
+    pub stabilised_at: Option<usize>,
+
+    pub initial_population: usize,
+
+    /// The largest population, and the first generation that reached it.
+    pub peak_population: usize,
+    pub peak_generation: usize,
+
+    /// The population at the generation that the pattern stabilised (or at
+    /// the generation limit). Any spaceships that escaped are included.
+    pub final_population: usize,
+}
+
+impl fmt::Display for Lifespan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.stabilised_at {
+            Some(generation) => writeln!(f, "Stabilises at generation: {}", generation)?,
+            None => writeln!(f, "Stabilises at generation: unknown (did not stabilise)")?,
+        }
+        writeln!(f, "Initial population: {}", self.initial_population)?;
+        writeln!(
+            f,
+            "Peak population: {} (generation {})",
+            self.peak_population, self.peak_generation
+        )?;
+        writeln!(f, "Final population: {}", self.final_population)
+    }
+}
+
+/// Measure the lifespan of a pattern on an unbounded plane.
+///
+/// ## Arguments
+///
+/// * `cells`: The live cells of the pattern.
+/// * `max_generations`: The number of generations to run before giving up.
+pub fn measure(cells: &CellSet, max_generations: usize) -> Lifespan {
+    let stabilised = run_until_stable(cells, max_generations);
+    let populations = &stabilised.populations;
+    let last_generation = stabilised.generation.unwrap_or(populations.len() - 1);
+
+    // Find the first generation with the peak population.
+    let (peak_generation, peak_population) =
+        populations[..=last_generation].iter().enumerate().fold(
+            (0, 0),
+            |peak, (generation, population)| {
+                if *population > peak.1 {
+                    (generation, *population)
+                } else {
+                    peak
+                }
+            },
+        );
+
+    Lifespan {
+        stabilised_at: stabilised.generation,
+        initial_population: populations[0],
+        peak_population,
+        peak_generation,
+        final_population: populations[last_generation],
+    }
+}
+
+#[cfg(test)]
+mod lifespan_tests {
+    use super::*;
+    use crate::game::library;
+
+    fn measure_library_pattern(name: &str) -> Lifespan {
+        let pattern = library::find(name).unwrap().pattern();
+        let cells = pattern.cells().iter().map(|cell| (cell.x as i64, cell.y as i64)).collect();
+        measure(&cells, 10000)
+    }
+
+    #[test]
+    fn r_pentomino() {
+        let lifespan = measure_library_pattern("r-pentomino");
+
+        assert_eq!(Some(1103), lifespan.stabilised_at);
+        assert_eq!(5, lifespan.initial_population);
+        assert_eq!((319, 821), (lifespan.peak_population, lifespan.peak_generation));
+        assert_eq!(116, lifespan.final_population);
+    }
+
+    #[test]
+    fn acorn() {
+        let lifespan = measure_library_pattern("acorn");
+
+        assert_eq!(Some(5206), lifespan.stabilised_at);
+        assert_eq!(1057, lifespan.peak_population);
+        assert_eq!(633, lifespan.final_population);
+    }
+
+    #[test]
+    fn heptominoes() {
+        assert_eq!(Some(148), measure_library_pattern("b-heptomino").stabilised_at);
+        assert_eq!(Some(173), measure_library_pattern("pi-heptomino").stabilised_at);
+    }
+
+    #[test]
+    fn diehard() {
+        let lifespan = measure_library_pattern("diehard");
+
+        assert_eq!(Some(130), lifespan.stabilised_at);
+        assert_eq!(0, lifespan.final_population);
+    }
+
+    #[test]
+    fn lifespan_is_printed() {
+        let expected = concat!(
+            "Stabilises at generation: 0\n",
+            "Initial population: 4\n",
+            "Peak population: 4 (generation 0)\n",
+            "Final population: 4\n"
+        );
+        assert_eq!(expected, measure_library_pattern("block").to_string());
+    }
+}
@@ -0,0 +1,254 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use std::{collections::HashMap, fmt};
+
+use crate::analysis::life::{self, CellSet, Coordinate};
+
+/// How a pattern behaves once it has been run for long enough to repeat.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Behaviour {
+    /// Every cell dies.
+    DiesOut,
+    StillLife,
+    Oscillator {
+        period: usize,
+    },
+
+    /// The pattern repeats its shape in a different position. The
+    /// displacement is the distance that the pattern moves in each period.
+    Spaceship {
+        period: usize,
+        displacement: Coordinate,
+    },
+
+    /// The pattern did not repeat within the generation limit.
+    Unknown,
+}
+
+impl fmt::Display for Behaviour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Behaviour::DiesOut => write!(f, "dies out"),
+            Behaviour::StillLife => write!(f, "still life"),
+            Behaviour::Oscillator { period } => write!(f, "oscillator (period {})", period),
+            Behaviour::Spaceship { period, displacement } => {
+                write!(f, "spaceship (period {}, {})", period, format_speed(*displacement, *period))
+            }
+            Behaviour::Unknown => write!(f, "unknown (did not repeat)"),
+        }
+    }
+}
+
+/// The result of analysing a pattern.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PatternAnalysis {
+    pub behaviour: Behaviour,
+
+    /// Number of generations before the pattern first reached the repeating
+    /// state (or died out). For patterns that did not repeat, this is the
+    /// number of generations that were simulated.
+    pub generations: usize,
+
+    /// Smallest and largest population over the repeating cycle (or over every
+    /// generation simulated, if the pattern did not repeat).
+    pub min_population: usize,
+    pub max_population: usize,
+
+    /// Largest width and height of the pattern over the same generations as
+    /// the population.
+    pub bounding_box: Coordinate,
+}
+
+impl fmt::Display for PatternAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Behaviour: {}", self.behaviour)?;
+        match self.behaviour {
+            Behaviour::Unknown => writeln!(f, "Generations simulated: {}", self.generations)?,
+            Behaviour::DiesOut => writeln!(f, "Dies after: {} generations", self.generations)?,
+            _ => writeln!(f, "Settles after: {} generations", self.generations)?,
+        }
+        writeln!(f, "Population: {} to {}", self.min_population, self.max_population)?;
+        writeln!(f, "Bounding box: {}x{}", self.bounding_box.0, self.bounding_box.1)
+    }
+}
+
+/// Run a pattern until it repeats (a previous shape appears again, possibly in
+/// a different position) and report how it behaves.
+///
+/// ## Arguments
+///
+/// * `cells`: The live cells of the pattern.
+/// * `max_generations`: The number of generations to run before giving up.
+pub fn analyze(cells: &CellSet, max_generations: usize) -> PatternAnalysis {
+    // The shape of each generation (as sorted cells relative to the top-left),
+    // and when and where it was seen.
+    let mut seen = HashMap::<Vec<Coordinate>, (usize, Coordinate)>::new();
+    let mut history = vec![];
+    let mut generation = cells.clone();
+
+    for index in 0..=max_generations {
+        let Some(position) = life::top_left(&generation) else {
+            return summarize(Behaviour::DiesOut, index, &history);
+        };
+
+        let mut shape = life::normalize(&generation).into_iter().collect::<Vec<_>>();
+        shape.sort_unstable();
+        if let Some((first_seen, first_position)) = seen.get(&shape) {
+            let period = index - first_seen;
+            let displacement = (position.0 - first_position.0, position.1 - first_position.1);
+            let behaviour = match (period, displacement) {
+                (1, _) => Behaviour::StillLife,
+                (_, (0, 0)) => Behaviour::Oscillator { period },
+                _ => Behaviour::Spaceship { period, displacement },
+            };
+            return summarize(behaviour, *first_seen, &history[*first_seen..]);
+        }
+
+        history.push(generation.clone());
+        seen.insert(shape, (index, position));
+        generation = life::step(&generation);
+    }
+
+    summarize(Behaviour::Unknown, max_generations, &history)
+}
+
+fn summarize(behaviour: Behaviour, generations: usize, cycle: &[CellSet]) -> PatternAnalysis {
+    let bounding_boxes = cycle.iter().map(|cells| {
+        let (min_x, min_y) = life::top_left(cells).unwrap_or_default();
+        let max_x = cells.iter().map(|(x, _)| *x).max().unwrap_or(min_x - 1);
+        let max_y = cells.iter().map(|(_, y)| *y).max().unwrap_or(min_y - 1);
+        (max_x - min_x + 1, max_y - min_y + 1)
+    });
+
+    PatternAnalysis {
+        behaviour,
+        generations,
+        min_population: cycle.iter().map(|cells| cells.len()).min().unwrap_or(0),
+        max_population: cycle.iter().map(|cells| cells.len()).max().unwrap_or(0),
+        bounding_box: bounding_boxes.fold((0, 0), |(w, h), (x, y)| (w.max(x), h.max(y))),
+    }
+}
+
+/// Describe the speed of a spaceship in the usual notation, e.g. `c/4
+/// diagonal` for the glider or `c/2 orthogonal` for the LWSS.
+fn format_speed(displacement: Coordinate, period: usize) -> String {
+    let (dx, dy) = (displacement.0.unsigned_abs() as usize, displacement.1.unsigned_abs() as usize);
+    let distance = dx.max(dy);
+    let divisor = greatest_common_divisor(distance, period);
+    let (distance, period) = (distance / divisor, period / divisor);
+    let speed =
+        if distance == 1 { format!("c/{}", period) } else { format!("{}c/{}", distance, period) };
+
+    if (dx == 0) || (dy == 0) {
+        format!("{} orthogonal", speed)
+    } else if dx == dy {
+        format!("{} diagonal", speed)
+    } else {
+        format!("({}, {})c/{} oblique", dx, dy, period * divisor)
+    }
+}
+
+fn greatest_common_divisor(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        greatest_common_divisor(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod periodicity_tests {
+    use super::*;
+    use crate::game::library;
+
+    fn analyze_library_pattern(name: &str, max_generations: usize) -> PatternAnalysis {
+        let pattern = library::find(name).unwrap().pattern();
+        let cells = pattern.cells().iter().map(|cell| (cell.x as i64, cell.y as i64)).collect();
+        analyze(&cells, max_generations)
+    }
+
+    #[test]
+    fn still_lifes_are_detected() {
+        let block = analyze_library_pattern("block", 10);
+
+        assert_eq!(Behaviour::StillLife, block.behaviour);
+        assert_eq!((4, 4), (block.min_population, block.max_population));
+        assert_eq!((2, 2), block.bounding_box);
+    }
+
+    #[test]
+    fn oscillator_periods_are_detected() {
+        let pentadecathlon = analyze_library_pattern("pentadecathlon", 100);
+        assert_eq!(Behaviour::Oscillator { period: 15 }, pentadecathlon.behaviour);
+        assert_eq!(0, pentadecathlon.generations);
+
+        let blinker = analyze_library_pattern("blinker", 100);
+        assert_eq!(Behaviour::Oscillator { period: 2 }, blinker.behaviour);
+        assert_eq!((3, 3), blinker.bounding_box);
+    }
+
+    #[test]
+    fn spaceship_speeds_are_detected() {
+        let glider = analyze_library_pattern("glider", 100);
+        assert_eq!(Behaviour::Spaceship { period: 4, displacement: (1, 1) }, glider.behaviour);
+        assert_eq!("spaceship (period 4, c/4 diagonal)", glider.behaviour.to_string());
+
+        let lwss = analyze_library_pattern("lwss", 100);
+        assert_eq!("spaceship (period 4, c/2 orthogonal)", lwss.behaviour.to_string());
+    }
+
+    #[test]
+    fn patterns_that_die_are_detected() {
+        let diehard = analyze_library_pattern("diehard", 1000);
+
+        assert_eq!(Behaviour::DiesOut, diehard.behaviour);
+        assert_eq!(130, diehard.generations);
+    }
+
+    #[test]
+    fn patterns_that_do_not_repeat_are_unknown() {
+        let r_pentomino = analyze_library_pattern("r-pentomino", 100);
+
+        assert_eq!(Behaviour::Unknown, r_pentomino.behaviour);
+        assert_eq!(100, r_pentomino.generations);
+        assert_eq!(5, r_pentomino.min_population);
+    }
+
+    #[test]
+    fn speeds_are_formatted() {
+        assert_eq!("c/4 diagonal", format_speed((1, -1), 4));
+        assert_eq!("c/2 orthogonal", format_speed((0, 2), 4));
+        assert_eq!("2c/5 orthogonal", format_speed((-2, 0), 5));
+        assert_eq!("(2, 1)c/6 oblique", format_speed((2, 1), 6));
+    }
+
+    #[test]
+    fn analysis_is_printed() {
+        let expected = concat!(
+            "Behaviour: oscillator (period 2)\n",
+            "Settles after: 0 generations\n",
+            "Population: 3 to 3\n",
+            "Bounding box: 3x3\n"
+        );
+        assert_eq!(expected, analyze_library_pattern("blinker", 10).to_string());
+    }
+}
@@ -0,0 +1,154 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use crate::analysis::life::{self, CellSet};
+
+/// Longest cycle of populations that is recognised as stable. Mixtures of
+/// common oscillators (periods 2, 3, 4, 5 and 15) all repeat within this.
+const MAX_POPULATION_PERIOD: usize = 60;
+
+/// Number of generations that the population must follow a cycle before the
+/// pattern is considered stable.
+const STABLE_WINDOW: usize = 2 * MAX_POPULATION_PERIOD;
+
+/// A pattern that has been run until it stabilised.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Stabilised {
+    /// The live cells once the pattern was found to be stable.
+    pub cells: CellSet,
+
+    /// The first generation of the stable population cycle, or `None` if the
+    /// pattern did not stabilise within the generation limit.
+    pub generation: Option<usize>,
+
+    /// Number of generations that were simulated.
+    pub generations_simulated: usize,
+
+    /// Population of every generation that was simulated, starting with the
+    /// initial pattern.
+    pub populations: Vec<usize>,
+}
+
+/// Run a pattern until it is stable.
+///
+/// A pattern is considered stable once its population has followed a short
+/// cycle for a while. Unlike waiting for the whole pattern to repeat, this
+/// copes with spaceships (e.g. gliders) escaping from the pattern, since a
+/// spaceship's population does not change as it moves.
+///
+/// ## Arguments
+///
+/// * `cells`: The live cells of the pattern.
+/// * `max_generations`: The number of generations to run before giving up.
+pub fn run_until_stable(cells: &CellSet, max_generations: usize) -> Stabilised {
+    let mut populations = vec![cells.len()];
+    let mut generation = cells.clone();
+
+    for index in 1..=max_generations {
+        generation = life::step(&generation);
+        populations.push(generation.len());
+
+        if let Some(start) = find_stable_start(&populations) {
+            return Stabilised {
+                cells: generation,
+                generation: Some(start),
+                generations_simulated: index,
+                populations,
+            };
+        }
+    }
+
+    Stabilised {
+        cells: generation,
+        generation: None,
+        generations_simulated: max_generations,
+        populations,
+    }
+}
+
+/// Check whether the end of a population history follows a cycle, and if so
+/// return the first generation of the cycle.
+fn find_stable_start(populations: &[usize]) -> Option<usize> {
+    let last = populations.len() - 1;
+    if last < STABLE_WINDOW + MAX_POPULATION_PERIOD {
+        return None;
+    }
+
+    let period = (1..=MAX_POPULATION_PERIOD).find(|period| {
+        ((last - STABLE_WINDOW + 1)..=last).all(|i| populations[i] == populations[i - period])
+    })?;
+
+    // Walk back to find where the cycle started.
+    let mut start = last - STABLE_WINDOW + 1 - period;
+    while (start > 0) && (populations[start - 1] == populations[start - 1 + period]) {
+        start -= 1;
+    }
+
+    Some(start)
+}
+
+#[cfg(test)]
+mod stabilisation_tests {
+    use super::*;
+    use crate::game::library;
+
+    fn library_cells(name: &str) -> CellSet {
+        let pattern = library::find(name).unwrap().pattern();
+        pattern.cells().iter().map(|cell| (cell.x as i64, cell.y as i64)).collect()
+    }
+
+    #[test]
+    fn stable_patterns_are_stable_immediately() {
+        let block = run_until_stable(&library_cells("block"), 1000);
+        assert_eq!(Some(0), block.generation);
+        assert_eq!(library_cells("block"), block.cells);
+
+        let glider = run_until_stable(&library_cells("glider"), 1000);
+        assert_eq!(Some(0), glider.generation);
+    }
+
+    #[test]
+    fn methuselahs_stabilise() {
+        let pi = run_until_stable(&library_cells("pi-heptomino"), 1000);
+        assert_eq!(Some(173), pi.generation);
+
+        let diehard = run_until_stable(&library_cells("diehard"), 1000);
+        assert_eq!(Some(130), diehard.generation);
+        assert!(diehard.cells.is_empty());
+    }
+
+    #[test]
+    fn patterns_that_do_not_stabilise_are_reported() {
+        let r_pentomino = run_until_stable(&library_cells("r-pentomino"), 500);
+
+        assert_eq!(None, r_pentomino.generation);
+        assert_eq!(500, r_pentomino.generations_simulated);
+    }
+
+    #[test]
+    fn population_cycles_are_found() {
+        let mut populations = vec![10, 9, 8];
+        populations.extend([5, 6, 7].repeat(100));
+
+        assert_eq!(Some(3), find_stable_start(&populations));
+        assert_eq!(None, find_stable_start(&populations[..20]));
+    }
+}
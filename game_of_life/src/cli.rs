@@ -20,17 +20,140 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use clap::Parser;
-use xy_utils::Dimensions;
+use std::str::FromStr;
 
-/// Command line arguments.
-#[derive(Parser)]
-#[command(author, version, about, long_about = None)]
-pub struct Args {
-    /// Frequency in which the game board is updated.
-    #[arg(short, long, value_name = "HZ", default_value_t = 4)]
-    pub update_frequency: u32,
+use clap::{Parser, Subcommand};
+use tui::ColorSupport;
+use xy_utils::{Dimensions, Point};
+
+use crate::{
+    game::{PatternFormat, Rule, Symmetry, Topology, Transform},
+    statistics::StatisticsFormat,
+};
+
+/// A pattern to place on the game board, given on the command line as
+/// `NAME[@X,Y][:TRANSFORM...]`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PatternPlacement {
+    /// Path to the pattern file, or the name of a pattern in the library.
+    pub name: String,
+
+    /// Position of the top-left corner of the pattern.
+    pub position: Point,
+
+    /// Transforms to apply to the pattern, in order.
+    pub transforms: Vec<Transform>,
+}
+
+impl FromStr for PatternPlacement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<PatternPlacement, String> {
+        let mut parts = s.split(':');
+        let file_and_position = parts.next().unwrap_or_default();
+        let transforms = parts.map(Transform::from_str).collect::<Result<Vec<_>, _>>()?;
+
+        let (name, position) = match file_and_position.rsplit_once('@') {
+            Some((name, position)) => (name, parse_position(position)?),
+            None => (file_and_position, Point { x: 0, y: 0 }),
+        };
+        if name.is_empty() {
+            return Err("No pattern given".to_string());
+        }
+
+        Ok(PatternPlacement { name: name.to_string(), position, transforms })
+    }
+}
+
+/// Parse a position of the format `X,Y`.
+fn parse_position(s: &str) -> Result<Point, String> {
+    let Some((x, y)) = s.split_once(',') else {
+        return Err(format!("Invalid position \"{}\" (expected X,Y)", s));
+    };
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<usize>()
+            .map_err(|error| format!("Invalid position \"{}\": {}", s, error))
+    };
+
+    Ok(Point { x: parse(x)?, y: parse(y)? })
+}
+
+/// Patterns to load, from files or the built-in library.
+#[derive(clap::Args)]
+pub struct PatternArgs {
+    /// Pattern file to place on the game board. Can be given several times.
+    /// The format of the file is guessed from its extension (see `convert`).
+    ///
+    /// Each file can be followed by the position of its top-left corner and a
+    /// list of transforms that are applied in order (rot90, rot180, rot270,
+    /// flipx, flipy), e.g. `gun.txt@10,20:rot90:flipx`.
+    #[arg(short = 'f', long = "game-board-file", value_name = "FILE[@X,Y][:TRANSFORM...]")]
+    pub pattern_placements: Vec<PatternPlacement>,
+
+    /// Pattern from the built-in library to place on the game board (see
+    /// `--list-patterns`). Can be given several times, and accepts the same
+    /// positions and transforms as `-f`, e.g. `acorn@10,10:rot90`.
+    #[arg(short = 'p', long = "pattern", value_name = "NAME[@X,Y][:TRANSFORM...]")]
+    pub library_placements: Vec<PatternPlacement>,
+}
+
+/// Options that change how the game is simulated.
+#[derive(clap::Args)]
+pub struct WorldArgs {
+    /// Rule of the cellular automaton in B/S notation (e.g. B36/S23 for
+    /// HighLife). Defaults to the rule of a macrocell file, or B3/S23.
+    #[arg(long, value_name = "RULE")]
+    pub rule: Option<Rule>,
+
+    /// How the edges of the game board behave. Defaults to a torus, except
+    /// for macrocell files, which are always played on an unbounded plane.
+    #[arg(long, value_enum, value_name = "TOPOLOGY")]
+    pub topology: Option<Topology>,
+}
+
+impl WorldArgs {
+    /// Get the rule that the user asked for, or Conway's Game of Life.
+    pub fn rule(&self) -> Rule {
+        self.rule.unwrap_or(Rule::CONWAY)
+    }
+
+    /// Get the topology that the user asked for, or a torus.
+    pub fn topology(&self) -> Topology {
+        self.topology.unwrap_or(Topology::Torus)
+    }
+}
+
+/// Options for the random soup that is used when no patterns are given.
+#[derive(clap::Args)]
+pub struct SoupArgs {
+    /// Seed for the random soup. A random seed is chosen (and displayed) if
+    /// this is not given, so that interesting runs can be reproduced.
+    #[arg(long, value_name = "N")]
+    pub seed: Option<u64>,
 
+    /// Probability of each cell in the random soup being alive.
+    #[arg(long, value_name = "0.0..1.0", default_value_t = 0.5, value_parser = parse_density)]
+    pub density: f64,
+
+    /// Size of the random soup, centred on the game board. Cells outside of
+    /// the soup start dead. Fills the whole board if not given (searches use
+    /// 16x16 soups).
+    #[arg(long, value_name = "WxH", default_value_t = Dimensions::create_empty())]
+    pub soup_size: Dimensions,
+
+    /// Symmetry of the random soup, using apgsearch names (e.g. C1, C2_4,
+    /// D2_+1, D4_x1, D8_1). The soup size is reduced if necessary so that
+    /// the soup can have the symmetry.
+    #[arg(long, value_name = "SYMMETRY", default_value_t = Symmetry::C1)]
+    pub symmetry: Symmetry,
+}
+
+/// Options for setting up a game board, shared by every command that plays
+/// the game.
+#[derive(clap::Args)]
+pub struct BoardArgs {
     /// Size of the grid that the game will be played in.
     #[arg(
         short = 's',
@@ -40,11 +163,359 @@ pub struct Args {
     )]
     pub grid_size: Dimensions,
 
-    /// File to read to seed the game board.
+    #[command(flatten)]
+    pub patterns: PatternArgs,
+
+    /// Centre the placed patterns (from files and the library) on the game
+    /// board. Pattern positions are kept relative to each other.
+    #[arg(long)]
+    pub center: bool,
+
+    #[command(flatten)]
+    pub soup: SoupArgs,
+
+    #[command(flatten)]
+    pub world: WorldArgs,
+
+    /// Write the statistics of each generation (population, births, deaths,
+    /// bounding box and hash) to the given file.
+    #[arg(long, value_name = "FILE")]
+    pub stats_out: Option<String>,
+
+    /// Format of the statistics file. Guessed from the file extension if not
+    /// given (`.json`, `.jsonl` and `.ndjson` files are JSON lines, everything
+    /// else is CSV).
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub stats_format: Option<StatisticsFormat>,
+
+    /// Print a census of the objects on the game board (identified by
+    /// apgcode) when the game ends.
+    #[arg(long)]
+    pub census: bool,
+}
+
+/// Subcommands.
+#[derive(Subcommand)]
+pub enum Command {
+    /// Play the game in the terminal (the default if no subcommand is given).
+    Run(RunArgs),
+
+    /// Run the game without the terminal user interface, as fast as possible.
+    Headless(HeadlessArgs),
+
+    /// Convert a pattern file to another format.
+    Convert(ConvertArgs),
+
+    /// Run a pattern until it repeats, and report whether it is a still life,
+    /// oscillator or spaceship.
+    Analyze(AnalyzeArgs),
+
+    /// Measure how long a pattern lives before it stabilises, and its peak
+    /// and final populations.
+    Measure(MeasureArgs),
+
+    /// Search random soups for rare objects (high period oscillators and
+    /// spaceships other than the glider).
+    Search(SearchArgs),
+
+    /// Render a game to an animated GIF without the terminal user interface.
+    Render(RenderArgs),
+}
+
+/// Arguments of the `run` subcommand.
+#[derive(clap::Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub board: BoardArgs,
+
+    /// Frequency in which the game board is updated.
+    #[arg(
+        short,
+        long,
+        value_name = "HZ",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub update_frequency: u32,
+
+    /// Number of generations to run before exiting. The game runs until
+    /// interrupted if this is not given.
+    #[arg(short = 'g', long, value_name = "N")]
+    pub generations: Option<usize>,
+
+    /// List the patterns in the built-in library and exit.
+    #[arg(long)]
+    pub list_patterns: bool,
+
+    /// Height of the population chart beneath the game board (zero to hide).
+    #[arg(long, value_name = "ROWS", default_value_t = 6)]
+    pub chart_height: usize,
+
+    /// Overlay the births and deaths of each generation on the population
+    /// chart.
+    #[arg(long)]
+    pub chart_overlays: bool,
+
+    /// Record the game as an animated GIF to the given file.
+    #[arg(long, value_name = "FILE")]
+    pub record_gif: Option<String>,
+
+    /// Size of each cell in the recorded GIF.
+    #[arg(long, value_name = "PIXELS", default_value_t = 4)]
+    pub gif_cell_size: usize,
+
+    /// Colors that the terminal can show: none, 16, 256 or truecolor. They are
+    /// detected from the environment (`NO_COLOR`, `COLORTERM` and `TERM`) if
+    /// this is not given.
+    #[arg(long, value_name = "COLORS")]
+    pub colors: Option<ColorSupport>,
+}
+
+/// Arguments of the `headless` subcommand.
+#[derive(clap::Args)]
+pub struct HeadlessArgs {
+    #[command(flatten)]
+    pub board: BoardArgs,
+
+    /// Number of generations to run.
+    #[arg(short = 'g', long, value_name = "N", default_value_t = 1000)]
+    pub generations: usize,
+}
+
+/// Arguments of the `convert` subcommand.
+#[derive(clap::Args)]
+pub struct ConvertArgs {
+    /// Pattern file to convert.
+    #[arg(value_name = "INPUT")]
+    pub input: String,
+
+    /// File to write the converted pattern to. The pattern is written to the
+    /// standard output if this is not given.
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<String>,
+
+    /// Format of the input file. Guessed from the file extension if not given
+    /// (`.cells`, `.rle`, `.lif`, `.life` and `.mc`, everything else is the
+    /// native format).
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub from: Option<PatternFormat>,
+
+    /// Format to convert the pattern to. Guessed from the extension of the
+    /// output file if not given, or RLE when writing to the standard output.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub to: Option<PatternFormat>,
+}
+
+/// Arguments of the `analyze` subcommand.
+#[derive(clap::Args)]
+pub struct AnalyzeArgs {
+    #[command(flatten)]
+    pub patterns: PatternArgs,
+
+    /// Maximum number of generations to run while waiting for the pattern to
+    /// repeat.
+    #[arg(short = 'g', long, value_name = "N", default_value_t = 1000)]
+    pub generations: usize,
+}
+
+/// Arguments of the `measure` subcommand.
+#[derive(clap::Args)]
+pub struct MeasureArgs {
+    #[command(flatten)]
+    pub patterns: PatternArgs,
+
+    /// Maximum number of generations to run while waiting for the pattern to
+    /// stabilise.
+    #[arg(short = 'g', long, value_name = "N", default_value_t = 100000)]
+    pub generations: usize,
+}
+
+/// Arguments of the `search` subcommand.
+#[derive(clap::Args)]
+pub struct SearchArgs {
+    /// Number of soups to search. Searches forever if not given.
+    #[arg(short = 'n', long, value_name = "N")]
+    pub soups: Option<u64>,
+
+    /// Number of worker threads. Defaults to the number of CPUs.
+    #[arg(short = 'j', long, value_name = "N")]
+    pub threads: Option<usize>,
+
+    // The seed is used for the first soup, and each soup after that uses the
+    // next seed.
+    #[command(flatten)]
+    pub soup: SoupArgs,
+
+    /// Number of generations to wait for each soup to stabilise.
+    #[arg(short = 'g', long, value_name = "N", default_value_t = 10000)]
+    pub generations: usize,
+
+    /// Oscillators with at least this period are logged as rare.
+    #[arg(long, value_name = "PERIOD", default_value_t = 3)]
+    pub min_period: usize,
+
+    /// File that rare objects are appended to, along with the options needed
+    /// to reproduce the soup that they came from.
+    #[arg(short = 'o', long, value_name = "FILE", default_value = "search_results.txt")]
+    pub results: String,
+}
+
+/// Arguments of the `render` subcommand.
+#[derive(clap::Args)]
+pub struct RenderArgs {
+    #[command(flatten)]
+    pub board: BoardArgs,
+
+    /// File to write the animated GIF to.
+    #[arg(short = 'o', long, value_name = "FILE")]
+    pub output: String,
+
+    /// Number of generations to render.
+    #[arg(short = 'g', long, value_name = "N", default_value_t = 100)]
+    pub generations: usize,
+
+    /// Number of generations shown per second of the animation.
     #[arg(
-        short = 'f',
+        short,
         long,
-        default_value_t = String::new()
+        value_name = "HZ",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u32).range(1..)
     )]
-    pub game_board_file: String,
+    pub update_frequency: u32,
+
+    /// Size of each cell in the GIF.
+    #[arg(long, value_name = "PIXELS", default_value_t = 4)]
+    pub gif_cell_size: usize,
+}
+
+/// Command line arguments.
+#[derive(Parser)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Options for playing the game when no subcommand is given.
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+impl Args {
+    /// Get the subcommand to run. Running without a subcommand is the same as
+    /// `run`.
+    pub fn into_command(self) -> Command {
+        self.command.unwrap_or(Command::Run(self.run))
+    }
+}
+
+/// Parse a soup density, which must be a probability between 0.0 and 1.0.
+fn parse_density(s: &str) -> Result<f64, String> {
+    let density = s.parse::<f64>().map_err(|error| format!("Invalid density: {}", error))?;
+    if (0.0..=1.0).contains(&density) {
+        Ok(density)
+    } else {
+        Err(format!("Density must be between 0.0 and 1.0 (received: {})", density))
+    }
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    #[test]
+    fn pattern_placements_are_parsed() {
+        assert_eq!(
+            Ok(PatternPlacement {
+                name: "gun.txt".to_string(),
+                position: Point { x: 0, y: 0 },
+                transforms: vec![],
+            }),
+            PatternPlacement::from_str("gun.txt")
+        );
+        assert_eq!(
+            Ok(PatternPlacement {
+                name: "data/eater.txt".to_string(),
+                position: Point { x: 80, y: 40 },
+                transforms: vec![Transform::Rotate90, Transform::FlipX],
+            }),
+            PatternPlacement::from_str("data/eater.txt@80,40:rot90:flipx")
+        );
+        assert_eq!(
+            Ok(vec![Transform::FlipY]),
+            PatternPlacement::from_str("a.txt:flipy").map(|placement| placement.transforms)
+        );
+    }
+
+    #[test]
+    fn invalid_pattern_placements_are_rejected() {
+        assert!(PatternPlacement::from_str("").is_err());
+        assert!(PatternPlacement::from_str("@1,2").is_err());
+        assert!(PatternPlacement::from_str("gun.txt@10").is_err());
+        assert!(PatternPlacement::from_str("gun.txt@-1,2").is_err());
+        assert!(PatternPlacement::from_str("gun.txt@1,2:rot45").is_err());
+    }
+
+    #[test]
+    fn arguments_are_valid() {
+        use clap::CommandFactory;
+
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn running_without_a_subcommand_plays_the_game() {
+        let args = Args::try_parse_from(["game_of_life", "-p", "glider", "-u", "10"]).unwrap();
+
+        let Command::Run(run_args) = args.into_command() else {
+            panic!("Expected the run subcommand");
+        };
+        assert_eq!(10, run_args.update_frequency);
+        assert_eq!(1, run_args.board.patterns.library_placements.len());
+    }
+
+    #[test]
+    fn shared_options_are_parsed_by_subcommands() {
+        let args = Args::try_parse_from([
+            "game_of_life",
+            "headless",
+            "--rule",
+            "B36/S23",
+            "--topology",
+            "bounded",
+            "--seed",
+            "42",
+        ])
+        .unwrap();
+
+        let Command::Headless(headless_args) = args.into_command() else {
+            panic!("Expected the headless subcommand");
+        };
+        assert_eq!(Rule::new(&[3, 6], &[2, 3]), headless_args.board.world.rule());
+        assert_eq!(Topology::Bounded, headless_args.board.world.topology());
+        assert_eq!(Some(42), headless_args.board.soup.seed);
+        assert_eq!(1000, headless_args.generations);
+    }
+
+    #[test]
+    fn options_are_not_mixed_with_subcommands() {
+        assert!(Args::try_parse_from(["game_of_life", "-u", "10", "headless"]).is_err());
+        assert!(Args::try_parse_from(["game_of_life", "render"]).is_err());
+    }
+
+    #[test]
+    fn update_frequency_must_be_positive() {
+        assert!(Args::try_parse_from(["game_of_life", "-u", "0"]).is_err());
+        assert!(Args::try_parse_from(["game_of_life", "render", "-o", "a.gif", "-u", "0"]).is_err());
+        assert!(Args::try_parse_from(["game_of_life", "render", "-o", "a.gif", "-u", "1"]).is_ok());
+    }
+
+    #[test]
+    fn density_must_be_a_probability() {
+        assert_eq!(Ok(0.25), parse_density("0.25"));
+        assert_eq!(Ok(1.0), parse_density("1"));
+        assert!(parse_density("1.5").is_err());
+        assert!(parse_density("-0.1").is_err());
+        assert!(parse_density("dense").is_err());
+    }
 }
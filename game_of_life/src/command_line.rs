@@ -0,0 +1,223 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::str::FromStr;
+
+use tui::components::TextInput;
+use tui::event::{KeyCode, KeyEvent};
+use tui::Paintbrush;
+use xy_utils::{Dimensions, Point};
+
+use crate::game::Rule;
+
+/// A command that can be typed into the command line while the game is
+/// running.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Play by a different rule (e.g. `:rule B36/S23`).
+    Rule(Rule),
+
+    /// Insert a pattern file in the middle of the board (e.g.
+    /// `:load gun.rle`).
+    Load(String),
+
+    /// Run the game until it reaches a generation (e.g. `:goto 1000`).
+    Goto(usize),
+
+    /// Change the number of generations per second (e.g. `:speed 20`).
+    Speed(u32),
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, argument) =
+            s.split_once(char::is_whitespace).map_or((s, ""), |(name, arg)| (name, arg.trim()));
+
+        match name {
+            "rule" => Ok(Command::Rule(Rule::from_str(argument)?)),
+            "load" if !argument.is_empty() => Ok(Command::Load(argument.to_string())),
+            "load" => Err("No pattern file was given to load".to_string()),
+            "goto" => argument
+                .parse()
+                .map(Command::Goto)
+                .map_err(|_| format!("Invalid generation \"{}\"", argument)),
+            "speed" => match argument.parse() {
+                Ok(speed) if speed > 0 => Ok(Command::Speed(speed)),
+                _ => Err(format!("Invalid speed \"{}\" (generations per second)", argument)),
+            },
+            _ => Err(format!("Unknown command \"{}\" (use rule, load, goto or speed)", name)),
+        }
+    }
+}
+
+/// Something that the game needs to do in response to the command line.
+#[derive(Debug, PartialEq)]
+pub enum CommandLineAction {
+    /// The command line was opened or edited, and needs to be shown again.
+    Edited,
+
+    /// The command line was closed without running a command.
+    Closed,
+
+    /// The command line was closed, and a command should be run (or the
+    /// error shown if the command is invalid).
+    Run(Result<Command, String>),
+}
+
+/// Command line that lets the user type commands while the game is running.
+///
+/// The command line is driven by key presses and displayed in place of the
+/// message bar: `:` opens the command line, `Enter` runs the command and `Esc`
+/// closes the command line. `Up` and `Down` browse the commands that have
+/// already been run.
+pub struct CommandLine {
+    /// The text being typed. Its size and position are chosen by the renderer.
+    input: TextInput,
+
+    open: bool,
+}
+
+impl CommandLine {
+    pub fn new() -> Self {
+        let input = TextInput::new(
+            Paintbrush::create_default(),
+            Point { x: 0, y: 0 },
+            Dimensions { width: 0, height: 1 },
+            ":",
+        );
+        CommandLine { input, open: false }
+    }
+
+    /// Get the command line to show, or `None` if it is closed.
+    pub fn input(&self) -> Option<&TextInput> {
+        self.open.then_some(&self.input)
+    }
+
+    pub fn input_mut(&mut self) -> Option<&mut TextInput> {
+        self.open.then_some(&mut self.input)
+    }
+
+    /// Handle a key press.
+    ///
+    /// ## Arguments
+    ///
+    /// * `key_event`: The key that was pressed.
+    ///
+    /// ## Returns
+    ///
+    /// The action to take in response to the key, if any. Every key is used
+    /// while the command line is open.
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> Option<CommandLineAction> {
+        if !self.open {
+            self.open = key_event == KeyEvent::new(KeyCode::Char(':'));
+            return self.open.then_some(CommandLineAction::Edited);
+        }
+
+        match key_event.code {
+            KeyCode::Enter => {
+                self.open = false;
+                Some(CommandLineAction::Run(self.input.submit().parse()))
+            }
+            KeyCode::Escape => {
+                self.open = false;
+                self.input.clear();
+                Some(CommandLineAction::Closed)
+            }
+            _ => {
+                self.input.handle_key(&key_event);
+                Some(CommandLineAction::Edited)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod command_line_tests {
+    use super::*;
+
+    fn type_text(command_line: &mut CommandLine, text: &str) {
+        for c in text.chars() {
+            command_line.handle_key(KeyEvent::new(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn commands_are_parsed() {
+        assert_eq!(Ok(Command::Rule(Rule::new(&[3, 6], &[2, 3]))), " rule  B36/S23 ".parse());
+        assert_eq!(Ok(Command::Load("my gun.rle".to_string())), "load my gun.rle".parse());
+        assert_eq!(Ok(Command::Goto(1000)), "goto 1000".parse());
+        assert_eq!(Ok(Command::Speed(20)), "speed 20".parse());
+    }
+
+    #[test]
+    fn invalid_commands_are_rejected() {
+        for command in ["", "jump 10", "rule B9", "load", "goto -1", "speed 0", "speed fast"] {
+            assert!(command.parse::<Command>().is_err(), "\"{}\" was accepted", command);
+        }
+    }
+
+    #[test]
+    fn keys_are_ignored_until_the_command_line_is_opened() {
+        let mut command_line = CommandLine::new();
+
+        assert_eq!(None, command_line.handle_key(KeyEvent::new(KeyCode::Char('g'))));
+        assert!(command_line.input().is_none());
+        assert_eq!(
+            Some(CommandLineAction::Edited),
+            command_line.handle_key(KeyEvent::new(KeyCode::Char(':')))
+        );
+        assert_eq!(Some(""), command_line.input().map(TextInput::text));
+    }
+
+    #[test]
+    fn enter_runs_the_command() {
+        let mut command_line = CommandLine::new();
+        type_text(&mut command_line, ":goto 12");
+
+        assert_eq!(
+            Some(CommandLineAction::Run(Ok(Command::Goto(12)))),
+            command_line.handle_key(KeyEvent::new(KeyCode::Enter))
+        );
+        assert!(command_line.input().is_none());
+
+        // The command can be run again from the history.
+        type_text(&mut command_line, ":");
+        command_line.handle_key(KeyEvent::new(KeyCode::Up));
+        assert_eq!(Some("goto 12"), command_line.input().map(TextInput::text));
+    }
+
+    #[test]
+    fn escape_closes_the_command_line() {
+        let mut command_line = CommandLine::new();
+        type_text(&mut command_line, ":speed");
+
+        assert_eq!(
+            Some(CommandLineAction::Closed),
+            command_line.handle_key(KeyEvent::new(KeyCode::Escape))
+        );
+        type_text(&mut command_line, ":");
+        assert_eq!(Some(""), command_line.input().map(TextInput::text));
+    }
+}
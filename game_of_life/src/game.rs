@@ -20,10 +20,29 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
+pub mod board;
 pub mod cell;
 pub mod game_board;
+pub mod library;
+pub mod multi_renderer;
+pub mod pattern;
+pub mod pattern_file;
+pub mod quadtree;
+pub mod quadtree_board;
 pub mod renderer;
+pub mod rule;
+pub mod soup;
+pub mod symmetry;
 
+pub use board::Board;
 pub use cell::{Cell, CellGenerator, RandomCellGenerator, UserCellGenerator};
-pub use game_board::GameBoard;
+pub use game_board::{GameBoard, Topology};
+pub use multi_renderer::MultiRenderer;
+pub use pattern::{Pattern, Transform};
+pub use pattern_file::{PatternFile, PatternFormat};
+pub use quadtree::QuadTree;
+pub use quadtree_board::QuadTreeBoard;
 pub use renderer::Renderer;
+pub use rule::Rule;
+pub use soup::SoupSettings;
+pub use symmetry::{SymmetricCellGenerator, Symmetry};
@@ -0,0 +1,63 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use xy_utils::{Dimensions, Point};
+
+use crate::game::{Pattern, Renderer, Rule};
+
+/// A game board that can be played, and that draws itself with a renderer.
+pub trait Board {
+    type Renderer: Renderer;
+
+    /// Run the game for one generation, and send the changed cells to the
+    /// renderer.
+    fn calculate_iteration(&mut self);
+
+    /// Get the size of the game board (or the visible part of it).
+    fn get_size(&self) -> Dimensions;
+
+    /// Get the address of every live cell on the game board (or the visible
+    /// part of it), in row-major order.
+    fn get_live_cells(&self) -> Vec<Point>;
+
+    /// Place a pattern on the game board.
+    ///
+    /// ## Arguments
+    ///
+    /// * `pattern`: The pattern to place.
+    /// * `position`: The position of the top-left corner of the pattern.
+    fn insert_pattern(&mut self, pattern: &Pattern, position: Point);
+
+    /// Play by a different rule from now on.
+    ///
+    /// ## Arguments
+    ///
+    /// * `rule`: The new rule.
+    ///
+    /// ## Returns
+    ///
+    /// An error if the game board cannot be played by the rule.
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String>;
+
+    /// Get the renderer that the game board is drawn with.
+    fn get_renderer(&mut self) -> &mut Self::Renderer;
+}
@@ -20,9 +20,10 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use rand;
+use crate::game::Pattern;
+use rand::{rngs::StdRng, SeedableRng};
 use std::collections::HashSet;
-use xy_utils::Point;
+use xy_utils::{Dimensions, Point};
 
 /// Cell Enumeration
 ///
@@ -48,14 +49,43 @@ pub trait CellGenerator {
     fn generate(&mut self, address: Point) -> Cell;
 }
 
+/// Boxed cell generators are also cell generators, so that the type of
+/// generator can be chosen at runtime.
+impl<CellGeneratorT: CellGenerator + ?Sized> CellGenerator for Box<CellGeneratorT> {
+    fn generate(&mut self, address: Point) -> Cell {
+        (**self).generate(address)
+    }
+}
+
 /// GellGenerator trait implementation that generates a random cell state.
+///
+/// By default, each cell on the board has a 50% chance of being alive. The
+/// density of live cells can be changed with `with_density()`, and the random
+/// "soup" can be restricted to a rectangular area of the board with
+/// `with_soup_area()` (cells outside of the area are always dead).
+///
+/// Random numbers are only drawn for cells inside the soup area, in row-major
+/// order, so a seeded generator will produce the same soup regardless of the
+/// size of the board that it is placed on.
 pub struct RandomCellGenerator<RandomT: rand::RngCore> {
-    pub rng: RandomT,
+    rng: RandomT,
+
+    /// A cell is alive if the next random number is below this threshold.
+    /// Stored as a `u128` so that a density of 1.0 can be represented.
+    alive_threshold: u128,
+
+    /// The area of the board that contains the soup (top-left cell and size).
+    /// `None` means that the whole board is used.
+    soup_area: Option<(Point, Dimensions)>,
 }
 
 impl<RandomT: rand::RngCore> CellGenerator for RandomCellGenerator<RandomT> {
-    fn generate(&mut self, _: Point) -> Cell {
-        if (self.rng.next_u64() % 2) == 0 {
+    fn generate(&mut self, address: Point) -> Cell {
+        if !self.is_in_soup_area(address) {
+            return Cell::Dead;
+        }
+
+        if (self.rng.next_u64() as u128) < self.alive_threshold {
             Cell::Alive
         } else {
             Cell::Dead
@@ -63,6 +93,60 @@ impl<RandomT: rand::RngCore> CellGenerator for RandomCellGenerator<RandomT> {
     }
 }
 
+impl<RandomT: rand::RngCore> RandomCellGenerator<RandomT> {
+    /// Create a generator that fills the whole board with a 50% density soup.
+    ///
+    /// ## Arguments
+    ///
+    /// * `rng`: The random number generator used to create the soup.
+    pub fn new(rng: RandomT) -> Self {
+        RandomCellGenerator { rng, alive_threshold: 0, soup_area: None }.with_density(0.5)
+    }
+
+    /// Set the probability of each cell in the soup being alive.
+    ///
+    /// ## Arguments
+    ///
+    /// * `density`: Probability of a cell being alive, between 0.0 and 1.0
+    ///   (values outside of this range are clamped).
+    pub fn with_density(mut self, density: f64) -> Self {
+        let density = density.clamp(0.0, 1.0);
+        self.alive_threshold = (density * (u64::MAX as f64 + 1.0)) as u128;
+        self
+    }
+
+    /// Restrict the soup to a rectangular area of the board.
+    ///
+    /// ## Arguments
+    ///
+    /// * `position`: The top-left cell of the soup.
+    /// * `size`: The size of the soup.
+    pub fn with_soup_area(mut self, position: Point, size: Dimensions) -> Self {
+        self.soup_area = Some((position, size));
+        self
+    }
+
+    fn is_in_soup_area(&self, address: Point) -> bool {
+        match self.soup_area {
+            Some((position, size)) => {
+                (address.x >= position.x)
+                    && (address.y >= position.y)
+                    && (address.x < position.x + size.width)
+                    && (address.y < position.y + size.height)
+            }
+            None => true,
+        }
+    }
+}
+
+impl RandomCellGenerator<StdRng> {
+    /// Create a generator from a seed. The same seed (with the same density
+    /// and soup size) will always produce the same soup.
+    pub fn from_seed(seed: u64) -> Self {
+        Self::new(StdRng::seed_from_u64(seed))
+    }
+}
+
 /// Cell generator that uses a pre-defined pattern to generate the cells.
 pub struct UserCellGenerator {
     alive_cells_list: HashSet<Point>,
@@ -79,47 +163,31 @@ impl CellGenerator for UserCellGenerator {
 }
 
 impl UserCellGenerator {
-    /// Create the starting layout from a string.
-    ///
-    /// ## Example
+    /// Create a generator with no live cells. Patterns can then be placed on
+    /// the board with `add_pattern()`.
+    pub fn new() -> UserCellGenerator {
+        UserCellGenerator { alive_cells_list: HashSet::new() }
+    }
+
+    /// Place a pattern on the board.
     ///
-    /// ```
-    /// let input =
-    ///     "*****   *****    *****\n\
-    ///      *      *     *  *     *\n\
-    ///      ***    *     *  *     *\n\
-    ///      *      *     *  *     *\n\
-    ///      *       *****    *****"
+    /// ## Arguments
     ///
-    /// let gen = UserCellGenerator::from_str(input);
-    /// ```
-    pub fn from_str(s: &str) -> Result<UserCellGenerator, String> {
-        let mut x = 0;
-        let mut y = 0;
-        let mut cell_set = HashSet::new();
-        for c in s.chars() {
-            match c {
-                '*' => {
-                    cell_set.insert(Point { x, y });
-                    x += 1;
-                }
-                ' ' => {
-                    x += 1;
-                }
-                '\n' => {
-                    x = 0;
-                    y += 1;
-                }
-                _ => {
-                    return Err(format!(
-                        "Invalid character '{}' specified in UserCellGenerator::from_str()",
-                        c
-                    ));
-                }
-            }
-        }
+    /// * `pattern`: The pattern to place.
+    /// * `position`: The position of the top-left corner of the pattern.
+    pub fn add_pattern(&mut self, pattern: &Pattern, position: Point) {
+        self.alive_cells_list.extend(
+            pattern
+                .cells()
+                .iter()
+                .map(|cell| Point { x: position.x + cell.x, y: position.y + cell.y }),
+        );
+    }
+}
 
-        Ok(UserCellGenerator { alive_cells_list: cell_set })
+impl Default for UserCellGenerator {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -139,10 +207,15 @@ mod cell_tests {
 mod random_cell_generator_tests {
     use super::*;
 
+    use rand::rngs::mock::StepRng;
+
+    /// Increment that makes `StepRng` alternate between zero and half of the
+    /// range of a `u64`.
+    const HALF_RANGE: u64 = 1 << 63;
+
     #[test]
     fn generates_random_cell_states() {
-        use rand::rngs::mock::StepRng;
-        let mut gen = RandomCellGenerator { rng: StepRng::new(0, 1) };
+        let mut gen = RandomCellGenerator::new(StepRng::new(0, HALF_RANGE));
 
         // Using StepRng should produce a consistent true/false pattern. Cell
         // address doesn't matter for this generator.
@@ -157,11 +230,59 @@ mod random_cell_generator_tests {
         assert_eq!(gen.generate(Point { x: 0, y: 0 }), Cell::Alive);
         assert_eq!(gen.generate(Point { x: 0, y: 0 }), Cell::Dead);
     }
+
+    #[test]
+    fn density_controls_the_proportion_of_live_cells() {
+        let mut empty = RandomCellGenerator::new(StepRng::new(0, 1)).with_density(0.0);
+        let mut full = RandomCellGenerator::new(StepRng::new(u64::MAX, 0)).with_density(1.0);
+
+        assert_eq!(empty.generate(Point { x: 0, y: 0 }), Cell::Dead);
+        assert_eq!(full.generate(Point { x: 0, y: 0 }), Cell::Alive);
+
+        // A quarter density soup should be roughly a quarter alive.
+        let mut gen = RandomCellGenerator::from_seed(42).with_density(0.25);
+        let alive =
+            (0..10000).filter(|x| gen.generate(Point { x: *x, y: 0 }) == Cell::Alive).count();
+        assert!((2000..3000).contains(&alive), "{} cells alive", alive);
+    }
+
+    #[test]
+    fn seeded_generators_are_reproducible() {
+        let mut first = RandomCellGenerator::from_seed(1234);
+        let mut second = RandomCellGenerator::from_seed(1234);
+
+        for x in 0..100 {
+            let address = Point { x, y: 0 };
+            assert_eq!(first.generate(address), second.generate(address));
+        }
+    }
+
+    #[test]
+    fn cells_outside_the_soup_area_are_dead() {
+        let mut gen = RandomCellGenerator::new(StepRng::new(0, 0))
+            .with_soup_area(Point { x: 2, y: 1 }, Dimensions { width: 2, height: 2 });
+
+        assert_eq!(gen.generate(Point { x: 1, y: 1 }), Cell::Dead);
+        assert_eq!(gen.generate(Point { x: 2, y: 0 }), Cell::Dead);
+        assert_eq!(gen.generate(Point { x: 2, y: 1 }), Cell::Alive);
+        assert_eq!(gen.generate(Point { x: 3, y: 2 }), Cell::Alive);
+        assert_eq!(gen.generate(Point { x: 4, y: 2 }), Cell::Dead);
+        assert_eq!(gen.generate(Point { x: 3, y: 3 }), Cell::Dead);
+    }
 }
 
 #[cfg(test)]
 mod user_cell_generator_tests {
     use super::*;
+    use std::str::FromStr;
+
+    /// Create a generator holding a pattern in the native format, with its
+    /// top-left corner at the top-left corner of the board.
+    fn generator_from_str(s: &str) -> Result<UserCellGenerator, String> {
+        let mut gen = UserCellGenerator::new();
+        gen.add_pattern(&Pattern::from_str(s)?, Point { x: 0, y: 0 });
+        Ok(gen)
+    }
 
     #[test]
     fn generates_cell_distribution_from_user_input() {
@@ -170,7 +291,7 @@ mod user_cell_generator_tests {
         // ** *
         //  **** *
         //  ****   **
-        let mut gen = UserCellGenerator::from_str(
+        let mut gen = generator_from_str(
             "** * \n\
              **** *\n\
              ****   **",
@@ -212,8 +333,22 @@ mod user_cell_generator_tests {
     #[test]
     #[should_panic]
     fn invalid_characters_produce_an_error() {
-        // This call to UserCellGenerator::from_str should report an error.
-        UserCellGenerator::from_str("*** *** This_string_is_full_of_invalid_characters *** ***")
-            .unwrap();
+        // Parsing the pattern should report an error.
+        generator_from_str("*** *** This_string_is_full_of_invalid_characters *** ***").unwrap();
+    }
+
+    #[test]
+    fn patterns_are_placed_at_an_offset() {
+        let mut gen = UserCellGenerator::new();
+        let pattern = Pattern::from_str("**\n *").unwrap();
+        gen.add_pattern(&pattern, Point { x: 3, y: 2 });
+        gen.add_pattern(&pattern, Point { x: 0, y: 0 });
+
+        assert_eq!(gen.generate(Point { x: 0, y: 0 }), Cell::Alive);
+        assert_eq!(gen.generate(Point { x: 1, y: 1 }), Cell::Alive);
+        assert_eq!(gen.generate(Point { x: 3, y: 2 }), Cell::Alive);
+        assert_eq!(gen.generate(Point { x: 4, y: 2 }), Cell::Alive);
+        assert_eq!(gen.generate(Point { x: 3, y: 3 }), Cell::Dead);
+        assert_eq!(gen.generate(Point { x: 4, y: 3 }), Cell::Alive);
     }
 }
@@ -20,9 +20,22 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::game::{Cell, CellGenerator, Renderer};
+use clap::ValueEnum;
+
+use crate::game::{Board, Cell, CellGenerator, Pattern, Renderer, Rule};
 use xy_utils::{Dimensions, Point};
 
+/// How the edges of the game board behave.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Topology {
+    /// Cells on each edge are adjacent to the cells on the opposite edge.
+    #[default]
+    Torus,
+
+    /// Cells beyond the edges of the board are always dead.
+    Bounded,
+}
+
 /// The Game Board.
 ///
 /// The game board struct is used to hold the dimensions of the game, as well as
@@ -45,6 +58,16 @@ pub struct GameBoard<'a, RendererT: Renderer> {
 
     /// Renderer is used to print the game progress to a user interface.
     renderer: &'a mut RendererT,
+
+    /// Cells changed outside of an iteration (e.g. by inserting a pattern)
+    /// that have not been sent to the renderer yet.
+    pending_changes: Vec<(Point, Cell)>,
+
+    /// The rule that cells are born and survive by.
+    rule: Rule,
+
+    /// How the edges of the game board behave.
+    topology: Topology,
 }
 
 impl<'a, RendererT: Renderer> GameBoard<'a, RendererT> {
@@ -80,12 +103,86 @@ impl<'a, RendererT: Renderer> GameBoard<'a, RendererT> {
         }
         renderer.apply_changes(cells_to_render);
 
-        GameBoard::<'a> { cells, dimensions, renderer }
+        GameBoard::<'a> {
+            cells,
+            dimensions,
+            renderer,
+            pending_changes: vec![],
+            rule: Rule::default(),
+            topology: Topology::default(),
+        }
+    }
+
+    /// Play the game by a different rule to Conway's Game of Life.
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Change how the edges of the game board behave (the board is a torus by
+    /// default).
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Get the size of the game board.
+    pub fn get_size(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    /// Get the address of every live cell, in row-major order.
+    pub fn get_live_cells(&self) -> Vec<Point> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| **cell == Cell::Alive)
+            .map(|(i, _)| Self::get_cell_address_from_array_index(i, self.dimensions))
+            .collect()
+    }
+
+    /// Get the renderer that the game board is drawn with.
+    pub fn get_renderer(&mut self) -> &mut RendererT {
+        self.renderer
+    }
+
+    /// Place a pattern on the game board. On a torus the pattern wraps around
+    /// the edges of the board in the same way that cells do, otherwise cells
+    /// beyond the edges are dropped.
+    ///
+    /// The new cells are sent to the renderer along with the changes from the
+    /// next iteration, so that renderers still see one set of changes per
+    /// generation.
+    ///
+    /// ## Arguments
+    ///
+    /// * `pattern`: The pattern to place.
+    /// * `position`: The position of the top-left corner of the pattern.
+    pub fn insert_pattern(&mut self, pattern: &Pattern, position: Point) {
+        for cell in pattern.cells() {
+            if self.topology == Topology::Bounded
+                && (position.x + cell.x >= self.dimensions.width
+                    || position.y + cell.y >= self.dimensions.height)
+            {
+                continue;
+            }
+
+            let cell_address = Point {
+                x: (position.x + cell.x) % self.dimensions.width,
+                y: (position.y + cell.y) % self.dimensions.height,
+            };
+            let array_index = (cell_address.y * self.dimensions.width) + cell_address.x;
+
+            if self.cells[array_index] == Cell::Dead {
+                self.cells[array_index] = Cell::Alive;
+                self.pending_changes.push((cell_address, Cell::Alive));
+            }
+        }
     }
 
     pub fn calculate_iteration(&mut self) {
         let mut new_cells = Vec::<Cell>::with_capacity(self.cells.capacity());
-        let mut cells_to_render = Vec::<(Point, Cell)>::new();
+        let mut cells_to_render = std::mem::take(&mut self.pending_changes);
         for (i, cell) in self.cells.iter().enumerate() {
             let cell_address = Self::get_cell_address_from_array_index(i, self.dimensions);
             let new_cell_state = self.calculate_new_cell_state(cell_address, *cell);
@@ -103,44 +200,20 @@ impl<'a, RendererT: Renderer> GameBoard<'a, RendererT> {
 
     /// For a given cell, calculate it's new state based on it's adjacent cells.
     fn calculate_new_cell_state(&self, cell_address: Point, cell: Cell) -> Cell {
-        let alive_adjacents = self.count_alive_adjacent_cells(cell_address);
-
-        // Rules of Conway's Game of Life:
-        //
-        // 1. A live cell with fewer than two live neighbours dies.
-        // 2. A live cell with two or three live neighbours lives on.
-        // 3. A live cell with more than three live neighbours dies.
-        // 4. A dead cell with exactly three live neighbours becomes a live
-        //    cell.
-
-        match cell {
-            Cell::Alive => {
-                if (alive_adjacents == 2) || (alive_adjacents == 3) {
-                    Cell::Alive
-                } else {
-                    Cell::Dead
-                }
-            }
-            Cell::Dead => {
-                if alive_adjacents == 3 {
-                    Cell::Alive
-                } else {
-                    Cell::Dead
-                }
-            }
-        }
+        self.rule.next_state(cell, self.count_alive_adjacent_cells(cell_address))
     }
 
     /// Cound the number of adjacent cells that are alive.
     fn count_alive_adjacent_cells(&self, cell_address: Point) -> usize {
         let mut count = 0;
-        for adjacent_cell_address in self.calculate_adjacent_cell_addresses(cell_address) {
+        for adjacent_cell_address in
+            self.calculate_adjacent_cell_addresses(cell_address).iter().flatten()
+        {
             let array_index =
                 (adjacent_cell_address.y * self.dimensions.width) + (adjacent_cell_address.x);
 
-            match self.cells[array_index] {
-                Cell::Alive => count += 1,
-                _ => {}
+            if self.cells[array_index] == Cell::Alive {
+                count += 1;
             }
         }
 
@@ -149,8 +222,8 @@ impl<'a, RendererT: Renderer> GameBoard<'a, RendererT> {
 
     /// Locate the adjacent cell addresses for a given cell address.
     ///
-    /// Adjacent cells wrap around, so if the game board is 10x10, the adjacent
-    /// cells for {0, 0} would be:
+    /// On a torus, adjacent cells wrap around, so if the game board is 10x10,
+    /// the adjacent cells for {0, 0} would be:
     ///
     /// * {x: 1, y: 0}
     /// * {x: 9, y: 0}
@@ -160,31 +233,47 @@ impl<'a, RendererT: Renderer> GameBoard<'a, RendererT> {
     /// * {x: 0, y: 9}
     /// * {x: 1, y: 9}
     /// * {x: 9, y: 9}
-    fn calculate_adjacent_cell_addresses(&self, cell_address: Point) -> [Point; 8] {
+    ///
+    /// On a bounded board, addresses beyond the edges are `None`.
+    fn calculate_adjacent_cell_addresses(&self, cell_address: Point) -> [Option<Point>; 8] {
         // Work out the adjacent cells. On edges (i.e. x or y is zero, or max
         // x or max y), the cell address will wrap around to the opposite edge
-        // of the board.
-        let row_above_y =
-            if cell_address.y == 0 { self.dimensions.height - 1 } else { cell_address.y - 1 };
-        let column_left_x =
-            if cell_address.x == 0 { self.dimensions.width - 1 } else { cell_address.x - 1 };
-        let column_right_x =
-            if cell_address.x == (self.dimensions.width - 1) { 0 } else { cell_address.x + 1 };
-        let row_below_y =
-            if cell_address.y == (self.dimensions.height - 1) { 0 } else { cell_address.y + 1 };
-
+        // of the board, or be missing if the board is bounded.
+        let wraps = self.topology == Topology::Torus;
+        let row_above_y = match cell_address.y {
+            0 => Some(self.dimensions.height - 1).filter(|_| wraps),
+            y => Some(y - 1),
+        };
+        let column_left_x = match cell_address.x {
+            0 => Some(self.dimensions.width - 1).filter(|_| wraps),
+            x => Some(x - 1),
+        };
+        let column_right_x = if cell_address.x == (self.dimensions.width - 1) {
+            Some(0).filter(|_| wraps)
+        } else {
+            Some(cell_address.x + 1)
+        };
+        let row_below_y = if cell_address.y == (self.dimensions.height - 1) {
+            Some(0).filter(|_| wraps)
+        } else {
+            Some(cell_address.y + 1)
+        };
+
+        let address = |x: Option<usize>, y: Option<usize>| Some(Point { x: x?, y: y? });
+        let x = Some(cell_address.x);
+        let y = Some(cell_address.y);
         [
             // Top row
-            Point { x: column_left_x, y: row_above_y },
-            Point { x: cell_address.x, y: row_above_y },
-            Point { x: column_right_x, y: row_above_y },
+            address(column_left_x, row_above_y),
+            address(x, row_above_y),
+            address(column_right_x, row_above_y),
             // Middle row
-            Point { x: column_left_x, y: cell_address.y },
-            Point { x: column_right_x, y: cell_address.y },
+            address(column_left_x, y),
+            address(column_right_x, y),
             // Bottom row.
-            Point { x: column_left_x, y: row_below_y },
-            Point { x: cell_address.x, y: row_below_y },
-            Point { x: column_right_x, y: row_below_y },
+            address(column_left_x, row_below_y),
+            address(x, row_below_y),
+            address(column_right_x, row_below_y),
         ]
     }
 
@@ -194,11 +283,48 @@ impl<'a, RendererT: Renderer> GameBoard<'a, RendererT> {
     }
 }
 
+impl<RendererT: Renderer> Board for GameBoard<'_, RendererT> {
+    type Renderer = RendererT;
+
+    fn calculate_iteration(&mut self) {
+        GameBoard::calculate_iteration(self)
+    }
+
+    fn get_size(&self) -> Dimensions {
+        GameBoard::get_size(self)
+    }
+
+    fn get_live_cells(&self) -> Vec<Point> {
+        GameBoard::get_live_cells(self)
+    }
+
+    fn insert_pattern(&mut self, pattern: &Pattern, position: Point) {
+        GameBoard::insert_pattern(self, pattern, position)
+    }
+
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String> {
+        self.rule = rule;
+        Ok(())
+    }
+
+    fn get_renderer(&mut self) -> &mut RendererT {
+        GameBoard::get_renderer(self)
+    }
+}
+
 #[cfg(test)]
 #[rustfmt::skip]  // Skipping rustfmt here because otherwise the cell layouts are unreadable.
 mod game_board_tests {
     use super::*;
-    use crate::game::{renderer::mock::MockRenderer, Renderer, UserCellGenerator};
+    use crate::game::{renderer::mock::MockRenderer, Pattern, Renderer, UserCellGenerator};
+
+    /// Create a generator holding a pattern in the native format, with its
+    /// top-left corner at the top-left corner of the board.
+    fn generator_from_str(s: &str) -> Result<UserCellGenerator, String> {
+        let mut gen = UserCellGenerator::new();
+        gen.add_pattern(&s.parse::<Pattern>()?, Point { x: 0, y: 0 });
+        Ok(gen)
+    }
 
     #[test]
     fn initializes() {
@@ -218,7 +344,7 @@ mod game_board_tests {
             // Scope the game board so that the renderer borrow is returned for
             // introspection.
             GameBoard::new_from_seed(
-                renderer.get_grid_size(), UserCellGenerator::from_str(expected).unwrap(), &mut renderer
+                renderer.get_grid_size(), generator_from_str(expected).unwrap(), &mut renderer
             );
         }
 
@@ -235,10 +361,38 @@ mod game_board_tests {
         assert_eq!(expected, renderer.print_grid());
     }
 
+    #[test]
+    fn inserted_patterns_are_rendered_with_the_next_iteration() {
+        use std::str::FromStr;
+
+        let mut renderer = MockRenderer::new();
+
+        {
+            let mut game_board = GameBoard::new_from_seed(
+                renderer.get_grid_size(), UserCellGenerator::new(), &mut renderer
+            );
+
+            // A block placed over the bottom-right corner wraps around the
+            // edges of the board.
+            game_board.insert_pattern(&Pattern::from_str("**\n**").unwrap(), Point { x: 4, y: 4 });
+            assert_eq!("     \n     \n     \n     \n     ", game_board.get_renderer().print_grid());
+
+            game_board.calculate_iteration();
+        }
+
+        let expected = concat!(
+            "*   *\n",
+            "     \n",
+            "     \n",
+            "     \n",
+            "*   *"
+        );
+
+        assert_eq!(expected, renderer.print_grid());
+    }
+
     /// The following patterns do not change between game iterations.
     mod still_lifes {
-        use crate::game::UserCellGenerator;
-
         use super::*;
 
         #[test]
@@ -258,7 +412,7 @@ mod game_board_tests {
                 // introspection.
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(expected).unwrap(),
+                    generator_from_str(expected).unwrap(),
                     &mut renderer
                 );
 
@@ -284,7 +438,7 @@ mod game_board_tests {
             {
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(expected).unwrap(),
+                    generator_from_str(expected).unwrap(),
                     &mut renderer
                 );
 
@@ -310,7 +464,7 @@ mod game_board_tests {
             {
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(expected).unwrap(),
+                    generator_from_str(expected).unwrap(),
                     &mut renderer
                 );
 
@@ -335,7 +489,7 @@ mod game_board_tests {
             {
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(expected).unwrap(),
+                    generator_from_str(expected).unwrap(),
                     &mut renderer
                 );
 
@@ -360,7 +514,7 @@ mod game_board_tests {
             {
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(expected).unwrap(),
+                    generator_from_str(expected).unwrap(),
                     &mut renderer
                 );
 
@@ -374,7 +528,6 @@ mod game_board_tests {
     /// The following patterns change their pattern in-place, but return to
     /// their original form.
     mod oscillators {
-        use crate::game::UserCellGenerator;
         use super::*;
 
         #[test]
@@ -400,7 +553,7 @@ mod game_board_tests {
             {
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(initial).unwrap(),
+                    generator_from_str(initial).unwrap(),
                     &mut renderer
                 );
 
@@ -435,7 +588,7 @@ mod game_board_tests {
             {
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(initial).unwrap(),
+                    generator_from_str(initial).unwrap(),
                     &mut renderer
                 );
 
@@ -470,7 +623,7 @@ mod game_board_tests {
             {
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(initial).unwrap(),
+                    generator_from_str(initial).unwrap(),
                     &mut renderer
                 );
 
@@ -484,8 +637,6 @@ mod game_board_tests {
     // These tests test travelling spaceships.
     mod spaceships {
         use super::*;
-        use crate::game::UserCellGenerator;
-
         #[test]
         fn glider() {
             let mut renderer = MockRenderer::new_with_size(Dimensions { width: 6, height: 6 });
@@ -513,7 +664,7 @@ mod game_board_tests {
             {
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(phase_1).unwrap(),
+                    generator_from_str(phase_1).unwrap(),
                     &mut renderer
                 );
 
@@ -537,7 +688,7 @@ mod game_board_tests {
                 // In reality we would simply reuse the existing game board.
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(phase_2).unwrap(),
+                    generator_from_str(phase_2).unwrap(),
                     &mut renderer
                 );
 
@@ -561,7 +712,7 @@ mod game_board_tests {
                 // In reality we would simply reuse the existing game board.
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(phase_3).unwrap(),
+                    generator_from_str(phase_3).unwrap(),
                     &mut renderer
                 );
 
@@ -586,7 +737,7 @@ mod game_board_tests {
                 // In reality we would simply reuse the existing game board.
                 let mut game_board = GameBoard::new_from_seed(
                     renderer.get_grid_size(),
-                    UserCellGenerator::from_str(phase_4).unwrap(),
+                    generator_from_str(phase_4).unwrap(),
                     &mut renderer
                 );
 
@@ -596,4 +747,121 @@ mod game_board_tests {
             assert_eq!(phase_1_translated, renderer.print_grid());
         }
     }
+
+    /// These tests use different rules and topologies to Conway's Game of
+    /// Life on a torus.
+    mod rules_and_topologies {
+        use super::*;
+        use std::str::FromStr;
+
+        /// Run one iteration of a game board and return the rendered result.
+        fn iterate(initial: &str, rule: Rule, topology: Topology) -> String {
+            let mut renderer = MockRenderer::new();
+
+            {
+                let mut game_board = GameBoard::new_from_seed(
+                    renderer.get_grid_size(),
+                    generator_from_str(initial).unwrap(),
+                    &mut renderer
+                )
+                .with_rule(rule)
+                .with_topology(topology);
+
+                game_board.calculate_iteration();
+            }
+
+            renderer.print_grid()
+        }
+
+        #[test]
+        fn cells_wrap_around_a_torus() {
+            let initial = concat!(
+                "     \n",
+                "*    \n",
+                "*    \n",
+                "*    \n",
+                "     "
+            );
+
+            let expected = concat!(
+                "     \n",
+                "     \n",
+                "**  *\n",
+                "     \n",
+                "     "
+            );
+
+            assert_eq!(expected, iterate(initial, Rule::CONWAY, Topology::Torus));
+        }
+
+        #[test]
+        fn cells_beyond_bounded_edges_are_dead() {
+            let initial = concat!(
+                "     \n",
+                "*    \n",
+                "*    \n",
+                "*    \n",
+                "     "
+            );
+
+            let expected = concat!(
+                "     \n",
+                "     \n",
+                "**   \n",
+                "     \n",
+                "     "
+            );
+
+            assert_eq!(expected, iterate(initial, Rule::CONWAY, Topology::Bounded));
+        }
+
+        #[test]
+        fn patterns_are_clipped_at_bounded_edges() {
+            let mut renderer = MockRenderer::new();
+
+            {
+                let mut game_board = GameBoard::new_from_seed(
+                    renderer.get_grid_size(), UserCellGenerator::new(), &mut renderer
+                )
+                .with_topology(Topology::Bounded);
+
+                game_board.insert_pattern(&Pattern::from_str("**\n**").unwrap(), Point { x: 4, y: 4 });
+                assert_eq!(vec![Point { x: 4, y: 4 }], game_board.get_live_cells());
+            }
+        }
+
+        #[test]
+        fn cells_follow_the_rule() {
+            let initial = concat!(
+                "     \n",
+                " *** \n",
+                "     \n",
+                " *** \n",
+                "     "
+            );
+
+            let conway = concat!(
+                "  *  \n",
+                "  *  \n",
+                "     \n",
+                "  *  \n",
+                "  *  "
+            );
+
+            // In HighLife, dead cells with six live neighbours are also born.
+            let high_life = concat!(
+                "  *  \n",
+                "  *  \n",
+                "  *  \n",
+                "  *  \n",
+                "  *  "
+            );
+
+            assert_eq!(conway, iterate(initial, Rule::CONWAY, Topology::Bounded));
+            assert_eq!(
+                high_life,
+                iterate(initial, Rule::from_str("B36/S23").unwrap(), Topology::Bounded)
+            );
+        }
+    }
 }
@@ -0,0 +1,322 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::{fmt, str::FromStr};
+
+use crate::game::Pattern;
+
+/// The kind of pattern in the library.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Category {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    Gun,
+    Puffer,
+    Methuselah,
+}
+
+impl Category {
+    /// All categories, in the order that they are listed.
+    pub const ALL: [Category; 6] = [
+        Category::StillLife,
+        Category::Oscillator,
+        Category::Spaceship,
+        Category::Gun,
+        Category::Puffer,
+        Category::Methuselah,
+    ];
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Category::StillLife => "Still lifes",
+            Category::Oscillator => "Oscillators",
+            Category::Spaceship => "Spaceships",
+            Category::Gun => "Guns",
+            Category::Puffer => "Puffers",
+            Category::Methuselah => "Methuselahs",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A well-known pattern that is built in to the game.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LibraryPattern {
+    /// Name used to look up the pattern (lower case, words separated by `-`).
+    pub name: &'static str,
+    pub category: Category,
+
+    /// One line description of the pattern.
+    pub description: &'static str,
+
+    /// Rows of the pattern, where `*` is a live cell and a space is a dead
+    /// cell.
+    rows: &'static [&'static str],
+}
+
+impl LibraryPattern {
+    /// Create the pattern so that it can be placed on a game board.
+    pub fn pattern(&self) -> Pattern {
+        Pattern::from_str(&self.rows.join("\n"))
+            .expect("Library patterns only contain valid characters")
+    }
+}
+
+/// Find a pattern in the library. Names are not case sensitive, and spaces or
+/// underscores can be used in place of `-`.
+///
+/// ## Arguments
+///
+/// * `name`: The name of the pattern.
+pub fn find(name: &str) -> Option<&'static LibraryPattern> {
+    let name = name.trim().to_lowercase().replace([' ', '_'], "-");
+    LIBRARY.iter().find(|pattern| pattern.name == name)
+}
+
+/// Every pattern in the library, grouped by category.
+pub const LIBRARY: &[LibraryPattern] = &[
+    LibraryPattern {
+        name: "block",
+        category: Category::StillLife,
+        description: "The smallest and most common still life.",
+        rows: &["**", "**"],
+    },
+    LibraryPattern {
+        name: "beehive",
+        category: Category::StillLife,
+        description: "The second most common still life.",
+        rows: &[" **", "*  *", " **"],
+    },
+    LibraryPattern {
+        name: "loaf",
+        category: Category::StillLife,
+        description: "A seven cell still life.",
+        rows: &[" **", "*  *", " * *", "  *"],
+    },
+    LibraryPattern {
+        name: "boat",
+        category: Category::StillLife,
+        description: "The only five cell still life.",
+        rows: &["**", "* *", " *"],
+    },
+    LibraryPattern {
+        name: "tub",
+        category: Category::StillLife,
+        description: "A four cell still life.",
+        rows: &[" *", "* *", " *"],
+    },
+    LibraryPattern {
+        name: "ship",
+        category: Category::StillLife,
+        description: "A six cell still life.",
+        rows: &["**", "* *", " **"],
+    },
+    LibraryPattern {
+        name: "pond",
+        category: Category::StillLife,
+        description: "An eight cell still life.",
+        rows: &[" **", "*  *", "*  *", " **"],
+    },
+    LibraryPattern {
+        name: "blinker",
+        category: Category::Oscillator,
+        description: "The smallest and most common oscillator (period 2).",
+        rows: &["***"],
+    },
+    LibraryPattern {
+        name: "toad",
+        category: Category::Oscillator,
+        description: "The second most common oscillator (period 2).",
+        rows: &[" ***", "***"],
+    },
+    LibraryPattern {
+        name: "beacon",
+        category: Category::Oscillator,
+        description: "Two diagonal blocks that touch every other generation (period 2).",
+        rows: &["**", "**", "  **", "  **"],
+    },
+    LibraryPattern {
+        name: "pulsar",
+        category: Category::Oscillator,
+        description: "The most common period 3 oscillator.",
+        rows: &[
+            "  ***   ***",
+            "",
+            "*    * *    *",
+            "*    * *    *",
+            "*    * *    *",
+            "  ***   ***",
+            "",
+            "  ***   ***",
+            "*    * *    *",
+            "*    * *    *",
+            "*    * *    *",
+            "",
+            "  ***   ***",
+        ],
+    },
+    LibraryPattern {
+        name: "pentadecathlon",
+        category: Category::Oscillator,
+        description: "A period 15 oscillator.",
+        rows: &["***", "* *", "***", "***", "***", "***", "* *", "***"],
+    },
+    LibraryPattern {
+        name: "glider",
+        category: Category::Spaceship,
+        description: "The smallest spaceship, travelling diagonally at c/4.",
+        rows: &[" *", "  *", "***"],
+    },
+    LibraryPattern {
+        name: "lwss",
+        category: Category::Spaceship,
+        description: "Lightweight spaceship, travelling orthogonally at c/2.",
+        rows: &[" *  *", "*", "*   *", "****"],
+    },
+    LibraryPattern {
+        name: "mwss",
+        category: Category::Spaceship,
+        description: "Middleweight spaceship, travelling orthogonally at c/2.",
+        rows: &["   *", " *   *", "*", "*    *", "*****"],
+    },
+    LibraryPattern {
+        name: "hwss",
+        category: Category::Spaceship,
+        description: "Heavyweight spaceship, travelling orthogonally at c/2.",
+        rows: &["   **", " *    *", "*", "*     *", "******"],
+    },
+    LibraryPattern {
+        name: "gosper-glider-gun",
+        category: Category::Gun,
+        description: "The first known gun, emitting a glider every 30 generations.",
+        rows: &[
+            "                        *",
+            "                      * *",
+            "            **      **            **",
+            "           *   *    **            **",
+            "**        *     *   **",
+            "**        *   * **    * *",
+            "          *     *       *",
+            "           *   *",
+            "            **",
+        ],
+    },
+    LibraryPattern {
+        name: "blinker-puffer",
+        category: Category::Puffer,
+        description: "A c/2 puffer that leaves a trail of blinkers behind it.",
+        rows: &[
+            "   *",
+            " *   *",
+            "*",
+            "*    *",
+            "*****",
+            "",
+            "",
+            "",
+            " **",
+            "** ***",
+            " ****",
+            "  **",
+            "",
+            "     **",
+            "   *    *",
+            "  *",
+            "  *     *",
+            "  ******",
+        ],
+    },
+    LibraryPattern {
+        name: "r-pentomino",
+        category: Category::Methuselah,
+        description: "Stabilises after 1103 generations.",
+        rows: &[" **", "**", " *"],
+    },
+    LibraryPattern {
+        name: "acorn",
+        category: Category::Methuselah,
+        description: "Stabilises after 5206 generations.",
+        rows: &[" *", "   *", "**  ***"],
+    },
+    LibraryPattern {
+        name: "diehard",
+        category: Category::Methuselah,
+        description: "Dies out completely after 130 generations.",
+        rows: &["      *", "**", " *   ***"],
+    },
+    LibraryPattern {
+        name: "pi-heptomino",
+        category: Category::Methuselah,
+        description: "Stabilises after 173 generations.",
+        rows: &["***", "* *", "* *"],
+    },
+    LibraryPattern {
+        name: "b-heptomino",
+        category: Category::Methuselah,
+        description: "Stabilises after 148 generations.",
+        rows: &["* **", "***", " *"],
+    },
+];
+
+#[cfg(test)]
+mod library_tests {
+    use super::*;
+
+    #[test]
+    fn every_pattern_is_valid() {
+        for library_pattern in LIBRARY {
+            let pattern = library_pattern.pattern();
+            assert!(!pattern.cells().is_empty(), "{} is empty", library_pattern.name);
+        }
+    }
+
+    #[test]
+    fn pattern_names_are_unique() {
+        for (i, library_pattern) in LIBRARY.iter().enumerate() {
+            assert!(
+                LIBRARY[(i + 1)..].iter().all(|other| other.name != library_pattern.name),
+                "{} is in the library twice",
+                library_pattern.name
+            );
+        }
+    }
+
+    #[test]
+    fn patterns_are_grouped_by_category() {
+        let categories = LIBRARY.iter().map(|pattern| pattern.category).collect::<Vec<_>>();
+        let mut sorted = categories.clone();
+        sorted.sort_by_key(|category| Category::ALL.iter().position(|c| c == category));
+
+        assert_eq!(sorted, categories);
+    }
+
+    #[test]
+    fn patterns_are_found_by_name() {
+        assert_eq!("r-pentomino", find("R-Pentomino").unwrap().name);
+        assert_eq!("gosper-glider-gun", find("gosper glider_gun").unwrap().name);
+        assert_eq!(5, find("glider").unwrap().pattern().cells().len());
+        assert!(find("garden-of-eden").is_none());
+    }
+}
@@ -0,0 +1,220 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::game::{Cell, Renderer};
+use xy_utils::{Dimensions, Point};
+
+/// Renderer that fans changes out to zero or more child renderers.
+///
+/// The game board can only notify a single renderer, so the multi-renderer
+/// allows several observers (e.g. the TUI, a GIF recorder and a statistics
+/// collector) to watch the same game.
+///
+/// Children can either be owned by the multi-renderer, or borrowed from the
+/// caller (mutable references to renderers are also renderers), which allows
+/// the caller to inspect or finalize a renderer once the game has ended.
+///
+/// ## Grid Size
+///
+/// The grid size of the multi-renderer is the largest grid that all of the
+/// children can render. Children that report an undefined (zero) width or
+/// height are considered to be unlimited in that dimension. If no child
+/// defines a dimension, the dimension is undefined.
+///
+/// ## Example
+///
+/// ```ignore
+/// let mut tui = TuiRenderer::new(DefaultPlotter::create_from_stdout(), size);
+/// let mut renderers = MultiRenderer::new();
+/// renderers.add(Box::new(&mut tui));
+/// renderers.add(Box::new(GifRecorder::new(file, size, 4, 10)?));
+/// ```
+pub struct MultiRenderer<'a> {
+    renderers: Vec<Box<dyn Renderer + 'a>>,
+}
+
+impl<'a> MultiRenderer<'a> {
+    /// Create a multi-renderer with no children.
+    pub fn new() -> Self {
+        MultiRenderer { renderers: vec![] }
+    }
+
+    /// Add a child renderer. Children are notified of changes in the order
+    /// that they are added.
+    pub fn add(&mut self, renderer: Box<dyn Renderer + 'a>) {
+        self.renderers.push(renderer);
+    }
+}
+
+impl Default for MultiRenderer<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderer for MultiRenderer<'_> {
+    fn initialize(&mut self) {
+        for renderer in &mut self.renderers {
+            renderer.initialize();
+        }
+    }
+
+    fn get_grid_size(&self) -> Dimensions {
+        let mut result = Dimensions::create_empty();
+        for renderer in &self.renderers {
+            let size = renderer.get_grid_size();
+            result.width = smallest_defined_dimension(result.width, size.width);
+            result.height = smallest_defined_dimension(result.height, size.height);
+        }
+
+        result
+    }
+
+    fn apply_changes(&mut self, changes: Vec<(Point, Cell)>) {
+        // Avoid a needless copy for the last renderer.
+        if let Some((last, others)) = self.renderers.split_last_mut() {
+            for renderer in others {
+                renderer.apply_changes(changes.clone());
+            }
+            last.apply_changes(changes);
+        }
+    }
+
+    fn show_message(&mut self, message: &str) {
+        for renderer in &mut self.renderers {
+            renderer.show_message(message);
+        }
+    }
+}
+
+/// Get the smaller of two dimensions, where zero means "undefined".
+fn smallest_defined_dimension(a: usize, b: usize) -> usize {
+    match (a, b) {
+        (0, _) => b,
+        (_, 0) => a,
+        _ => a.min(b),
+    }
+}
+
+#[cfg(test)]
+mod multi_renderer_tests {
+    use super::*;
+    use crate::game::renderer::mock::MockRenderer;
+
+    /// Renderer that can render any grid size. Counts the calls made to it.
+    #[derive(Default)]
+    struct UnlimitedRenderer {
+        initialize_calls: usize,
+        changes_received: Vec<Vec<(Point, Cell)>>,
+    }
+
+    impl Renderer for UnlimitedRenderer {
+        fn initialize(&mut self) {
+            self.initialize_calls += 1;
+        }
+
+        fn get_grid_size(&self) -> Dimensions {
+            Dimensions::create_empty()
+        }
+
+        fn apply_changes(&mut self, changes: Vec<(Point, Cell)>) {
+            self.changes_received.push(changes);
+        }
+    }
+
+    #[test]
+    fn changes_are_applied_to_every_renderer() {
+        let mut first = MockRenderer::new_with_size(Dimensions { width: 2, height: 1 });
+        let mut second = MockRenderer::new_with_size(Dimensions { width: 3, height: 1 });
+
+        {
+            let mut renderers = MultiRenderer::new();
+            renderers.add(Box::new(&mut first));
+            renderers.add(Box::new(&mut second));
+
+            renderers.apply_changes(vec![(Point { x: 1, y: 0 }, Cell::Alive)]);
+        }
+
+        assert_eq!(" *", first.print_grid());
+        assert_eq!(" * ", second.print_grid());
+    }
+
+    #[test]
+    fn every_renderer_is_initialized() {
+        let mut first = UnlimitedRenderer::default();
+        let mut second = UnlimitedRenderer::default();
+
+        {
+            let mut renderers = MultiRenderer::new();
+            renderers.add(Box::new(&mut first));
+            renderers.add(Box::new(&mut second));
+            renderers.initialize();
+        }
+
+        assert_eq!(1, first.initialize_calls);
+        assert_eq!(1, second.initialize_calls);
+    }
+
+    #[test]
+    fn grid_size_is_the_smallest_grid_all_renderers_can_show() {
+        let mut renderers = MultiRenderer::new();
+        renderers.add(Box::new(MockRenderer::new_with_size(Dimensions { width: 2, height: 7 })));
+        renderers.add(Box::new(MockRenderer::new_with_size(Dimensions { width: 5, height: 3 })));
+
+        assert_eq!(Dimensions { width: 2, height: 3 }, renderers.get_grid_size());
+    }
+
+    #[test]
+    fn unlimited_renderers_do_not_restrict_the_grid_size() {
+        let mut renderers = MultiRenderer::new();
+        renderers.add(Box::new(UnlimitedRenderer::default()));
+        assert_eq!(Dimensions::create_empty(), renderers.get_grid_size());
+
+        renderers.add(Box::new(MockRenderer::new_with_size(Dimensions { width: 2, height: 7 })));
+        assert_eq!(Dimensions { width: 2, height: 7 }, renderers.get_grid_size());
+    }
+
+    #[test]
+    fn empty_multi_renderer_ignores_changes() {
+        let mut renderers = MultiRenderer::new();
+        renderers.apply_changes(vec![(Point { x: 1, y: 0 }, Cell::Alive)]);
+        assert_eq!(Dimensions::create_empty(), renderers.get_grid_size());
+    }
+
+    #[test]
+    fn each_renderer_receives_the_same_changes() {
+        let mut first = UnlimitedRenderer::default();
+        let mut second = UnlimitedRenderer::default();
+
+        {
+            let mut renderers = MultiRenderer::new();
+            renderers.add(Box::new(&mut first));
+            renderers.add(Box::new(&mut second));
+
+            renderers.apply_changes(vec![(Point { x: 1, y: 0 }, Cell::Alive)]);
+            renderers.apply_changes(vec![]);
+        }
+
+        assert_eq!(first.changes_received, second.changes_received);
+        assert_eq!(2, first.changes_received.len());
+    }
+}
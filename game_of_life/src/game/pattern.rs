@@ -0,0 +1,297 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::{fmt, str::FromStr};
+
+use xy_utils::{Dimensions, Point};
+
+/// A transformation that can be applied to a pattern.
+///
+/// Rotations are clockwise, and flips mirror the pattern along the named axis
+/// (`FlipX` reverses each row, `FlipY` reverses the order of the rows).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Transform {
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipX,
+    FlipY,
+}
+
+impl Transform {
+    /// Apply the transform to a single cell of a pattern with the given size.
+    fn apply(&self, cell: Point, size: Dimensions) -> Point {
+        let Point { x, y } = cell;
+        match self {
+            Transform::Rotate90 => Point { x: size.height - 1 - y, y: x },
+            Transform::Rotate180 => Point { x: size.width - 1 - x, y: size.height - 1 - y },
+            Transform::Rotate270 => Point { x: y, y: size.width - 1 - x },
+            Transform::FlipX => Point { x: size.width - 1 - x, y },
+            Transform::FlipY => Point { x, y: size.height - 1 - y },
+        }
+    }
+
+    /// Size of a pattern after the transform has been applied.
+    fn apply_to_size(&self, size: Dimensions) -> Dimensions {
+        match self {
+            Transform::Rotate90 | Transform::Rotate270 => {
+                Dimensions { width: size.height, height: size.width }
+            }
+            _ => size,
+        }
+    }
+}
+
+impl FromStr for Transform {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Transform, String> {
+        match s {
+            "rot90" => Ok(Transform::Rotate90),
+            "rot180" => Ok(Transform::Rotate180),
+            "rot270" => Ok(Transform::Rotate270),
+            "flipx" => Ok(Transform::FlipX),
+            "flipy" => Ok(Transform::FlipY),
+            _ => Err(format!(
+                "Unknown transform \"{}\" (expected rot90, rot180, rot270, flipx or flipy)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Transform::Rotate90 => "rot90",
+            Transform::Rotate180 => "rot180",
+            Transform::Rotate270 => "rot270",
+            Transform::FlipX => "flipx",
+            Transform::FlipY => "flipy",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A pattern of live cells that can be placed on a game board.
+///
+/// Cells are relative to the top-left corner of the pattern, and the size of
+/// the pattern includes any empty space around the live cells, so that a
+/// pattern read from a file keeps its layout when it is transformed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Pattern {
+    cells: Vec<Point>,
+    size: Dimensions,
+}
+
+impl Pattern {
+    /// Create a pattern.
+    ///
+    /// ## Arguments
+    ///
+    /// * `cells`: The live cells, relative to the top-left corner of the
+    ///   pattern. Duplicates are ignored.
+    /// * `size`: The size of the pattern, which is grown if necessary to fit
+    ///   every cell.
+    pub fn new(cells: Vec<Point>, size: Dimensions) -> Pattern {
+        let size = cells.iter().fold(size, |size, cell| Dimensions {
+            width: size.width.max(cell.x + 1),
+            height: size.height.max(cell.y + 1),
+        });
+        let mut pattern = Pattern { cells, size };
+        pattern.sort();
+        pattern.cells.dedup();
+        pattern
+    }
+
+    /// The live cells of the pattern, in row-major order.
+    pub fn cells(&self) -> &[Point] {
+        &self.cells
+    }
+
+    /// The size of the pattern.
+    pub fn size(&self) -> Dimensions {
+        self.size
+    }
+
+    /// Create a copy of the pattern with a transform applied.
+    ///
+    /// ## Arguments
+    ///
+    /// * `transform`: The transform to apply.
+    pub fn transformed(&self, transform: Transform) -> Pattern {
+        let mut pattern = Pattern {
+            cells: self.cells.iter().map(|cell| transform.apply(*cell, self.size)).collect(),
+            size: transform.apply_to_size(self.size),
+        };
+        pattern.sort();
+        pattern
+    }
+
+    fn sort(&mut self) {
+        self.cells.sort_by_key(|cell| (cell.y, cell.x));
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = String;
+
+    /// Read a pattern from a string, where `*` is a live cell and a space is a
+    /// dead cell. Each line of the string is one row of the pattern.
+    fn from_str(s: &str) -> Result<Pattern, String> {
+        let mut cells = vec![];
+        let mut size = Dimensions::create_empty();
+
+        for (y, line) in s.lines().enumerate() {
+            size.height = y + 1;
+            size.width = size.width.max(line.chars().count());
+
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '*' => cells.push(Point { x, y }),
+                    ' ' => {}
+                    _ => {
+                        return Err(format!(
+                            "Invalid character '{}' in pattern (line {}, column {})",
+                            c,
+                            y + 1,
+                            x + 1
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(Pattern { cells, size })
+    }
+}
+
+impl fmt::Display for Pattern {
+    /// Write the pattern in the same format that it is read in, with `*` for
+    /// live cells. Trailing dead cells are left off each line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut cells = self.cells.iter().peekable();
+        for y in 0..self.size.height {
+            let mut x = 0;
+            while let Some(cell) = cells.next_if(|cell| cell.y == y) {
+                write!(f, "{:>width$}", '*', width = cell.x - x + 1)?;
+                x = cell.x + 1;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod pattern_tests {
+    use super::*;
+
+    /// An asymmetric pattern:
+    ///
+    /// ```text
+    /// **
+    /// *
+    /// *
+    /// ```
+    fn create_l_pattern() -> Pattern {
+        Pattern::from_str("**\n*\n*").unwrap()
+    }
+
+    fn points(cells: &[(usize, usize)]) -> Vec<Point> {
+        cells.iter().map(|(x, y)| Point { x: *x, y: *y }).collect()
+    }
+
+    #[test]
+    fn patterns_are_read_from_strings() {
+        let pattern = Pattern::from_str(" *\n  *\n***\n").unwrap();
+
+        assert_eq!(Dimensions { width: 3, height: 3 }, pattern.size());
+        assert_eq!(points(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]), pattern.cells());
+        assert!(Pattern::from_str("*o*").is_err());
+    }
+
+    #[test]
+    fn patterns_are_rotated_clockwise() {
+        let pattern = create_l_pattern();
+
+        // ***
+        //   *
+        let rotated = pattern.transformed(Transform::Rotate90);
+        assert_eq!(Dimensions { width: 3, height: 2 }, rotated.size());
+        assert_eq!(points(&[(0, 0), (1, 0), (2, 0), (2, 1)]), rotated.cells());
+
+        //  *
+        //  *
+        // **
+        let rotated = pattern.transformed(Transform::Rotate180);
+        assert_eq!(points(&[(1, 0), (1, 1), (0, 2), (1, 2)]), rotated.cells());
+
+        // *
+        // ***
+        let rotated = pattern.transformed(Transform::Rotate270);
+        assert_eq!(points(&[(0, 0), (0, 1), (1, 1), (2, 1)]), rotated.cells());
+    }
+
+    #[test]
+    fn patterns_are_flipped() {
+        let pattern = create_l_pattern();
+
+        assert_eq!(
+            points(&[(0, 0), (1, 0), (1, 1), (1, 2)]),
+            pattern.transformed(Transform::FlipX).cells()
+        );
+        assert_eq!(
+            points(&[(0, 0), (0, 1), (0, 2), (1, 2)]),
+            pattern.transformed(Transform::FlipY).cells()
+        );
+    }
+
+    #[test]
+    fn four_rotations_restore_the_pattern() {
+        let pattern = create_l_pattern();
+        let mut rotated = pattern.clone();
+        for _ in 0..4 {
+            rotated = rotated.transformed(Transform::Rotate90);
+        }
+
+        assert_eq!(pattern, rotated);
+    }
+
+    #[test]
+    fn patterns_are_written_in_the_format_they_are_read() {
+        let text = " *\n  *\n***\n\n";
+        let pattern = Pattern::from_str(text).unwrap();
+
+        assert_eq!(text, pattern.to_string());
+        assert_eq!(pattern, Pattern::from_str(&pattern.to_string()).unwrap());
+    }
+
+    #[test]
+    fn transforms_are_parsed_from_names() {
+        assert_eq!(Ok(Transform::Rotate90), Transform::from_str("rot90"));
+        assert_eq!(Ok(Transform::FlipY), Transform::from_str("flipy"));
+        assert!(Transform::from_str("rot45").is_err());
+        assert_eq!("rot270", Transform::Rotate270.to_string());
+    }
+}
@@ -0,0 +1,245 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Reading and writing patterns in the file formats used by other Life
+//! programs.
+
+use std::str::FromStr;
+
+use clap::ValueEnum;
+
+use crate::game::{Pattern, Rule};
+
+mod life106;
+pub mod macrocell;
+mod plaintext;
+mod rle;
+
+/// Pattern file formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PatternFormat {
+    /// This program's own format, where `*` is a live cell and a space is a
+    /// dead cell.
+    Native,
+
+    /// Plaintext (`.cells`), where `O` is a live cell, `.` is a dead cell and
+    /// lines starting with `!` are comments.
+    Plaintext,
+
+    /// Run length encoded (`.rle`).
+    Rle,
+
+    /// Life 1.06 (`.lif`, `.life`), a list of live cell coordinates.
+    Life106,
+
+    /// Golly's macrocell format (`.mc`), a quadtree of the pattern.
+    Macrocell,
+}
+
+impl PatternFormat {
+    /// Guess the format of a pattern file from its name. Files with an
+    /// unrecognised extension are assumed to be in the native format.
+    pub fn from_file_name(file_name: &str) -> PatternFormat {
+        let lowercase_name = file_name.to_lowercase();
+        let extension = lowercase_name.rsplit_once('.').map_or("", |(_, extension)| extension);
+        match extension {
+            "cells" => PatternFormat::Plaintext,
+            "rle" => PatternFormat::Rle,
+            "lif" | "life" => PatternFormat::Life106,
+            "mc" => PatternFormat::Macrocell,
+            _ => PatternFormat::Native,
+        }
+    }
+}
+
+/// A pattern along with the information that pattern files can hold about
+/// it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PatternFile {
+    /// The pattern.
+    pub pattern: Pattern,
+
+    /// Name of the pattern.
+    pub name: Option<String>,
+
+    /// Who discovered or created the pattern.
+    pub author: Option<String>,
+
+    /// Free text comments, one per line.
+    pub comments: Vec<String>,
+
+    /// The rule that the pattern is meant to be run with.
+    pub rule: Option<Rule>,
+}
+
+impl PatternFile {
+    /// Create a pattern file with no information other than the pattern.
+    pub fn new(pattern: Pattern) -> PatternFile {
+        PatternFile { pattern, name: None, author: None, comments: vec![], rule: None }
+    }
+
+    /// Read a pattern file.
+    ///
+    /// ## Arguments
+    ///
+    /// * `contents`: The contents of the file.
+    /// * `format`: The format of the file.
+    pub fn parse(contents: &str, format: PatternFormat) -> Result<PatternFile, String> {
+        match format {
+            PatternFormat::Native => Ok(PatternFile::new(Pattern::from_str(contents)?)),
+            PatternFormat::Plaintext => plaintext::parse(contents),
+            PatternFormat::Rle => rle::parse(contents),
+            PatternFormat::Life106 => life106::parse(contents),
+            PatternFormat::Macrocell => macrocell::parse(contents),
+        }
+    }
+
+    /// Write the pattern file. Information that the format cannot hold (such
+    /// as the rule in a plaintext file) is left out.
+    ///
+    /// ## Arguments
+    ///
+    /// * `format`: The format to write.
+    pub fn write(&self, format: PatternFormat) -> String {
+        match format {
+            PatternFormat::Native => self.pattern.to_string(),
+            PatternFormat::Plaintext => plaintext::write(self),
+            PatternFormat::Rle => rle::write(self),
+            PatternFormat::Life106 => life106::write(self),
+            PatternFormat::Macrocell => macrocell::write(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod pattern_file_tests {
+    use super::*;
+    use xy_utils::{Dimensions, Point};
+
+    const FORMATS: [PatternFormat; 5] = [
+        PatternFormat::Native,
+        PatternFormat::Plaintext,
+        PatternFormat::Rle,
+        PatternFormat::Life106,
+        PatternFormat::Macrocell,
+    ];
+
+    const BUNDLED_PATTERNS: [&str; 2] = [
+        include_str!("../../../data/game_gun.txt"),
+        include_str!("../../../data/game_pentadecathlon.txt"),
+    ];
+
+    fn create_annotated_glider() -> PatternFile {
+        PatternFile {
+            pattern: Pattern::from_str(" *\n  *\n***").unwrap(),
+            name: Some("Glider".to_string()),
+            author: Some("Richard K. Guy".to_string()),
+            comments: vec!["The smallest spaceship.".to_string(), "".to_string()],
+            rule: Some(Rule::CONWAY),
+        }
+    }
+
+    #[test]
+    fn formats_are_guessed_from_file_names() {
+        assert_eq!(PatternFormat::Plaintext, PatternFormat::from_file_name("glider.cells"));
+        assert_eq!(PatternFormat::Rle, PatternFormat::from_file_name("data/GUN.RLE"));
+        assert_eq!(PatternFormat::Life106, PatternFormat::from_file_name("a.lif"));
+        assert_eq!(PatternFormat::Life106, PatternFormat::from_file_name("a.life"));
+        assert_eq!(PatternFormat::Macrocell, PatternFormat::from_file_name("big.mc"));
+        assert_eq!(PatternFormat::Native, PatternFormat::from_file_name("data/game_gun.txt"));
+        assert_eq!(PatternFormat::Native, PatternFormat::from_file_name("pattern"));
+    }
+
+    #[test]
+    fn bundled_patterns_round_trip_through_every_format() {
+        for contents in BUNDLED_PATTERNS {
+            let original = PatternFile::parse(contents, PatternFormat::Native).unwrap();
+
+            for format in FORMATS {
+                let written = original.write(format);
+                let read = PatternFile::parse(&written, format).unwrap();
+                assert_eq!(original, read, "{:?} round trip changed the pattern", format);
+
+                // The converted file converts back to the original file.
+                assert_eq!(contents, read.write(PatternFormat::Native), "{:?}", format);
+            }
+        }
+    }
+
+    #[test]
+    fn bundled_patterns_convert_between_every_pair_of_formats() {
+        for contents in BUNDLED_PATTERNS {
+            let original = PatternFile::parse(contents, PatternFormat::Native).unwrap();
+
+            for from in FORMATS {
+                let source = PatternFile::parse(&original.write(from), from).unwrap();
+                for to in FORMATS {
+                    let converted = PatternFile::parse(&source.write(to), to).unwrap();
+                    assert_eq!(original.pattern, converted.pattern, "{:?} to {:?}", from, to);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rules_and_comments_are_kept_where_the_format_allows() {
+        let glider = create_annotated_glider();
+
+        for format in [PatternFormat::Rle, PatternFormat::Macrocell] {
+            assert_eq!(glider, PatternFile::parse(&glider.write(format), format).unwrap());
+        }
+
+        // Plaintext files have no rule.
+        let plaintext =
+            PatternFile::parse(&glider.write(PatternFormat::Plaintext), PatternFormat::Plaintext)
+                .unwrap();
+        assert_eq!(PatternFile { rule: None, ..glider.clone() }, plaintext);
+
+        // Life 1.06 and native files only hold the pattern.
+        for format in [PatternFormat::Life106, PatternFormat::Native] {
+            let read = PatternFile::parse(&glider.write(format), format).unwrap();
+            assert_eq!(PatternFile::new(glider.pattern.clone()), read);
+        }
+    }
+
+    #[test]
+    fn empty_patterns_round_trip() {
+        let empty = PatternFile::new(Pattern::new(vec![], Dimensions::create_empty()));
+
+        for format in FORMATS {
+            let read = PatternFile::parse(&empty.write(format), format).unwrap();
+            assert!(read.pattern.cells().is_empty(), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn large_patterns_round_trip() {
+        // A diagonal line spanning several levels of a macrocell quadtree,
+        // with long runs in the RLE rows.
+        let cells = (0..300).map(|i| Point { x: i, y: i / 3 }).collect();
+        let file = PatternFile::new(Pattern::new(cells, Dimensions::create_empty()));
+
+        for format in FORMATS {
+            assert_eq!(file, PatternFile::parse(&file.write(format), format).unwrap());
+        }
+    }
+}
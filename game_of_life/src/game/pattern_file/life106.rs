@@ -0,0 +1,113 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The Life 1.06 format, a list of the coordinates of each live cell.
+//!
+//! See <https://conwaylife.com/wiki/Life_1.06>.
+
+use xy_utils::{Dimensions, Point};
+
+use crate::game::{pattern_file::PatternFile, Pattern};
+
+const HEADER: &str = "#Life 1.06";
+
+/// Read a Life 1.06 file. Coordinates may be negative, in which case the
+/// pattern is moved so that every cell has a positive position.
+pub fn parse(contents: &str) -> Result<PatternFile, String> {
+    let mut lines = contents.lines().map(str::trim).filter(|line| !line.is_empty()).peekable();
+    if lines.next_if(|line| line.starts_with('#')).is_some_and(|line| line != HEADER) {
+        return Err(format!("Unsupported Life file (expected the \"{}\" header)", HEADER));
+    }
+
+    let mut coordinates = vec![];
+    for line in lines.filter(|line| !line.starts_with('#')) {
+        let parse_coordinate = |value: Option<&str>| {
+            value
+                .and_then(|value| value.parse::<i64>().ok())
+                .ok_or_else(|| format!("Invalid Life 1.06 cell \"{}\" (expected X Y)", line))
+        };
+        let mut values = line.split_whitespace();
+        let x = parse_coordinate(values.next())?;
+        let y = parse_coordinate(values.next())?;
+        if values.next().is_some() {
+            return Err(format!("Invalid Life 1.06 cell \"{}\" (expected X Y)", line));
+        }
+
+        coordinates.push((x, y));
+    }
+
+    let min_x = coordinates.iter().map(|(x, _)| *x).min().unwrap_or(0).min(0);
+    let min_y = coordinates.iter().map(|(_, y)| *y).min().unwrap_or(0).min(0);
+    let offset = |value: i64, min: i64| {
+        value
+            .checked_sub(min)
+            .and_then(|value| usize::try_from(value).ok())
+            .ok_or_else(|| "Life 1.06 pattern is too large".to_string())
+    };
+    let cells = coordinates
+        .iter()
+        .map(|(x, y)| Ok(Point { x: offset(*x, min_x)?, y: offset(*y, min_y)? }))
+        .collect::<Result<_, String>>()?;
+
+    Ok(PatternFile::new(Pattern::new(cells, Dimensions::create_empty())))
+}
+
+/// Write a Life 1.06 file.
+pub fn write(file: &PatternFile) -> String {
+    let mut contents = format!("{}\n", HEADER);
+    for cell in file.pattern.cells() {
+        contents.push_str(&format!("{} {}\n", cell.x, cell.y));
+    }
+
+    contents
+}
+
+#[cfg(test)]
+mod life106_tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn life106_files_are_read() {
+        let file = parse("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n").unwrap();
+
+        assert_eq!(Pattern::from_str(" *\n  *\n***").unwrap(), file.pattern);
+    }
+
+    #[test]
+    fn life106_files_are_written() {
+        let file = PatternFile::new(Pattern::from_str("\n *\n  *\n***").unwrap());
+
+        assert_eq!("#Life 1.06\n1 1\n2 2\n0 3\n1 3\n2 3\n", write(&file));
+    }
+
+    #[test]
+    fn invalid_life106_files_are_rejected() {
+        assert!(parse("#Life 1.05\n*.*").is_err());
+        assert!(parse("#Life 1.06\n1").is_err());
+        assert!(parse("#Life 1.06\n1 2 3").is_err());
+        assert!(parse("#Life 1.06\n1 a").is_err());
+        // The pattern must be small enough to move every cell to a positive
+        // position.
+        assert!(parse("#Life 1.06\n-9223372036854775808 0\n9223372036854775807 0").is_err());
+    }
+}
@@ -0,0 +1,481 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Golly's macrocell format, which stores a pattern as a quadtree so that
+//! repeated parts of the pattern are only stored once.
+//!
+//! See <https://conwaylife.com/wiki/Macrocell>.
+
+use std::{collections::HashMap, str::FromStr};
+
+use xy_utils::{Dimensions, Point};
+
+use crate::game::{pattern_file::PatternFile, Pattern, Rule};
+
+const HEADER: &str = "[M2]";
+
+/// Level of the leaf nodes, which are 8x8 cells.
+pub const LEAF_LEVEL: u32 = 3;
+
+/// Level of the largest node that can be read, so that the position of every
+/// cell in the node fits in a `usize`.
+const MAX_LEVEL: u32 = usize::BITS - 1;
+
+/// The most live cells a macrocell file can have for its quadtree to be
+/// expanded into a pattern. Larger files can only be played with a quadtree.
+pub const MAX_EXPANDED_CELLS: u64 = 1 << 22;
+
+/// A node of the quadtree in a macrocell file.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum MacrocellNode {
+    /// An 8x8 square of cells, holding the position of each live cell.
+    Leaf(Vec<Point>),
+
+    /// A square of four nodes (north-west, north-east, south-west and
+    /// south-east), each of which is the number of the node or zero if it is
+    /// empty.
+    Branch { level: u32, children: [usize; 4] },
+}
+
+impl MacrocellNode {
+    /// Get the level of the node. A node of level `n` is `2^n` cells wide.
+    pub fn level(&self) -> u32 {
+        match self {
+            MacrocellNode::Leaf(_) => LEAF_LEVEL,
+            MacrocellNode::Branch { level, .. } => *level,
+        }
+    }
+}
+
+/// Read a macrocell file without expanding the quadtree, so that patterns far
+/// too large to hold cell by cell can be loaded.
+///
+/// ## Returns
+///
+/// The file (with an empty pattern), and the nodes of the quadtree. Nodes are
+/// numbered from one, so the first node is always `None` (the empty node), and
+/// the root is the last node.
+pub fn read_tree(contents: &str) -> Result<(PatternFile, Vec<Option<MacrocellNode>>), String> {
+    let mut file = PatternFile::new(Pattern::new(vec![], Dimensions::create_empty()));
+    let mut lines = contents.lines().map(str::trim).filter(|line| !line.is_empty());
+    if !lines.next().is_some_and(|line| line.starts_with(HEADER)) {
+        return Err(format!("Unsupported macrocell file (expected the \"{}\" header)", HEADER));
+    }
+
+    let mut nodes = vec![None];
+    for line in lines {
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut chars = comment.chars();
+            let kind = chars.next();
+            let text = chars.as_str().strip_prefix(' ').unwrap_or(chars.as_str()).to_string();
+            match kind {
+                Some('R') => file.rule = Some(Rule::from_str(&text)?),
+                Some('N') => file.name = Some(text),
+                Some('O') => file.author = Some(text),
+                Some('C' | 'D') => file.comments.push(text),
+                _ => {}
+            }
+        } else if line.starts_with(['.', '*', '$']) {
+            nodes.push(Some(parse_leaf(line)?));
+        } else {
+            nodes.push(Some(parse_branch(line, &nodes)?));
+        }
+    }
+
+    Ok((file, nodes))
+}
+
+/// Read a macrocell file, expanding its quadtree into the file's pattern.
+///
+/// ## Returns
+///
+/// The file, or an error if it cannot be read or has more than
+/// `MAX_EXPANDED_CELLS` live cells.
+pub fn parse(contents: &str) -> Result<PatternFile, String> {
+    let (file, nodes) = read_tree(contents)?;
+    let cells = count_cells(&nodes);
+    if cells > MAX_EXPANDED_CELLS {
+        return Err(format!(
+            "The macrocell pattern is too large to expand ({} cells, the most is {})",
+            cells, MAX_EXPANDED_CELLS
+        ));
+    }
+
+    Ok(expand_tree(file, &nodes))
+}
+
+/// Expand the quadtree of a macrocell file into the file's pattern.
+///
+/// ## Arguments
+///
+/// * `file`: The file, as returned by `read_tree()`.
+/// * `nodes`: The nodes of the file's quadtree.
+pub fn expand_tree(mut file: PatternFile, nodes: &[Option<MacrocellNode>]) -> PatternFile {
+    let mut cells = vec![];
+    if nodes.len() > 1 {
+        expand(nodes, nodes.len() - 1, Point { x: 0, y: 0 }, &mut cells);
+    }
+
+    file.pattern = Pattern::new(cells, Dimensions::create_empty());
+    file
+}
+
+/// Count the live cells of a quadtree without expanding it, so that the cost
+/// of expanding it can be checked first. The count saturates at `u64::MAX`.
+pub fn count_cells(nodes: &[Option<MacrocellNode>]) -> u64 {
+    // Children come before their parents, so each node's count can be found
+    // from the counts before it.
+    let mut counts: Vec<u64> = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let count = match node {
+            None => 0,
+            Some(MacrocellNode::Leaf(cells)) => cells.len() as u64,
+            Some(MacrocellNode::Branch { children, .. }) => {
+                children.iter().fold(0, |count: u64, child| count.saturating_add(counts[*child]))
+            }
+        };
+        counts.push(count);
+    }
+
+    counts.last().copied().unwrap_or(0)
+}
+
+/// Read a leaf node (e.g. `.*$..*$***$`).
+fn parse_leaf(line: &str) -> Result<MacrocellNode, String> {
+    let size = 1 << LEAF_LEVEL;
+    let mut cells = vec![];
+    let mut cell = Point { x: 0, y: 0 };
+    for c in line.chars() {
+        match c {
+            '.' => cell.x += 1,
+            '*' => {
+                cells.push(cell);
+                cell.x += 1;
+            }
+            '$' => cell = Point { x: 0, y: cell.y + 1 },
+            _ => return Err(format!("Invalid character '{}' in macrocell leaf", c)),
+        }
+        if cell.x > size || cell.y > size || (c == '*' && cell.y == size) {
+            return Err(format!("Macrocell leaf \"{}\" is larger than 8x8", line));
+        }
+    }
+
+    Ok(MacrocellNode::Leaf(cells))
+}
+
+/// Read a branch node (e.g. `4 0 1 2 0`), checking that it is above the leaves,
+/// and that its children come before it and are one level lower.
+fn parse_branch(line: &str, nodes: &[Option<MacrocellNode>]) -> Result<MacrocellNode, String> {
+    let invalid = || format!("Invalid macrocell node \"{}\"", line);
+    let values = line
+        .split_whitespace()
+        .map(|value| value.parse::<usize>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let [level, nw, ne, sw, se] = values[..] else {
+        return Err(invalid());
+    };
+
+    let level = u32::try_from(level).map_err(|_| invalid())?;
+    if level <= LEAF_LEVEL {
+        return Err(invalid());
+    }
+    if level > MAX_LEVEL {
+        return Err(format!("Macrocell node \"{}\" is too large", line));
+    }
+
+    let children = [nw, ne, sw, se];
+    for child in children {
+        match nodes.get(child) {
+            Some(None) => {}
+            Some(Some(node)) if node.level() + 1 == level => {}
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(MacrocellNode::Branch { level, children })
+}
+
+/// Add the live cells of a node to a list of cells.
+///
+/// ## Arguments
+///
+/// * `nodes`: Every node in the file.
+/// * `index`: The node to expand.
+/// * `position`: Position of the top-left corner of the node.
+/// * `cells`: List that the live cells are added to.
+fn expand(nodes: &[Option<MacrocellNode>], index: usize, position: Point, cells: &mut Vec<Point>) {
+    match &nodes[index] {
+        None => {}
+        Some(MacrocellNode::Leaf(leaf)) => {
+            cells.extend(
+                leaf.iter().map(|cell| Point { x: position.x + cell.x, y: position.y + cell.y }),
+            );
+        }
+        Some(MacrocellNode::Branch { level, children }) => {
+            let half = 1 << (level - 1);
+            let offsets = [(0, 0), (half, 0), (0, half), (half, half)];
+            for (child, (x, y)) in children.iter().zip(offsets) {
+                expand(nodes, *child, Point { x: position.x + x, y: position.y + y }, cells);
+            }
+        }
+    }
+}
+
+/// Write a macrocell file. The top-left corner of the pattern is placed at the
+/// top-left corner of the quadtree.
+pub fn write(file: &PatternFile) -> String {
+    let mut contents = write_header(file);
+    let size = file.pattern.size();
+    let mut level = LEAF_LEVEL;
+    while (1 << level) < size.width.max(size.height) {
+        level += 1;
+    }
+
+    let mut writer = NodeWriter::default();
+    writer.write_node(level, Point { x: 0, y: 0 }, file.pattern.cells().to_vec());
+    contents.push_str(&writer.lines);
+    contents
+}
+
+/// Write a macrocell file from a quadtree without expanding it, so that
+/// patterns far too large to hold cell by cell can be written.
+///
+/// ## Arguments
+///
+/// * `file`: The file, whose pattern is ignored.
+/// * `nodes`: The nodes of the quadtree, as returned by `read_tree()`.
+pub fn write_tree(file: &PatternFile, nodes: &[Option<MacrocellNode>]) -> String {
+    let mut contents = write_header(file);
+    for node in nodes.iter().flatten() {
+        contents.push_str(&format_node(node));
+        contents.push('\n');
+    }
+    contents
+}
+
+/// Write the header line and the comments of a macrocell file.
+fn write_header(file: &PatternFile) -> String {
+    let mut contents = format!("{} (game_of_life {})\n", HEADER, env!("CARGO_PKG_VERSION"));
+    if let Some(rule) = file.rule {
+        contents.push_str(&format!("#R {}\n", rule));
+    }
+    if let Some(name) = &file.name {
+        contents.push_str(&format!("#N {}\n", name));
+    }
+    if let Some(author) = &file.author {
+        contents.push_str(&format!("#O {}\n", author));
+    }
+    for comment in &file.comments {
+        contents.push_str(&format!("#C {}\n", comment));
+    }
+    contents
+}
+
+/// Format a node as a line of a macrocell file.
+fn format_node(node: &MacrocellNode) -> String {
+    match node {
+        MacrocellNode::Leaf(cells) => format_leaf(cells),
+        MacrocellNode::Branch { level, children: [nw, ne, sw, se] } => {
+            format!("{} {} {} {} {}", level, nw, ne, sw, se)
+        }
+    }
+}
+
+/// Format the cells of a leaf. Dead cells at the end of each row, and empty
+/// rows at the end of the leaf, are left out.
+fn format_leaf(cells: &[Point]) -> String {
+    let mut rows = vec![String::new(); 1 << LEAF_LEVEL];
+    for cell in cells {
+        let row = &mut rows[cell.y];
+        while row.len() < cell.x {
+            row.push('.');
+        }
+        row.push('*');
+    }
+
+    let last_row = rows.iter().rposition(|row| !row.is_empty()).unwrap_or_default();
+    rows[..=last_row].iter().map(|row| format!("{}$", row)).collect()
+}
+
+/// Writes the nodes of a quadtree, writing each distinct node once.
+#[derive(Default)]
+struct NodeWriter {
+    lines: String,
+    line_numbers: HashMap<MacrocellNode, usize>,
+}
+
+impl NodeWriter {
+    /// Write a node and its children, if they have not been written already.
+    ///
+    /// ## Returns
+    ///
+    /// The line number of the node, or zero if it is empty.
+    fn write_node(&mut self, level: u32, position: Point, cells: Vec<Point>) -> usize {
+        if cells.is_empty() {
+            return 0;
+        }
+
+        let node = if level == LEAF_LEVEL {
+            let cells =
+                cells.iter().map(|cell| Point { x: cell.x - position.x, y: cell.y - position.y });
+            MacrocellNode::Leaf(cells.collect())
+        } else {
+            let half = 1 << (level - 1);
+            let mut quadrants = [vec![], vec![], vec![], vec![]];
+            for cell in cells {
+                let east = cell.x >= position.x + half;
+                let south = cell.y >= position.y + half;
+                quadrants[usize::from(east) + 2 * usize::from(south)].push(cell);
+            }
+
+            let offsets = [(0, 0), (half, 0), (0, half), (half, half)];
+            let mut children = [0; 4];
+            for (i, (quadrant, (x, y))) in quadrants.into_iter().zip(offsets).enumerate() {
+                let child_position = Point { x: position.x + x, y: position.y + y };
+                children[i] = self.write_node(level - 1, child_position, quadrant);
+            }
+
+            MacrocellNode::Branch { level, children }
+        };
+
+        if let Some(line_number) = self.line_numbers.get(&node) {
+            return *line_number;
+        }
+
+        self.lines.push_str(&format_node(&node));
+        self.lines.push('\n');
+
+        let line_number = self.line_numbers.len() + 1;
+        self.line_numbers.insert(node, line_number);
+        line_number
+    }
+}
+
+#[cfg(test)]
+mod macrocell_tests {
+    use super::*;
+
+    #[test]
+    fn macrocell_files_are_read() {
+        let file = parse(concat!(
+            "[M2] (golly 4.2)\n",
+            "#R B3/S23\n",
+            "#C A glider in the south-east corner.\n",
+            ".*$..*$***$\n",
+            "4 0 0 0 1\n"
+        ))
+        .unwrap();
+
+        assert_eq!(Some(Rule::CONWAY), file.rule);
+        assert_eq!(vec!["A glider in the south-east corner.".to_string()], file.comments);
+        let expected =
+            [(9, 8), (10, 9), (8, 10), (9, 10), (10, 10)].map(|(x, y)| Point { x, y }).to_vec();
+        assert_eq!(expected, file.pattern.cells());
+    }
+
+    #[test]
+    fn cells_are_counted_without_expanding_the_tree() {
+        let mut contents = "[M2]\n**$**$\n4 1 1 1 1\n".to_string();
+        for level in 5..=40 {
+            contents.push_str(&format!("{} {} {} {} {}\n", level, level - 3, 0, 0, level - 3));
+        }
+        let (_, nodes) = read_tree(&contents).unwrap();
+
+        assert_eq!(16 << 36, count_cells(&nodes));
+        assert_eq!(0, count_cells(&read_tree("[M2]\n").unwrap().1));
+    }
+
+    #[test]
+    fn patterns_too_large_to_expand_are_rejected() {
+        let mut contents = "[M2]\n**$**$\n4 1 1 1 1\n".to_string();
+        for level in 5..=40 {
+            contents.push_str(&format!("{} {} {} {} {}\n", level, level - 3, 0, 0, level - 3));
+        }
+
+        assert!(parse(&contents).is_err());
+        assert!(read_tree(&contents).is_ok());
+    }
+
+    #[test]
+    fn repeated_nodes_are_written_once() {
+        // Four blocks, one in each corner of a 16x16 square.
+        let cells = [(0, 0), (14, 0), (0, 14), (14, 14)]
+            .iter()
+            .flat_map(|(x, y)| {
+                [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(i, j)| Point { x: x + i, y: y + j })
+            })
+            .collect();
+        let file = PatternFile::new(Pattern::new(cells, Dimensions::create_empty()));
+
+        assert_eq!(
+            concat!(
+                "**$**$\n",
+                "......**$......**$\n",
+                "$$$$$$**$**$\n",
+                "$$$$$$......**$......**$\n",
+                "4 1 2 3 4\n"
+            ),
+            write(&file).split_once('\n').unwrap().1
+        );
+    }
+
+    #[test]
+    fn repeated_leaves_share_a_line() {
+        let cells = [(0, 0), (8, 0), (0, 8), (8, 8)].map(|(x, y)| Point { x, y }).to_vec();
+        let file = PatternFile::new(Pattern::new(cells, Dimensions::create_empty()));
+
+        assert_eq!("*$\n4 1 1 1 1\n", write(&file).split_once('\n').unwrap().1);
+    }
+
+    #[test]
+    fn trees_are_written_without_expanding_them() {
+        let mut contents = "[M2]\n#N Blocks\n**$**$\n4 1 1 1 1\n".to_string();
+        for level in 5..=40 {
+            contents.push_str(&format!("{} {} {} {} {}\n", level, level - 3, 0, 0, level - 3));
+        }
+        let (file, nodes) = read_tree(&contents).unwrap();
+
+        let written = write_tree(&file, &nodes);
+        assert_eq!(contents.split_once('\n').unwrap().1, written.split_once('\n').unwrap().1);
+        assert_eq!(nodes, read_tree(&written).unwrap().1);
+    }
+
+    #[test]
+    fn invalid_macrocell_files_are_rejected() {
+        assert!(parse("").is_err());
+        assert!(parse("x = 3, y = 3\nbo$!").is_err());
+        // Children must come before their parent.
+        assert!(parse("[M2]\n4 0 0 0 2\n*$").is_err());
+        // Children must be one level below their parent.
+        assert!(parse("[M2]\n*$\n5 1 0 0 0").is_err());
+        assert!(parse("[M2]\n*$\n4 1 0 0").is_err());
+        assert!(parse("[M2]\n.*x$").is_err());
+        assert!(parse("[M2]\n.........*$").is_err());
+        assert!(parse("[M2]\n$$$$$$$$*$").is_err());
+        // Branches must be above the leaves.
+        assert!(parse("[M2]\n0 0 0 0 0").is_err());
+        assert!(parse("[M2]\n3 0 0 0 0").is_err());
+        // Branches must be small enough to address every cell.
+        assert!(parse("[M2]\n64 0 0 0 0").is_err());
+        assert!(parse("[M2]\n99999999999 0 0 0 0").is_err());
+    }
+}
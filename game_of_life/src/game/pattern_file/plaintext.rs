@@ -0,0 +1,142 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The plaintext (`.cells`) format.
+//!
+//! See <https://conwaylife.com/wiki/Plaintext>.
+
+use xy_utils::{Dimensions, Point};
+
+use crate::game::{pattern_file::PatternFile, Pattern};
+
+const NAME_PREFIX: &str = "Name: ";
+const AUTHOR_PREFIX: &str = "Author: ";
+
+/// Read a plaintext file.
+pub fn parse(contents: &str) -> Result<PatternFile, String> {
+    let mut file = PatternFile::new(Pattern::new(vec![], Dimensions::create_empty()));
+    let mut cells = vec![];
+    let mut size = Dimensions::create_empty();
+
+    for line in contents.lines() {
+        if let Some(comment) = line.strip_prefix('!') {
+            if let Some(name) = comment.strip_prefix(NAME_PREFIX) {
+                file.name = Some(name.to_string());
+            } else if let Some(author) = comment.strip_prefix(AUTHOR_PREFIX) {
+                file.author = Some(author.to_string());
+            } else {
+                file.comments.push(comment.to_string());
+            }
+            continue;
+        }
+
+        let y = size.height;
+        size.height += 1;
+        size.width = size.width.max(line.trim_end().chars().count());
+        for (x, c) in line.trim_end().chars().enumerate() {
+            match c {
+                'O' | '*' => cells.push(Point { x, y }),
+                '.' => {}
+                _ => {
+                    return Err(format!(
+                        "Invalid character '{}' in plaintext pattern (row {}, column {})",
+                        c,
+                        y + 1,
+                        x + 1
+                    ))
+                }
+            }
+        }
+    }
+
+    file.pattern = Pattern::new(cells, size);
+    Ok(file)
+}
+
+/// Write a plaintext file. Every row is written at the full width of the
+/// pattern, so that the size of the pattern is kept.
+pub fn write(file: &PatternFile) -> String {
+    let mut contents = String::new();
+    if let Some(name) = &file.name {
+        contents.push_str(&format!("!{}{}\n", NAME_PREFIX, name));
+    }
+    if let Some(author) = &file.author {
+        contents.push_str(&format!("!{}{}\n", AUTHOR_PREFIX, author));
+    }
+    for comment in &file.comments {
+        contents.push_str(&format!("!{}\n", comment));
+    }
+
+    let size = file.pattern.size();
+    let mut rows = vec![vec!['.'; size.width]; size.height];
+    for cell in file.pattern.cells() {
+        rows[cell.y][cell.x] = 'O';
+    }
+    for row in rows {
+        contents.extend(row);
+        contents.push('\n');
+    }
+
+    contents
+}
+
+#[cfg(test)]
+mod plaintext_tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const GLIDER: &str = concat!(
+        "!Name: Glider\n",
+        "!Author: Richard K. Guy\n",
+        "!The smallest, most common, and first discovered spaceship.\n",
+        "!\n",
+        ".O.\n",
+        "..O\n",
+        "OOO\n"
+    );
+
+    #[test]
+    fn plaintext_files_are_read() {
+        let file = parse(GLIDER).unwrap();
+
+        assert_eq!(Pattern::from_str(" *\n  *\n***").unwrap(), file.pattern);
+        assert_eq!(Some("Glider".to_string()), file.name);
+        assert_eq!(Some("Richard K. Guy".to_string()), file.author);
+        assert_eq!(
+            vec![
+                "The smallest, most common, and first discovered spaceship.".to_string(),
+                "".to_string()
+            ],
+            file.comments
+        );
+    }
+
+    #[test]
+    fn plaintext_files_are_written() {
+        assert_eq!(GLIDER, write(&parse(GLIDER).unwrap()));
+    }
+
+    #[test]
+    fn invalid_plaintext_files_are_rejected() {
+        assert!(parse(".O.\n.o.").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The run length encoded (RLE) format.
+//!
+//! See <https://conwaylife.com/wiki/Run_Length_Encoded>.
+
+use std::str::FromStr;
+
+use xy_utils::{Dimensions, Point};
+
+use crate::game::{pattern_file::PatternFile, Pattern, Rule};
+
+/// Longest line that is written, as recommended by the format.
+const MAX_LINE_LENGTH: usize = 70;
+
+/// Read an RLE file.
+pub fn parse(contents: &str) -> Result<PatternFile, String> {
+    let mut file = PatternFile::new(Pattern::new(vec![], Dimensions::create_empty()));
+    let mut lines = contents.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    // Comments come before the header line.
+    let header = loop {
+        let Some(line) = lines.next() else {
+            return Err("RLE file has no header line (e.g. \"x = 3, y = 3\")".to_string());
+        };
+        let Some(comment) = line.strip_prefix('#') else {
+            break line;
+        };
+
+        let mut chars = comment.chars();
+        let kind = chars.next();
+        let text = chars.as_str().strip_prefix(' ').unwrap_or(chars.as_str()).to_string();
+        match kind {
+            Some('N') => file.name = Some(text),
+            Some('O') => file.author = Some(text),
+            Some('C' | 'c') => file.comments.push(text),
+            Some('r') => file.rule = Some(Rule::from_str(&text)?),
+            // Other lines (such as pattern offsets) have no equivalent in
+            // other formats.
+            _ => {}
+        }
+    };
+
+    let mut size = Dimensions::create_empty();
+    for field in header.split(',') {
+        let Some((key, value)) = field.split_once('=') else {
+            return Err(format!("Invalid RLE header \"{}\"", header));
+        };
+        let value = value.trim();
+        let parse_size = |value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|error| format!("Invalid RLE size \"{}\": {}", value, error))
+        };
+        match key.trim() {
+            "x" => size.width = parse_size(value)?,
+            "y" => size.height = parse_size(value)?,
+            "rule" => file.rule = Some(Rule::from_str(value)?),
+            _ => {}
+        }
+    }
+
+    let too_large = || "RLE pattern is too large".to_string();
+    let mut cells = vec![];
+    let mut position = Point { x: 0, y: 0 };
+    let mut run_length: Option<usize> = None;
+    'lines: for line in lines {
+        for c in line.chars() {
+            match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).unwrap_or_default() as usize;
+                    run_length = run_length
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|run_length| run_length.checked_add(digit))
+                        .map(Some)
+                        .ok_or_else(too_large)?;
+                    continue;
+                }
+                'b' | '.' => {
+                    position.x =
+                        position.x.checked_add(run_length.unwrap_or(1)).ok_or_else(too_large)?
+                }
+                'o' | 'A'..='X' => {
+                    let end =
+                        position.x.checked_add(run_length.unwrap_or(1)).ok_or_else(too_large)?;
+                    cells.extend((position.x..end).map(|x| Point { x, y: position.y }));
+                    position.x = end;
+                }
+                '$' => {
+                    let y =
+                        position.y.checked_add(run_length.unwrap_or(1)).ok_or_else(too_large)?;
+                    position = Point { x: 0, y };
+                }
+                '!' => break 'lines,
+                c if c.is_whitespace() => continue,
+                _ => return Err(format!("Invalid character '{}' in RLE pattern", c)),
+            }
+            run_length = None;
+        }
+    }
+
+    file.pattern = Pattern::new(cells, size);
+    Ok(file)
+}
+
+/// Write an RLE file.
+pub fn write(file: &PatternFile) -> String {
+    let mut contents = String::new();
+    if let Some(name) = &file.name {
+        contents.push_str(&format!("#N {}\n", name));
+    }
+    if let Some(author) = &file.author {
+        contents.push_str(&format!("#O {}\n", author));
+    }
+    for comment in &file.comments {
+        contents.push_str(&format!("#C {}\n", comment));
+    }
+
+    let size = file.pattern.size();
+    contents.push_str(&format!("x = {}, y = {}", size.width, size.height));
+    if let Some(rule) = file.rule {
+        contents.push_str(&format!(", rule = {}", rule));
+    }
+    contents.push('\n');
+
+    let mut lines = LineWrapper::default();
+    let mut cells = file.pattern.cells().iter().peekable();
+    let mut position = Point { x: 0, y: 0 };
+    while let Some(first_cell) = cells.next() {
+        if first_cell.y > position.y {
+            lines.push_run(first_cell.y - position.y, '$');
+            position = Point { x: 0, y: first_cell.y };
+        }
+        if first_cell.x > position.x {
+            lines.push_run(first_cell.x - position.x, 'b');
+        }
+
+        // Each run of live cells ends at a dead cell or the end of the row.
+        let mut run_length = 1;
+        while cells
+            .next_if(|cell| cell.y == first_cell.y && cell.x == first_cell.x + run_length)
+            .is_some()
+        {
+            run_length += 1;
+        }
+        lines.push_run(run_length, 'o');
+        position.x = first_cell.x + run_length;
+    }
+    lines.push_run(1, '!');
+
+    contents.push_str(&lines.finish());
+    contents
+}
+
+/// Builds the lines of an RLE pattern, without splitting any runs across
+/// lines.
+#[derive(Default)]
+struct LineWrapper {
+    lines: String,
+    line_length: usize,
+}
+
+impl LineWrapper {
+    fn push_run(&mut self, run_length: usize, tag: char) {
+        let run = if run_length == 1 { tag.to_string() } else { format!("{}{}", run_length, tag) };
+        if self.line_length + run.len() > MAX_LINE_LENGTH {
+            self.lines.push('\n');
+            self.line_length = 0;
+        }
+
+        self.lines.push_str(&run);
+        self.line_length += run.len();
+    }
+
+    fn finish(mut self) -> String {
+        self.lines.push('\n');
+        self.lines
+    }
+}
+
+#[cfg(test)]
+mod rle_tests {
+    use super::*;
+
+    const GLIDER: &str = concat!(
+        "#N Glider\n",
+        "#O Richard K. Guy\n",
+        "#C The smallest, most common, and first discovered spaceship.\n",
+        "x = 3, y = 3, rule = B3/S23\n",
+        "bob$2bo$3o!\n"
+    );
+
+    #[test]
+    fn rle_files_are_read() {
+        let file = parse(GLIDER).unwrap();
+
+        assert_eq!(Pattern::from_str(" *\n  *\n***").unwrap(), file.pattern);
+        assert_eq!(Some("Glider".to_string()), file.name);
+        assert_eq!(Some("Richard K. Guy".to_string()), file.author);
+        assert_eq!(
+            vec!["The smallest, most common, and first discovered spaceship.".to_string()],
+            file.comments
+        );
+        assert_eq!(Some(Rule::CONWAY), file.rule);
+    }
+
+    #[test]
+    fn rle_files_are_written() {
+        // Dead cells at the end of a row are left out.
+        let expected = GLIDER.replace("bob$", "bo$");
+        assert_eq!(expected, write(&parse(GLIDER).unwrap()));
+    }
+
+    #[test]
+    fn runs_of_blank_rows_and_long_patterns_are_read() {
+        let file = parse("x = 0, y = 0\n3o2$\n  2b\n3o!").unwrap();
+
+        assert_eq!(Pattern::from_str("***\n\n  ***").unwrap(), file.pattern);
+        assert_eq!(None, file.rule);
+    }
+
+    #[test]
+    fn long_lines_are_wrapped() {
+        let cells = (0..100).map(|x| Point { x: x * 2, y: 0 }).collect();
+        let written = write(&PatternFile::new(Pattern::new(cells, Dimensions::create_empty())));
+
+        // The header, and 200 runs of one cell each (including the `!`).
+        assert!(written.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert_eq!(4, written.lines().count());
+    }
+
+    #[test]
+    fn invalid_rle_files_are_rejected() {
+        assert!(parse("").is_err());
+        assert!(parse("#C Just a comment").is_err());
+        assert!(parse("x = a, y = 3\n!").is_err());
+        assert!(parse("x = 3, y = 3, rule = B3\n!").is_err());
+        assert!(parse("x = 3, y = 3\n3z!").is_err());
+        // Runs must be small enough to address every cell.
+        assert!(parse("x = 3, y = 3\n99999999999999999999999o!").is_err());
+        assert!(parse("x = 3, y = 3\n18446744073709551615bo!").is_err());
+        assert!(parse("x = 3, y = 3\no$18446744073709551615$o!").is_err());
+    }
+}
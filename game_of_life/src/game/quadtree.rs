@@ -0,0 +1,800 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! An unbounded game of life, stored as a quadtree and run with the HashLife
+//! algorithm.
+//!
+//! Identical nodes of the tree are only stored once, so patterns with billions
+//! of cells can be held as long as they are made up of repeated parts. See
+//! <https://conwaylife.com/wiki/HashLife>.
+
+use std::collections::HashMap;
+
+use xy_utils::{Dimensions, Point};
+
+use crate::game::{
+    pattern_file::macrocell::{MacrocellNode, LEAF_LEVEL},
+    Cell, Rule,
+};
+
+/// Identifies a node in the tree's node store.
+type NodeId = u32;
+
+/// The dead cell, which is the empty node of level zero.
+const DEAD: NodeId = 0;
+
+/// The live cell.
+const ALIVE: NodeId = 1;
+
+/// Smallest level of the root, so that the root can always be split into
+/// levels that the base case of the algorithm handles.
+const MIN_ROOT_LEVEL: u32 = 3;
+
+/// Largest level of a macrocell file's quadtree, leaving room in the `i64`
+/// positions of the cells for the pattern to grow as it is run.
+const MAX_MACROCELL_LEVEL: u32 = 60;
+
+/// Number of nodes that can be stored before unused nodes are discarded.
+const MAX_NODES: usize = 1 << 24;
+
+/// A node of level `n` is a square of `2^n` by `2^n` cells, made up of four
+/// nodes of level `n - 1`. Nodes of level zero are single cells.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Branch {
+    level: u32,
+
+    /// North-west, north-east, south-west and south-east children.
+    children: [NodeId; 4],
+}
+
+#[derive(Clone, Copy)]
+struct Node {
+    branch: Branch,
+    population: u64,
+}
+
+/// An unbounded game of life.
+pub struct QuadTree {
+    /// Every node, indexed by its ID.
+    nodes: Vec<Node>,
+
+    /// The ID of every node, so that each node is only stored once.
+    ids: HashMap<Branch, NodeId>,
+
+    /// The empty node of each level.
+    empty_nodes: Vec<NodeId>,
+
+    /// The result of running each node (see `successor()`).
+    successors: HashMap<(NodeId, u32), NodeId>,
+
+    /// The node containing every live cell.
+    root: NodeId,
+
+    /// Position of the top-left corner of the root.
+    origin: (i64, i64),
+
+    rule: Rule,
+}
+
+impl QuadTree {
+    /// Create an empty game.
+    ///
+    /// ## Arguments
+    ///
+    /// * `rule`: The rule to play by. Rules where dead cells with no live
+    ///   neighbours are born (`B0`) cannot be played, as the plane would fill
+    ///   with live cells.
+    pub fn new(rule: Rule) -> Result<QuadTree, String> {
+        if rule.next_state(Cell::Dead, 0) == Cell::Alive {
+            return Err(format!("Rules with B0 cannot be played on an unbounded plane ({})", rule));
+        }
+
+        let leaf =
+            |population| Node { branch: Branch { level: 0, children: [DEAD; 4] }, population };
+        let mut tree = QuadTree {
+            nodes: vec![leaf(0), leaf(1)],
+            ids: HashMap::new(),
+            empty_nodes: vec![DEAD],
+            successors: HashMap::new(),
+            root: DEAD,
+            origin: (0, 0),
+            rule,
+        };
+        tree.root = tree.empty_node(MIN_ROOT_LEVEL);
+
+        Ok(tree)
+    }
+
+    /// Play by a different rule from now on.
+    ///
+    /// ## Arguments
+    ///
+    /// * `rule`: The new rule. Rules with `B0` are rejected, as in `new()`.
+    pub fn set_rule(&mut self, rule: Rule) -> Result<(), String> {
+        if rule.next_state(Cell::Dead, 0) == Cell::Alive {
+            return Err(format!("Rules with B0 cannot be played on an unbounded plane ({})", rule));
+        }
+
+        // The stored successors were calculated with the old rule.
+        self.rule = rule;
+        self.successors.clear();
+        Ok(())
+    }
+
+    /// Create a game from the quadtree of a macrocell file, without expanding
+    /// the tree.
+    ///
+    /// ## Arguments
+    ///
+    /// * `nodes`: The nodes of the macrocell file (see
+    ///   `macrocell::read_tree()`).
+    /// * `position`: Position of the top-left corner of the file's quadtree.
+    /// * `rule`: The rule to play by.
+    pub fn from_macrocell(
+        nodes: &[Option<MacrocellNode>],
+        position: (i64, i64),
+        rule: Rule,
+    ) -> Result<QuadTree, String> {
+        let mut tree = QuadTree::new(rule)?;
+        let invalid = |index: usize| format!("Invalid macrocell node {}", index);
+
+        // Each macrocell node refers to earlier nodes, so they can be added in
+        // order. Empty children are given the correct level as they are used.
+        let mut ids = vec![None; nodes.len()];
+        for (index, node) in nodes.iter().enumerate() {
+            ids[index] = match node {
+                None => None,
+                Some(MacrocellNode::Leaf(cells)) => {
+                    let size = 1 << LEAF_LEVEL;
+                    if cells.iter().any(|cell| cell.x >= size || cell.y >= size) {
+                        return Err(invalid(index));
+                    }
+
+                    let mut leaf = tree.empty_node(LEAF_LEVEL);
+                    for cell in cells {
+                        leaf = tree.set_cell_in(leaf, cell.x as u64, cell.y as u64);
+                    }
+                    Some(leaf)
+                }
+                Some(MacrocellNode::Branch { level, children }) => {
+                    if *level <= LEAF_LEVEL {
+                        return Err(invalid(index));
+                    }
+                    if *level > MAX_MACROCELL_LEVEL {
+                        return Err(format!(
+                            "Macrocell node {} is too large (level {}, the largest is {})",
+                            index, level, MAX_MACROCELL_LEVEL
+                        ));
+                    }
+
+                    let mut child_ids = [DEAD; 4];
+                    let mut population: u64 = 0;
+                    for (child_id, child) in child_ids.iter_mut().zip(children) {
+                        *child_id = match nodes.get(*child) {
+                            Some(None) => tree.empty_node(level - 1),
+                            Some(Some(node)) if *child < index && node.level() + 1 == *level => {
+                                ids[*child].ok_or_else(|| invalid(index))?
+                            }
+                            _ => return Err(invalid(index)),
+                        };
+                        population =
+                            population.checked_add(tree.population_of(*child_id)).ok_or_else(
+                                || format!("Macrocell node {} has too many cells", index),
+                            )?;
+                    }
+
+                    let [nw, ne, sw, se] = child_ids;
+                    Some(tree.join(nw, ne, sw, se))
+                }
+            };
+        }
+
+        if let Some(root) = ids.last().copied().flatten() {
+            let size = 1i64 << tree.level(root);
+            if position.0.checked_add(size).is_none() || position.1.checked_add(size).is_none() {
+                return Err("The macrocell pattern does not fit at its position".to_string());
+            }
+
+            tree.root = root;
+            tree.origin = position;
+            while tree.level(tree.root) < MIN_ROOT_LEVEL {
+                tree.expand();
+            }
+        }
+
+        Ok(tree)
+    }
+
+    /// Get the number of live cells.
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root as usize].population
+    }
+
+    /// Bring a cell to life.
+    pub fn set_cell(&mut self, x: i64, y: i64) {
+        while !self.contains(x, y) {
+            self.expand();
+        }
+
+        let root = self.root;
+        self.root = self.set_cell_in(root, (x - self.origin.0) as u64, (y - self.origin.1) as u64);
+    }
+
+    /// Get the live cells in a rectangle.
+    ///
+    /// ## Arguments
+    ///
+    /// * `top_left`: Position of the top-left corner of the rectangle.
+    /// * `size`: Size of the rectangle.
+    ///
+    /// ## Returns
+    ///
+    /// The position of each live cell relative to the top-left corner of the
+    /// rectangle, in row-major order.
+    pub fn live_cells_in(&self, top_left: (i64, i64), size: Dimensions) -> Vec<Point> {
+        let mut cells = vec![];
+        let bottom_right = (top_left.0 + size.width as i64, top_left.1 + size.height as i64);
+        self.collect_cells(self.root, self.origin, (top_left, bottom_right), &mut cells);
+
+        cells.sort_by_key(|cell| (cell.y, cell.x));
+        cells
+    }
+
+    /// Get the smallest rectangle containing every live cell, as the top-left
+    /// and bottom-right cells (inclusive). `None` if there are no live cells.
+    pub fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        let mut bounds = HashMap::new();
+        let (min_x, min_y, max_x, max_y) = self.bounds(self.root, &mut bounds)?;
+        let (x, y) = self.origin;
+        Some(((x + min_x as i64, y + min_y as i64), (x + max_x as i64, y + max_y as i64)))
+    }
+
+    /// Run the game.
+    ///
+    /// ## Arguments
+    ///
+    /// * `generations_log2`: The number of generations to run, as a power of
+    ///   two. Large steps are much faster than running one generation at a
+    ///   time.
+    pub fn step(&mut self, generations_log2: u32) {
+        // The root is grown until every live cell is in its central sixteenth,
+        // so that nothing can escape the half of the root that the successor
+        // covers.
+        loop {
+            let level = self.level(self.root);
+            let centre = self.centre(self.root);
+            let centre = self.centre(centre);
+            if level >= generations_log2 + MIN_ROOT_LEVEL
+                && self.population_of(centre) == self.population()
+            {
+                break;
+            }
+            self.expand();
+        }
+
+        let level = self.level(self.root);
+        self.root = self.successor(self.root, generations_log2);
+        let offset = 1 << (level - 2);
+        self.origin = (self.origin.0 + offset, self.origin.1 + offset);
+
+        self.shrink();
+        if self.nodes.len() > MAX_NODES {
+            self.collect_garbage();
+        }
+    }
+
+    fn level(&self, node: NodeId) -> u32 {
+        self.nodes[node as usize].branch.level
+    }
+
+    fn population_of(&self, node: NodeId) -> u64 {
+        self.nodes[node as usize].population
+    }
+
+    fn children(&self, node: NodeId) -> [NodeId; 4] {
+        self.nodes[node as usize].branch.children
+    }
+
+    /// Find or create the node with the given children.
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let branch = Branch { level: self.level(nw) + 1, children: [nw, ne, sw, se] };
+        if let Some(id) = self.ids.get(&branch) {
+            return *id;
+        }
+
+        let population = branch.children.iter().map(|child| self.population_of(*child)).sum();
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node { branch, population });
+        self.ids.insert(branch, id);
+        id
+    }
+
+    fn empty_node(&mut self, level: u32) -> NodeId {
+        while self.empty_nodes.len() <= level as usize {
+            let empty = *self.empty_nodes.last().unwrap_or(&DEAD);
+            let node = self.join(empty, empty, empty, empty);
+            self.empty_nodes.push(node);
+        }
+
+        self.empty_nodes[level as usize]
+    }
+
+    /// Get the node of one level lower in the middle of a node.
+    fn centre(&mut self, node: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.children(node);
+        self.join(
+            self.children(nw)[3],
+            self.children(ne)[2],
+            self.children(sw)[1],
+            self.children(se)[0],
+        )
+    }
+
+    fn contains(&self, x: i64, y: i64) -> bool {
+        let size = 1i64 << self.level(self.root);
+        let (origin_x, origin_y) = self.origin;
+        (origin_x..origin_x + size).contains(&x) && (origin_y..origin_y + size).contains(&y)
+    }
+
+    /// Double the size of the root, keeping it in the middle.
+    fn expand(&mut self) {
+        let level = self.level(self.root);
+        let empty = self.empty_node(level - 1);
+        let [nw, ne, sw, se] = self.children(self.root);
+
+        let nw = self.join(empty, empty, empty, nw);
+        let ne = self.join(empty, empty, ne, empty);
+        let sw = self.join(empty, sw, empty, empty);
+        let se = self.join(se, empty, empty, empty);
+        self.root = self.join(nw, ne, sw, se);
+
+        let offset = 1 << (level - 1);
+        self.origin = (self.origin.0 - offset, self.origin.1 - offset);
+    }
+
+    /// Halve the size of the root while every live cell is in its middle.
+    fn shrink(&mut self) {
+        while self.level(self.root) > MIN_ROOT_LEVEL {
+            let centre = self.centre(self.root);
+            if self.population_of(centre) != self.population() {
+                break;
+            }
+
+            let offset = 1 << (self.level(self.root) - 2);
+            self.root = centre;
+            self.origin = (self.origin.0 + offset, self.origin.1 + offset);
+        }
+    }
+
+    /// Bring a cell to life within a node.
+    ///
+    /// ## Returns
+    ///
+    /// The node with the cell alive.
+    fn set_cell_in(&mut self, node: NodeId, x: u64, y: u64) -> NodeId {
+        let level = self.level(node);
+        if level == 0 {
+            return ALIVE;
+        }
+
+        let half = 1 << (level - 1);
+        let mut children = self.children(node);
+        let quadrant = usize::from(x >= half) + 2 * usize::from(y >= half);
+        children[quadrant] = self.set_cell_in(children[quadrant], x % half, y % half);
+
+        let [nw, ne, sw, se] = children;
+        self.join(nw, ne, sw, se)
+    }
+
+    /// Get the node of one level lower in the middle of a node, run for
+    /// `2^generations_log2` generations (which must be no more than
+    /// `2^(level - 2)`).
+    fn successor(&mut self, node: NodeId, generations_log2: u32) -> NodeId {
+        let level = self.level(node);
+        if self.population_of(node) == 0 {
+            return self.empty_node(level - 1);
+        }
+        if let Some(successor) = self.successors.get(&(node, generations_log2)) {
+            return *successor;
+        }
+
+        let successor = if level == 2 {
+            self.successor_of_4x4(node)
+        } else {
+            // The nine overlapping nodes of one level lower.
+            let [nw, ne, sw, se] = self.children(node);
+            let [_, nw_ne, nw_sw, nw_se] = self.children(nw);
+            let [ne_nw, _, ne_sw, ne_se] = self.children(ne);
+            let [sw_nw, sw_ne, _, sw_se] = self.children(sw);
+            let [se_nw, se_ne, se_sw, _] = self.children(se);
+            let n01 = self.join(nw_ne, ne_nw, nw_se, ne_sw);
+            let n10 = self.join(nw_sw, nw_se, sw_nw, sw_ne);
+            let n11 = self.join(nw_se, ne_sw, sw_ne, se_nw);
+            let n12 = self.join(ne_sw, ne_se, se_nw, se_ne);
+            let n21 = self.join(sw_ne, se_nw, sw_se, se_sw);
+            let nine = [nw, n01, ne, n10, n11, n12, sw, n21, se];
+
+            // At full speed, each half of the run is done by a successor.
+            // Otherwise the nodes are only cut down to size before the run.
+            let full_speed = generations_log2 + 2 == level;
+            let mut r = [DEAD; 9];
+            for (i, node) in nine.into_iter().enumerate() {
+                r[i] = if full_speed {
+                    self.successor(node, generations_log2 - 1)
+                } else {
+                    self.centre(node)
+                };
+            }
+
+            let remaining_log2 = if full_speed { generations_log2 - 1 } else { generations_log2 };
+            let quadrants = [
+                self.join(r[0], r[1], r[3], r[4]),
+                self.join(r[1], r[2], r[4], r[5]),
+                self.join(r[3], r[4], r[6], r[7]),
+                self.join(r[4], r[5], r[7], r[8]),
+            ];
+            let [nw, ne, sw, se] =
+                quadrants.map(|quadrant| self.successor(quadrant, remaining_log2));
+            self.join(nw, ne, sw, se)
+        };
+
+        self.successors.insert((node, generations_log2), successor);
+        successor
+    }
+
+    /// Run the middle 2x2 cells of a 4x4 node for one generation.
+    fn successor_of_4x4(&mut self, node: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (quadrant, child) in self.children(node).into_iter().enumerate() {
+            for (i, cell) in self.children(child).into_iter().enumerate() {
+                let x = (quadrant % 2) * 2 + i % 2;
+                let y = (quadrant / 2) * 2 + i / 2;
+                cells[y][x] = cell == ALIVE;
+            }
+        }
+
+        let mut next = [DEAD; 4];
+        for (i, next_cell) in next.iter_mut().enumerate() {
+            let (x, y) = (1 + i % 2, 1 + i / 2);
+            let mut alive_neighbours = 0;
+            for (neighbour_y, row) in cells.iter().enumerate().take(y + 2).skip(y - 1) {
+                for (neighbour_x, &alive) in row.iter().enumerate().take(x + 2).skip(x - 1) {
+                    if alive && (neighbour_x, neighbour_y) != (x, y) {
+                        alive_neighbours += 1;
+                    }
+                }
+            }
+
+            let cell = if cells[y][x] { Cell::Alive } else { Cell::Dead };
+            if self.rule.next_state(cell, alive_neighbours) == Cell::Alive {
+                *next_cell = ALIVE;
+            }
+        }
+
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    fn collect_cells(
+        &self,
+        node: NodeId,
+        position: (i64, i64),
+        area: ((i64, i64), (i64, i64)),
+        cells: &mut Vec<Point>,
+    ) {
+        let ((left, top), (right, bottom)) = area;
+        let size = 1i64 << self.level(node);
+        if self.population_of(node) == 0
+            || position.0 >= right
+            || position.1 >= bottom
+            || position.0 + size <= left
+            || position.1 + size <= top
+        {
+            return;
+        }
+
+        if node == ALIVE {
+            cells.push(Point { x: (position.0 - left) as usize, y: (position.1 - top) as usize });
+            return;
+        }
+
+        let half = size / 2;
+        let offsets = [(0, 0), (half, 0), (0, half), (half, half)];
+        for (child, (x, y)) in self.children(node).into_iter().zip(offsets) {
+            self.collect_cells(child, (position.0 + x, position.1 + y), area, cells);
+        }
+    }
+
+    /// Find the bounding box of a node's live cells, relative to the node.
+    fn bounds(
+        &self,
+        node: NodeId,
+        bounds: &mut HashMap<NodeId, (u64, u64, u64, u64)>,
+    ) -> Option<(u64, u64, u64, u64)> {
+        if self.population_of(node) == 0 {
+            return None;
+        }
+        if node == ALIVE {
+            return Some((0, 0, 0, 0));
+        }
+        if let Some(node_bounds) = bounds.get(&node) {
+            return Some(*node_bounds);
+        }
+
+        let half = 1 << (self.level(node) - 1);
+        let offsets = [(0, 0), (half, 0), (0, half), (half, half)];
+        let node_bounds = self
+            .children(node)
+            .into_iter()
+            .zip(offsets)
+            .filter_map(|(child, (x, y))| {
+                let (min_x, min_y, max_x, max_y) = self.bounds(child, bounds)?;
+                Some((min_x + x, min_y + y, max_x + x, max_y + y))
+            })
+            .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))?;
+
+        bounds.insert(node, node_bounds);
+        Some(node_bounds)
+    }
+
+    /// Discard every node that is no longer part of the game, along with the
+    /// stored successors.
+    fn collect_garbage(&mut self) {
+        let mut tree = QuadTree::new(self.rule).unwrap_or_else(|_| unreachable!());
+        let mut new_ids = HashMap::from([(DEAD, DEAD), (ALIVE, ALIVE)]);
+        tree.root = tree.copy_node(self, self.root, &mut new_ids);
+        tree.origin = self.origin;
+        *self = tree;
+    }
+
+    /// Copy a node from another tree.
+    fn copy_node(
+        &mut self,
+        other: &QuadTree,
+        node: NodeId,
+        new_ids: &mut HashMap<NodeId, NodeId>,
+    ) -> NodeId {
+        if let Some(id) = new_ids.get(&node) {
+            return *id;
+        }
+
+        let [nw, ne, sw, se] =
+            other.children(node).map(|child| self.copy_node(other, child, new_ids));
+        let id = self.join(nw, ne, sw, se);
+        new_ids.insert(node, id);
+        id
+    }
+}
+
+#[cfg(test)]
+mod quadtree_tests {
+    use super::*;
+    use crate::analysis::life;
+    use crate::game::library;
+
+    fn create_tree(pattern_name: &str, position: (i64, i64)) -> QuadTree {
+        let mut tree = QuadTree::new(Rule::CONWAY).unwrap();
+        for cell in library::find(pattern_name).unwrap().pattern().cells() {
+            tree.set_cell(position.0 + cell.x as i64, position.1 + cell.y as i64);
+        }
+        tree
+    }
+
+    /// Get every live cell of a tree.
+    fn live_cells(tree: &QuadTree) -> life::CellSet {
+        let Some((top_left, bottom_right)) = tree.bounding_box() else {
+            return life::CellSet::default();
+        };
+        let size = Dimensions {
+            width: (bottom_right.0 - top_left.0 + 1) as usize,
+            height: (bottom_right.1 - top_left.1 + 1) as usize,
+        };
+        tree.live_cells_in(top_left, size)
+            .iter()
+            .map(|cell| (top_left.0 + cell.x as i64, top_left.1 + cell.y as i64))
+            .collect()
+    }
+
+    #[test]
+    fn cells_can_be_set_anywhere() {
+        let mut tree = QuadTree::new(Rule::CONWAY).unwrap();
+        tree.set_cell(-100, 5);
+        tree.set_cell(3, 1000);
+        tree.set_cell(3, 1000);
+
+        assert_eq!(2, tree.population());
+        assert_eq!(Some(((-100, 5), (3, 1000))), tree.bounding_box());
+        assert_eq!(
+            vec![Point { x: 0, y: 5 }],
+            tree.live_cells_in((-100, 0), Dimensions { width: 10, height: 10 })
+        );
+    }
+
+    #[test]
+    fn live_cells_are_listed_relative_to_the_area() {
+        let tree = create_tree("glider", (-1, -1));
+
+        assert_eq!(
+            vec![Point { x: 0, y: 0 }, Point { x: 0, y: 1 }],
+            tree.live_cells_in((1, 0), Dimensions { width: 2, height: 2 })
+        );
+    }
+
+    #[test]
+    fn gliders_travel() {
+        let mut tree = create_tree("glider", (0, 0));
+        let start = live_cells(&tree);
+        for _ in 0..4 {
+            tree.step(0);
+        }
+
+        let expected = start.iter().map(|(x, y)| (x + 1, y + 1)).collect::<life::CellSet>();
+        assert_eq!(expected, live_cells(&tree));
+    }
+
+    #[test]
+    fn single_steps_match_a_simple_simulation() {
+        let mut tree = create_tree("r-pentomino", (-1, -1));
+        let mut cells = live_cells(&tree);
+        for _ in 0..200 {
+            tree.step(0);
+            cells = life::step(&cells);
+            assert_eq!(cells, live_cells(&tree));
+        }
+    }
+
+    #[test]
+    fn large_steps_match_single_steps() {
+        let mut tree = create_tree("acorn", (0, 0));
+        let mut single_stepped = create_tree("acorn", (0, 0));
+        tree.step(8);
+        for _ in 0..256 {
+            single_stepped.step(0);
+        }
+
+        assert_eq!(live_cells(&single_stepped), live_cells(&tree));
+    }
+
+    #[test]
+    fn methuselahs_stabilise_in_large_steps() {
+        let mut tree = create_tree("r-pentomino", (0, 0));
+        tree.step(13);
+
+        // Six gliders have escaped, leaving 116 cells in total.
+        assert_eq!(116, tree.population());
+    }
+
+    #[test]
+    fn rules_are_followed() {
+        // B36/S23 has a small replicator that copies itself every 12
+        // generations.
+        let mut tree = QuadTree::new(Rule::new(&[3, 6], &[2, 3])).unwrap();
+        for (x, y) in
+            [(1, 0), (2, 0), (3, 0), (0, 1), (3, 1), (0, 2), (3, 2), (0, 3), (1, 3), (2, 3)]
+        {
+            tree.set_cell(x, y);
+        }
+        let mut conway = create_tree("block", (0, 0));
+        tree.step(2);
+        conway.step(2);
+
+        assert_eq!(4, conway.population());
+        assert_ne!(10, tree.population());
+        assert!(QuadTree::new(Rule::new(&[0, 3], &[2, 3])).is_err());
+    }
+
+    #[test]
+    fn the_rule_can_be_changed() {
+        let mut tree = create_tree("block", (0, 0));
+        tree.step(0);
+        assert_eq!(4, tree.population());
+
+        // Nothing survives in B3/S, so the block dies even though its
+        // successor has been stored.
+        tree.set_rule(Rule::new(&[3], &[])).unwrap();
+        tree.step(0);
+
+        assert_eq!(0, tree.population());
+        assert!(tree.set_rule(Rule::new(&[0, 3], &[2, 3])).is_err());
+    }
+
+    #[test]
+    fn huge_macrocell_patterns_are_not_expanded() {
+        // A block in the corner of an 8x8 leaf, repeated over a square of
+        // 2^30 by 2^30 cells.
+        let mut nodes = vec![
+            None,
+            Some(MacrocellNode::Leaf(vec![
+                Point { x: 0, y: 0 },
+                Point { x: 1, y: 0 },
+                Point { x: 0, y: 1 },
+                Point { x: 1, y: 1 },
+            ])),
+        ];
+        for level in 4..=30 {
+            let child = nodes.len() - 1;
+            nodes.push(Some(MacrocellNode::Branch { level, children: [child; 4] }));
+        }
+
+        let mut tree = QuadTree::from_macrocell(&nodes, (0, 0), Rule::CONWAY).unwrap();
+        assert_eq!(4 << 54, tree.population());
+
+        // Blocks are still lifes, so the pattern never changes.
+        tree.step(20);
+        assert_eq!(4 << 54, tree.population());
+        assert_eq!(Some(((0, 0), ((1 << 30) - 7, (1 << 30) - 7))), tree.bounding_box());
+    }
+
+    #[test]
+    fn invalid_macrocell_trees_are_rejected() {
+        let from_branches = |levels: &[u32]| {
+            let mut nodes = vec![None, Some(MacrocellNode::Leaf(vec![Point { x: 0, y: 0 }]))];
+            for level in levels {
+                let child = nodes.len() - 1;
+                nodes.push(Some(MacrocellNode::Branch {
+                    level: *level,
+                    children: [child, 0, 0, 0],
+                }));
+            }
+            QuadTree::from_macrocell(&nodes, (0, 0), Rule::CONWAY)
+        };
+
+        assert!(from_branches(&[4, 5]).is_ok());
+        // Branches must be above the leaves, and one level above their
+        // children.
+        assert!(from_branches(&[0]).is_err());
+        assert!(from_branches(&[5]).is_err());
+        // Branches must be small enough to address every cell.
+        assert!(from_branches(&(4..=79).collect::<Vec<_>>()).is_err());
+
+        let leaf = Some(MacrocellNode::Leaf(vec![Point { x: 8, y: 0 }]));
+        assert!(QuadTree::from_macrocell(&[None, leaf], (0, 0), Rule::CONWAY).is_err());
+        let branch = Some(MacrocellNode::Branch { level: 4, children: [2, 0, 0, 0] });
+        assert!(QuadTree::from_macrocell(&[None, branch], (0, 0), Rule::CONWAY).is_err());
+
+        // The population must fit in a u64.
+        let full_leaf = (0..64).map(|i| Point { x: i % 8, y: i / 8 }).collect();
+        let mut nodes = vec![None, Some(MacrocellNode::Leaf(full_leaf))];
+        for level in 4..=32 {
+            let child = nodes.len() - 1;
+            nodes.push(Some(MacrocellNode::Branch { level, children: [child; 4] }));
+        }
+        assert!(QuadTree::from_macrocell(&nodes, (0, 0), Rule::CONWAY).is_err());
+
+        // The pattern must fit at its position.
+        let leaf = Some(MacrocellNode::Leaf(vec![Point { x: 0, y: 0 }]));
+        assert!(QuadTree::from_macrocell(&[None, leaf], (i64::MAX - 4, 0), Rule::CONWAY).is_err());
+    }
+
+    #[test]
+    fn discarding_unused_nodes_keeps_the_game() {
+        let mut tree = create_tree("acorn", (0, 0));
+        tree.step(6);
+        let cells = live_cells(&tree);
+        tree.collect_garbage();
+
+        assert_eq!(cells, live_cells(&tree));
+        tree.step(6);
+        let mut expected = create_tree("acorn", (0, 0));
+        expected.step(7);
+        assert_eq!(live_cells(&expected), live_cells(&tree));
+    }
+}
@@ -0,0 +1,277 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::cmp::Ordering;
+
+use xy_utils::{Dimensions, Point};
+
+use crate::game::{quadtree::QuadTree, Board, Cell, Pattern, Renderer, Rule};
+
+/// A game board on an unbounded plane, which can hold patterns far too large
+/// to store cell by cell. Only part of the plane is visible at a time.
+pub struct QuadTreeBoard<'a, RendererT: Renderer> {
+    /// The whole game.
+    tree: QuadTree,
+
+    /// Position of the top-left corner of the visible area.
+    viewport: (i64, i64),
+
+    /// Size of the visible area.
+    dimensions: Dimensions,
+
+    /// Renderer is used to print the game progress to a user interface.
+    renderer: &'a mut RendererT,
+
+    /// The live cells in the visible area, in row-major order.
+    visible_cells: Vec<Point>,
+
+    /// Called with the population of the whole game after each iteration.
+    population_observer: Option<Box<dyn FnMut(u64) + 'a>>,
+}
+
+impl<'a, RendererT: Renderer> QuadTreeBoard<'a, RendererT> {
+    /// Create a new game board, with the visible area in the middle of the
+    /// game's live cells.
+    ///
+    /// ## Arguments
+    ///
+    /// * `tree`: The game.
+    /// * `dimensions`: Size of the visible area.
+    /// * `renderer`: Renderer that will output the visible area after each
+    ///   iteration. Renderer should be initialized.
+    pub fn new(
+        tree: QuadTree,
+        dimensions: Dimensions,
+        renderer: &'a mut RendererT,
+    ) -> QuadTreeBoard<'a, RendererT> {
+        let viewport = match tree.bounding_box() {
+            Some(((left, top), (right, bottom))) => (
+                left + (right - left - dimensions.width as i64 + 1) / 2,
+                top + (bottom - top - dimensions.height as i64 + 1) / 2,
+            ),
+            None => (0, 0),
+        };
+
+        let visible_cells = tree.live_cells_in(viewport, dimensions);
+        renderer.apply_changes(visible_cells.iter().map(|cell| (*cell, Cell::Alive)).collect());
+
+        QuadTreeBoard {
+            tree,
+            viewport,
+            dimensions,
+            renderer,
+            visible_cells,
+            population_observer: None,
+        }
+    }
+
+    /// Watch the population of the whole game, rather than just the visible
+    /// area that the renderer sees.
+    ///
+    /// ## Arguments
+    ///
+    /// * `observer`: Called with the current population, and again after
+    ///   each iteration.
+    pub fn with_population_observer(
+        mut self,
+        mut observer: Box<dyn FnMut(u64) + 'a>,
+    ) -> QuadTreeBoard<'a, RendererT> {
+        observer(self.tree.population());
+        self.population_observer = Some(observer);
+        self
+    }
+}
+
+impl<RendererT: Renderer> Board for QuadTreeBoard<'_, RendererT> {
+    type Renderer = RendererT;
+
+    fn calculate_iteration(&mut self) {
+        self.tree.step(0);
+
+        let visible_cells = self.tree.live_cells_in(self.viewport, self.dimensions);
+        let changes = find_changes(&self.visible_cells, &visible_cells);
+        self.visible_cells = visible_cells;
+        self.renderer.apply_changes(changes);
+        if let Some(observer) = self.population_observer.as_mut() {
+            observer(self.tree.population());
+        }
+    }
+
+    fn get_size(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    fn get_live_cells(&self) -> Vec<Point> {
+        self.visible_cells.clone()
+    }
+
+    /// Place a pattern in the visible area. The new cells are drawn with the
+    /// next iteration.
+    fn insert_pattern(&mut self, pattern: &Pattern, position: Point) {
+        for cell in pattern.cells() {
+            self.tree.set_cell(
+                self.viewport.0 + (position.x + cell.x) as i64,
+                self.viewport.1 + (position.y + cell.y) as i64,
+            );
+        }
+    }
+
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String> {
+        self.tree.set_rule(rule)
+    }
+
+    fn get_renderer(&mut self) -> &mut RendererT {
+        self.renderer
+    }
+}
+
+/// Find the cells that changed between two lists of live cells, both in
+/// row-major order.
+fn find_changes(old_cells: &[Point], new_cells: &[Point]) -> Vec<(Point, Cell)> {
+    let mut changes = vec![];
+    let mut old_cells = old_cells.iter().peekable();
+    let mut new_cells = new_cells.iter().peekable();
+    loop {
+        let ordering = match (old_cells.peek(), new_cells.peek()) {
+            (Some(old), Some(new)) => (old.y, old.x).cmp(&(new.y, new.x)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => break,
+        };
+
+        match ordering {
+            Ordering::Less => changes.extend(old_cells.next().map(|cell| (*cell, Cell::Dead))),
+            Ordering::Greater => changes.extend(new_cells.next().map(|cell| (*cell, Cell::Alive))),
+            Ordering::Equal => {
+                old_cells.next();
+                new_cells.next();
+            }
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod quadtree_board_tests {
+    use super::*;
+    use crate::game::{renderer::mock::MockRenderer, Rule};
+    use std::str::FromStr;
+
+    fn create_tree(pattern: &str, position: (i64, i64)) -> QuadTree {
+        let mut tree = QuadTree::new(Rule::CONWAY).unwrap();
+        for cell in Pattern::from_str(pattern).unwrap().cells() {
+            tree.set_cell(position.0 + cell.x as i64, position.1 + cell.y as i64);
+        }
+        tree
+    }
+
+    #[test]
+    fn visible_area_is_centred_on_the_pattern() {
+        let mut renderer = MockRenderer::new();
+
+        {
+            QuadTreeBoard::new(
+                create_tree("***", (-1000, 5000)),
+                renderer.get_grid_size(),
+                &mut renderer,
+            );
+        }
+
+        assert_eq!("     \n     \n *** \n     \n     ", renderer.print_grid());
+    }
+
+    #[test]
+    fn the_population_of_the_whole_game_is_observed() {
+        let mut renderer = MockRenderer::new();
+        let mut populations = vec![];
+
+        {
+            // Only the middle one of three blinkers is visible.
+            let mut tree = create_tree("***", (-1, 0));
+            for x in [-1000, -999, -998, 1000, 1001, 1002] {
+                tree.set_cell(x, 0);
+            }
+            let size = Dimensions { width: 5, height: 5 };
+            let mut board = QuadTreeBoard::new(tree, size, &mut renderer)
+                .with_population_observer(Box::new(|population| populations.push(population)));
+            board.calculate_iteration();
+            assert_eq!(3, board.get_live_cells().len());
+        }
+
+        assert_eq!(vec![9, 9], populations);
+    }
+
+    #[test]
+    fn changes_in_the_visible_area_are_rendered() {
+        let mut renderer = MockRenderer::new();
+
+        {
+            let mut board = QuadTreeBoard::new(
+                create_tree(" *\n  *\n***", (0, 0)),
+                renderer.get_grid_size(),
+                &mut renderer,
+            );
+            for _ in 0..4 {
+                board.calculate_iteration();
+            }
+        }
+
+        assert_eq!("     \n     \n   * \n    *\n  ***", renderer.print_grid());
+    }
+
+    #[test]
+    fn inserted_patterns_are_rendered_with_the_next_iteration() {
+        let mut renderer = MockRenderer::new();
+
+        {
+            let mut board = QuadTreeBoard::new(
+                QuadTree::new(Rule::CONWAY).unwrap(),
+                renderer.get_grid_size(),
+                &mut renderer,
+            );
+            board.insert_pattern(&Pattern::from_str("**\n**").unwrap(), Point { x: 3, y: 3 });
+            assert!(board.get_live_cells().is_empty());
+
+            board.calculate_iteration();
+            assert_eq!(4, board.get_live_cells().len());
+        }
+
+        assert_eq!("     \n     \n     \n   **\n   **", renderer.print_grid());
+    }
+
+    #[test]
+    fn only_changed_cells_are_found() {
+        let points = |cells: &[(usize, usize)]| {
+            cells.iter().map(|(x, y)| Point { x: *x, y: *y }).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![
+                (Point { x: 0, y: 0 }, Cell::Dead),
+                (Point { x: 2, y: 0 }, Cell::Alive),
+                (Point { x: 0, y: 2 }, Cell::Dead)
+            ],
+            find_changes(&points(&[(0, 0), (1, 0), (0, 2)]), &points(&[(1, 0), (2, 0)]))
+        );
+    }
+}
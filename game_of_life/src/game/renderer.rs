@@ -20,6 +20,8 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
+use std::cell::RefCell;
+
 use crate::game::Cell;
 use xy_utils::{Dimensions, Point};
 
@@ -38,6 +40,10 @@ pub trait Renderer {
     fn initialize(&mut self);
 
     /// Get the size of the renderable grid.
+    ///
+    /// Renderers that are not limited in a given dimension (e.g. a renderer
+    /// that only collects statistics) should report a zero-sized dimension
+    /// (see `Dimensions::create_empty()`).
     fn get_grid_size(&self) -> Dimensions;
 
     /// Render cell changes. Accepts a list of cell changes. The renderer can
@@ -51,6 +57,56 @@ pub trait Renderer {
     ///
     /// A result type. If initialization failed, the string error is returned.
     fn apply_changes(&mut self, changes: Vec<(Point, Cell)>);
+
+    /// Show a status message to the user. Renderers without anywhere to show
+    /// messages ignore them.
+    ///
+    /// ## Arguments
+    ///
+    /// * `message`: The message to show.
+    fn show_message(&mut self, _message: &str) {}
+}
+
+/// Mutable references to renderers are also renderers. This allows a renderer
+/// to be lent to another object (e.g. a `MultiRenderer`) while the owner keeps
+/// access to it once the game has finished.
+impl<RendererT: Renderer + ?Sized> Renderer for &mut RendererT {
+    fn initialize(&mut self) {
+        (**self).initialize();
+    }
+
+    fn get_grid_size(&self) -> Dimensions {
+        (**self).get_grid_size()
+    }
+
+    fn apply_changes(&mut self, changes: Vec<(Point, Cell)>) {
+        (**self).apply_changes(changes);
+    }
+
+    fn show_message(&mut self, message: &str) {
+        (**self).show_message(message);
+    }
+}
+
+/// Shared renderers are also renderers. This allows a renderer to be drawn by
+/// a game board while the owner keeps using it in response to the user's
+/// input (e.g. to show the help).
+impl<RendererT: Renderer + ?Sized> Renderer for &RefCell<RendererT> {
+    fn initialize(&mut self) {
+        self.borrow_mut().initialize();
+    }
+
+    fn get_grid_size(&self) -> Dimensions {
+        self.borrow().get_grid_size()
+    }
+
+    fn apply_changes(&mut self, changes: Vec<(Point, Cell)>) {
+        self.borrow_mut().apply_changes(changes);
+    }
+
+    fn show_message(&mut self, message: &str) {
+        self.borrow_mut().show_message(message);
+    }
 }
 
 #[cfg(test)]
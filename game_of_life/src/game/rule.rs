@@ -0,0 +1,185 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::{fmt, str::FromStr};
+
+use crate::game::Cell;
+
+/// A rule for a Life-like cellular automaton, given by the numbers of live
+/// neighbours that cause a dead cell to be born and a live cell to survive.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rule {
+    /// Whether a dead cell with the index's number of live neighbours is born.
+    birth: [bool; 9],
+
+    /// Whether a live cell with the index's number of live neighbours
+    /// survives.
+    survival: [bool; 9],
+}
+
+impl Rule {
+    /// Conway's Game of Life (B3/S23).
+    pub const CONWAY: Rule = Rule::new(&[3], &[2, 3]);
+
+    /// Create a rule.
+    ///
+    /// ## Arguments
+    ///
+    /// * `birth`: Numbers of live neighbours that cause a dead cell to be
+    ///   born.
+    /// * `survival`: Numbers of live neighbours that allow a live cell to
+    ///   survive.
+    pub const fn new(birth: &[usize], survival: &[usize]) -> Rule {
+        let mut rule = Rule { birth: [false; 9], survival: [false; 9] };
+        let mut i = 0;
+        while i < birth.len() {
+            rule.birth[birth[i]] = true;
+            i += 1;
+        }
+        let mut i = 0;
+        while i < survival.len() {
+            rule.survival[survival[i]] = true;
+            i += 1;
+        }
+
+        rule
+    }
+
+    /// Calculate the next state of a cell.
+    ///
+    /// ## Arguments
+    ///
+    /// * `cell`: The current state of the cell.
+    /// * `alive_neighbours`: The number of live cells adjacent to the cell.
+    pub fn next_state(&self, cell: Cell, alive_neighbours: usize) -> Cell {
+        let alive = match cell {
+            Cell::Alive => self.survival[alive_neighbours],
+            Cell::Dead => self.birth[alive_neighbours],
+        };
+
+        if alive {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::CONWAY
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parse a rule in B/S notation (e.g. `B3/S23`, case insensitive and in
+    /// either order), or the older S/B notation (e.g. `23/3`).
+    fn from_str(s: &str) -> Result<Rule, String> {
+        let invalid = || format!("Invalid rule \"{}\" (expected B/S notation, e.g. B3/S23)", s);
+        let Some((first, second)) = s.trim().split_once('/') else {
+            return Err(invalid());
+        };
+
+        let parse_counts = |counts: &str| {
+            let mut states = [false; 9];
+            for c in counts.chars() {
+                match c.to_digit(10) {
+                    Some(count) if count <= 8 => states[count as usize] = true,
+                    _ => return Err(invalid()),
+                }
+            }
+            Ok(states)
+        };
+
+        let first = first.to_ascii_uppercase();
+        let second = second.to_ascii_uppercase();
+        let (birth, survival) = match (first.strip_prefix('B'), second.strip_prefix('S')) {
+            (Some(birth), Some(survival)) => (birth, survival),
+            _ => match (first.strip_prefix('S'), second.strip_prefix('B')) {
+                (Some(survival), Some(birth)) => (birth, survival),
+                // S/B notation has no prefixes.
+                _ => (second.as_str(), first.as_str()),
+            },
+        };
+
+        Ok(Rule { birth: parse_counts(birth)?, survival: parse_counts(survival)? })
+    }
+}
+
+impl fmt::Display for Rule {
+    /// Format the rule in B/S notation (e.g. `B3/S23`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |states: &[bool; 9]| {
+            (0..9).filter(|count| states[*count]).map(|count| count.to_string()).collect::<String>()
+        };
+
+        write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))
+    }
+}
+
+#[cfg(test)]
+mod rule_tests {
+    use super::*;
+
+    #[test]
+    fn conway_is_the_default() {
+        assert_eq!(Rule::CONWAY, Rule::default());
+        assert_eq!("B3/S23", Rule::default().to_string());
+    }
+
+    #[test]
+    fn rules_are_parsed() {
+        assert_eq!(Ok(Rule::CONWAY), Rule::from_str("B3/S23"));
+        assert_eq!(Ok(Rule::CONWAY), Rule::from_str("b3/s23"));
+        assert_eq!(Ok(Rule::CONWAY), Rule::from_str("S23/B3"));
+        assert_eq!(Ok(Rule::CONWAY), Rule::from_str("23/3"));
+        assert_eq!(Ok(Rule::new(&[3, 6], &[2, 3])), Rule::from_str("B36/S23"));
+        assert_eq!(Ok(Rule::new(&[], &[0, 8])), Rule::from_str("B/S08"));
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        assert!(Rule::from_str("").is_err());
+        assert!(Rule::from_str("B3S23").is_err());
+        assert!(Rule::from_str("B9/S23").is_err());
+        assert!(Rule::from_str("B3/S2x").is_err());
+        assert!(Rule::from_str("B3/B23").is_err());
+    }
+
+    #[test]
+    fn rules_are_printed_in_bs_notation() {
+        assert_eq!("B36/S23", Rule::new(&[6, 3], &[2, 3]).to_string());
+        assert_eq!("B/S08", Rule::new(&[], &[0, 8]).to_string());
+    }
+
+    #[test]
+    fn next_state_follows_the_rule() {
+        let high_life = Rule::from_str("B36/S23").unwrap();
+
+        assert_eq!(Cell::Alive, high_life.next_state(Cell::Dead, 6));
+        assert_eq!(Cell::Dead, Rule::CONWAY.next_state(Cell::Dead, 6));
+        assert_eq!(Cell::Alive, high_life.next_state(Cell::Alive, 2));
+        assert_eq!(Cell::Dead, high_life.next_state(Cell::Alive, 4));
+    }
+}
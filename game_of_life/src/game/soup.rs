@@ -0,0 +1,113 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use crate::game::{Cell, CellGenerator, RandomCellGenerator, SymmetricCellGenerator, Symmetry};
+use xy_utils::{Dimensions, Point};
+
+/// Everything needed to reproduce a random soup (apart from its size).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SoupSettings {
+    /// Seed of the random number generator.
+    pub seed: u64,
+
+    /// Probability of each cell being alive.
+    pub density: f64,
+
+    pub symmetry: Symmetry,
+}
+
+impl SoupSettings {
+    /// Create a cell generator for the soup.
+    ///
+    /// ## Arguments
+    ///
+    /// * `position`: The top-left cell of the soup on the game board.
+    /// * `size`: The size of the soup. This should already have been adjusted
+    ///   with `Symmetry::adjust_soup_size()`.
+    pub fn create_generator(&self, position: Point, size: Dimensions) -> Box<dyn CellGenerator> {
+        let generator = RandomCellGenerator::from_seed(self.seed).with_density(self.density);
+        if self.symmetry == Symmetry::C1 {
+            Box::new(generator.with_soup_area(position, size))
+        } else {
+            Box::new(SymmetricCellGenerator::new(generator, self.symmetry, position, size))
+        }
+    }
+
+    /// Generate the live cells of the soup, with its top-left cell at (0, 0).
+    /// The soup is identical to the one placed on a game board by the
+    /// generator from `create_generator()`.
+    ///
+    /// ## Arguments
+    ///
+    /// * `size`: The size of the soup (see `create_generator()`).
+    pub fn generate_cells(&self, size: Dimensions) -> Vec<Point> {
+        let mut generator = self.create_generator(Point { x: 0, y: 0 }, size);
+        let mut cells = vec![];
+        for y in 0..size.height {
+            for x in 0..size.width {
+                if generator.generate(Point { x, y }) == Cell::Alive {
+                    cells.push(Point { x, y });
+                }
+            }
+        }
+
+        cells
+    }
+}
+
+#[cfg(test)]
+mod soup_tests {
+    use super::*;
+
+    const SIZE: Dimensions = Dimensions { width: 8, height: 8 };
+
+    fn create_settings(seed: u64, symmetry: Symmetry) -> SoupSettings {
+        SoupSettings { seed, density: 0.5, symmetry }
+    }
+
+    #[test]
+    fn soups_are_reproducible() {
+        let settings = create_settings(5, Symmetry::C1);
+
+        assert_eq!(settings.generate_cells(SIZE), settings.generate_cells(SIZE));
+        assert_ne!(
+            settings.generate_cells(SIZE),
+            create_settings(6, Symmetry::C1).generate_cells(SIZE)
+        );
+    }
+
+    #[test]
+    fn soups_do_not_depend_on_their_position() {
+        let settings = create_settings(5, Symmetry::C2_4);
+        let mut generator = settings.create_generator(Point { x: 3, y: 2 }, SIZE);
+
+        let mut moved_cells = vec![];
+        for y in 0..20 {
+            for x in 0..20 {
+                if generator.generate(Point { x, y }) == Cell::Alive {
+                    moved_cells.push(Point { x: x - 3, y: y - 2 });
+                }
+            }
+        }
+
+        assert_eq!(settings.generate_cells(SIZE), moved_cells);
+    }
+}
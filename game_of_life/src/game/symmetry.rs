@@ -0,0 +1,439 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::game::{Cell, CellGenerator, RandomCellGenerator};
+use xy_utils::{Dimensions, Point};
+
+/// A transformation of a cell's offset from the centre of the soup. Offsets
+/// are measured in half-cells so that soups with an even width or height
+/// (whose centre lies between two cells) can be represented with integers.
+type Transform = fn((i64, i64)) -> (i64, i64);
+
+const IDENTITY: Transform = |(x, y)| (x, y);
+const ROTATE_90: Transform = |(x, y)| (-y, x);
+const ROTATE_180: Transform = |(x, y)| (-x, -y);
+const ROTATE_270: Transform = |(x, y)| (y, -x);
+const FLIP_X: Transform = |(x, y)| (-x, y);
+const FLIP_Y: Transform = |(x, y)| (x, -y);
+const FLIP_DIAGONAL: Transform = |(x, y)| (y, x);
+const FLIP_ANTI_DIAGONAL: Transform = |(x, y)| (-y, -x);
+
+/// Parity that a soup dimension must have for a symmetry. Odd dimensions put
+/// the centre of the soup in the middle of a cell, even dimensions put it on
+/// the boundary between two cells.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Parity {
+    Any,
+    Odd,
+    Even,
+}
+
+/// Symmetries of random soups, named as in apgsearch.
+///
+/// The letter and first number give the symmetry group (e.g. `C4` is 4-fold
+/// rotational symmetry, `D8` is the full symmetry of the square), `+` and `x`
+/// distinguish orthogonal and diagonal mirrors, and the final number describes
+/// where the centre of the soup lies: `1` is the middle of a cell, `2` is the
+/// middle of an edge and `4` is a corner between four cells.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Symmetry {
+    C1,
+    C2_1,
+    C2_2,
+    C2_4,
+    C4_1,
+    C4_4,
+    D2Plus1,
+    D2Plus2,
+    D2X,
+    D4Plus1,
+    D4Plus2,
+    D4Plus4,
+    D4X1,
+    D4X4,
+    D8_1,
+    D8_4,
+}
+
+impl Symmetry {
+    /// All supported symmetries.
+    pub const ALL: [Symmetry; 16] = [
+        Symmetry::C1,
+        Symmetry::C2_1,
+        Symmetry::C2_2,
+        Symmetry::C2_4,
+        Symmetry::C4_1,
+        Symmetry::C4_4,
+        Symmetry::D2Plus1,
+        Symmetry::D2Plus2,
+        Symmetry::D2X,
+        Symmetry::D4Plus1,
+        Symmetry::D4Plus2,
+        Symmetry::D4Plus4,
+        Symmetry::D4X1,
+        Symmetry::D4X4,
+        Symmetry::D8_1,
+        Symmetry::D8_4,
+    ];
+
+    /// The apgsearch name of the symmetry.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::C1 => "C1",
+            Symmetry::C2_1 => "C2_1",
+            Symmetry::C2_2 => "C2_2",
+            Symmetry::C2_4 => "C2_4",
+            Symmetry::C4_1 => "C4_1",
+            Symmetry::C4_4 => "C4_4",
+            Symmetry::D2Plus1 => "D2_+1",
+            Symmetry::D2Plus2 => "D2_+2",
+            Symmetry::D2X => "D2_x",
+            Symmetry::D4Plus1 => "D4_+1",
+            Symmetry::D4Plus2 => "D4_+2",
+            Symmetry::D4Plus4 => "D4_+4",
+            Symmetry::D4X1 => "D4_x1",
+            Symmetry::D4X4 => "D4_x4",
+            Symmetry::D8_1 => "D8_1",
+            Symmetry::D8_4 => "D8_4",
+        }
+    }
+
+    /// Adjust the size of a soup so that it can have this symmetry.
+    ///
+    /// Symmetries with rotations of 90 degrees or diagonal mirrors need a
+    /// square soup, and the position of the centre determines whether each
+    /// dimension is odd or even. Dimensions are only ever made smaller, so the
+    /// soup still fits in the area that was requested.
+    ///
+    /// ## Arguments
+    ///
+    /// * `size`: The requested size of the soup.
+    pub fn adjust_soup_size(&self, size: Dimensions) -> Dimensions {
+        let (width_parity, height_parity) = self.parities();
+        let (width, height) = if self.requires_square() {
+            let side = size.width.min(size.height);
+            (side, side)
+        } else {
+            (size.width, size.height)
+        };
+
+        Dimensions {
+            width: Self::adjust_dimension(width, width_parity),
+            height: Self::adjust_dimension(height, height_parity),
+        }
+    }
+
+    fn transforms(&self) -> &'static [Transform] {
+        match self {
+            Symmetry::C1 => &[IDENTITY],
+            Symmetry::C2_1 | Symmetry::C2_2 | Symmetry::C2_4 => &[IDENTITY, ROTATE_180],
+            Symmetry::C4_1 | Symmetry::C4_4 => &[IDENTITY, ROTATE_90, ROTATE_180, ROTATE_270],
+            Symmetry::D2Plus1 | Symmetry::D2Plus2 => &[IDENTITY, FLIP_Y],
+            Symmetry::D2X => &[IDENTITY, FLIP_DIAGONAL],
+            Symmetry::D4Plus1 | Symmetry::D4Plus2 | Symmetry::D4Plus4 => {
+                &[IDENTITY, FLIP_X, FLIP_Y, ROTATE_180]
+            }
+            Symmetry::D4X1 | Symmetry::D4X4 => {
+                &[IDENTITY, FLIP_DIAGONAL, FLIP_ANTI_DIAGONAL, ROTATE_180]
+            }
+            Symmetry::D8_1 | Symmetry::D8_4 => &[
+                IDENTITY,
+                ROTATE_90,
+                ROTATE_180,
+                ROTATE_270,
+                FLIP_X,
+                FLIP_Y,
+                FLIP_DIAGONAL,
+                FLIP_ANTI_DIAGONAL,
+            ],
+        }
+    }
+
+    fn requires_square(&self) -> bool {
+        matches!(
+            self,
+            Symmetry::C4_1
+                | Symmetry::C4_4
+                | Symmetry::D2X
+                | Symmetry::D4X1
+                | Symmetry::D4X4
+                | Symmetry::D8_1
+                | Symmetry::D8_4
+        )
+    }
+
+    /// Parity of the (width, height) of the soup.
+    fn parities(&self) -> (Parity, Parity) {
+        match self {
+            Symmetry::C1 | Symmetry::D2X => (Parity::Any, Parity::Any),
+            Symmetry::C2_1 | Symmetry::C4_1 | Symmetry::D4Plus1 | Symmetry::D4X1 => {
+                (Parity::Odd, Parity::Odd)
+            }
+            Symmetry::D8_1 => (Parity::Odd, Parity::Odd),
+            Symmetry::C2_2 | Symmetry::D4Plus2 => (Parity::Odd, Parity::Even),
+            Symmetry::C2_4 | Symmetry::C4_4 | Symmetry::D4Plus4 | Symmetry::D4X4 => {
+                (Parity::Even, Parity::Even)
+            }
+            Symmetry::D8_4 => (Parity::Even, Parity::Even),
+            Symmetry::D2Plus1 => (Parity::Any, Parity::Odd),
+            Symmetry::D2Plus2 => (Parity::Any, Parity::Even),
+        }
+    }
+
+    fn adjust_dimension(dimension: usize, parity: Parity) -> usize {
+        // `usize::is_multiple_of()` would raise the minimum Rust version to 1.87.
+        #[allow(clippy::manual_is_multiple_of)]
+        let is_even = dimension % 2 == 0;
+        match parity {
+            Parity::Odd if is_even => dimension.saturating_sub(1),
+            Parity::Even if !is_even => dimension - 1,
+            _ => dimension,
+        }
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Symmetry, String> {
+        Symmetry::ALL.iter().find(|symmetry| symmetry.name() == s).copied().ok_or_else(|| {
+            let names = Symmetry::ALL.iter().map(|symmetry| symmetry.name()).collect::<Vec<_>>();
+            format!("Unknown symmetry \"{}\" (expected one of: {})", s, names.join(", "))
+        })
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// CellGenerator trait implementation that generates a random soup with a
+/// given symmetry.
+///
+/// Each set of cells that map onto each other under the symmetry (an "orbit")
+/// shares a single random cell state, which is drawn from the wrapped
+/// `RandomCellGenerator` the first time that any cell in the orbit is
+/// generated.
+pub struct SymmetricCellGenerator<RandomT: rand::RngCore> {
+    generator: RandomCellGenerator<RandomT>,
+    symmetry: Symmetry,
+
+    /// Top-left cell and size of the soup.
+    soup_position: Point,
+    soup_size: Dimensions,
+
+    /// Cell states that have already been generated, indexed by the first
+    /// cell of each orbit (in row-major order).
+    orbits: HashMap<Point, Cell>,
+}
+
+impl<RandomT: rand::RngCore> SymmetricCellGenerator<RandomT> {
+    /// Create a symmetric soup generator.
+    ///
+    /// ## Arguments
+    ///
+    /// * `generator`: Generator for the random cell states.
+    /// * `symmetry`: The symmetry of the soup.
+    /// * `soup_position`: The top-left cell of the soup.
+    /// * `soup_size`: The size of the soup. This should already have been
+    ///   adjusted with `Symmetry::adjust_soup_size()`, otherwise cells whose
+    ///   images fall outside of the soup will not be symmetric.
+    pub fn new(
+        generator: RandomCellGenerator<RandomT>,
+        symmetry: Symmetry,
+        soup_position: Point,
+        soup_size: Dimensions,
+    ) -> Self {
+        SymmetricCellGenerator {
+            generator: generator.with_soup_area(soup_position, soup_size),
+            symmetry,
+            soup_position,
+            soup_size,
+            orbits: HashMap::new(),
+        }
+    }
+
+    /// Twice the coordinates of the centre of the soup.
+    fn doubled_centre(&self) -> (i64, i64) {
+        (
+            (2 * self.soup_position.x + self.soup_size.width) as i64 - 1,
+            (2 * self.soup_position.y + self.soup_size.height) as i64 - 1,
+        )
+    }
+
+    fn is_in_soup(&self, address: Point) -> bool {
+        (address.x >= self.soup_position.x)
+            && (address.y >= self.soup_position.y)
+            && (address.x < self.soup_position.x + self.soup_size.width)
+            && (address.y < self.soup_position.y + self.soup_size.height)
+    }
+
+    /// Find the first cell (in row-major order) of the orbit that contains the
+    /// given address.
+    fn find_orbit_representative(&self, address: Point) -> Point {
+        let (centre_x, centre_y) = self.doubled_centre();
+        let offset = (2 * address.x as i64 - centre_x, 2 * address.y as i64 - centre_y);
+
+        self.symmetry
+            .transforms()
+            .iter()
+            .filter_map(|transform| {
+                let (x, y) = transform(offset);
+                let (x, y) = (x + centre_x, y + centre_y);
+                if (x < 0) || (y < 0) || (x % 2 != 0) || (y % 2 != 0) {
+                    return None;
+                }
+
+                let image = Point { x: (x / 2) as usize, y: (y / 2) as usize };
+                self.is_in_soup(image).then_some(image)
+            })
+            .min_by_key(|image| (image.y, image.x))
+            .unwrap_or(address)
+    }
+}
+
+impl<RandomT: rand::RngCore> CellGenerator for SymmetricCellGenerator<RandomT> {
+    fn generate(&mut self, address: Point) -> Cell {
+        if !self.is_in_soup(address) {
+            return Cell::Dead;
+        }
+
+        let representative = self.find_orbit_representative(address);
+        match self.orbits.get(&representative) {
+            Some(cell) => *cell,
+            None => {
+                let cell = self.generator.generate(representative);
+                self.orbits.insert(representative, cell);
+                cell
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod symmetry_tests {
+    use super::*;
+
+    /// Generate a soup that fills a board of the given size.
+    fn generate_soup(symmetry: Symmetry, size: Dimensions) -> Vec<Vec<Cell>> {
+        let mut gen = SymmetricCellGenerator::new(
+            RandomCellGenerator::from_seed(99),
+            symmetry,
+            Point { x: 0, y: 0 },
+            size,
+        );
+
+        (0..size.height)
+            .map(|y| (0..size.width).map(|x| gen.generate(Point { x, y })).collect())
+            .collect()
+    }
+
+    fn transpose(soup: &[Vec<Cell>]) -> Vec<Vec<Cell>> {
+        (0..soup[0].len()).map(|x| soup.iter().map(|row| row[x]).collect()).collect()
+    }
+
+    fn flip_x(soup: &[Vec<Cell>]) -> Vec<Vec<Cell>> {
+        soup.iter().map(|row| row.iter().rev().copied().collect()).collect()
+    }
+
+    fn flip_y(soup: &[Vec<Cell>]) -> Vec<Vec<Cell>> {
+        soup.iter().rev().cloned().collect()
+    }
+
+    fn rotate_90(soup: &[Vec<Cell>]) -> Vec<Vec<Cell>> {
+        flip_x(&transpose(soup))
+    }
+
+    #[test]
+    fn symmetries_are_parsed_from_apgsearch_names() {
+        assert_eq!(Ok(Symmetry::C1), Symmetry::from_str("C1"));
+        assert_eq!(Ok(Symmetry::D2Plus1), Symmetry::from_str("D2_+1"));
+        assert_eq!(Ok(Symmetry::D8_4), Symmetry::from_str("D8_4"));
+        assert!(Symmetry::from_str("D3_1").is_err());
+
+        for symmetry in Symmetry::ALL {
+            assert_eq!(Ok(symmetry), Symmetry::from_str(&symmetry.to_string()));
+        }
+    }
+
+    #[test]
+    fn soup_sizes_are_adjusted_to_fit_the_symmetry() {
+        let size = Dimensions { width: 16, height: 12 };
+
+        assert_eq!(size, Symmetry::C1.adjust_soup_size(size));
+        assert_eq!(Dimensions { width: 15, height: 11 }, Symmetry::C2_1.adjust_soup_size(size));
+        assert_eq!(Dimensions { width: 15, height: 12 }, Symmetry::C2_2.adjust_soup_size(size));
+        assert_eq!(Dimensions { width: 12, height: 12 }, Symmetry::C4_4.adjust_soup_size(size));
+        assert_eq!(Dimensions { width: 11, height: 11 }, Symmetry::D8_1.adjust_soup_size(size));
+        assert_eq!(Dimensions { width: 16, height: 11 }, Symmetry::D2Plus1.adjust_soup_size(size));
+    }
+
+    #[test]
+    fn rotational_symmetries_are_preserved() {
+        let c2 = generate_soup(Symmetry::C2_2, Dimensions { width: 7, height: 8 });
+        assert_eq!(c2, flip_y(&flip_x(&c2)));
+
+        for symmetry in [Symmetry::C4_1, Symmetry::C4_4] {
+            let size = symmetry.adjust_soup_size(Dimensions { width: 10, height: 10 });
+            let c4 = generate_soup(symmetry, size);
+            assert_eq!(c4, rotate_90(&c4));
+            assert_ne!(c4, flip_x(&c4));
+        }
+    }
+
+    #[test]
+    fn mirror_symmetries_are_preserved() {
+        let d2 = generate_soup(Symmetry::D2Plus2, Dimensions { width: 9, height: 8 });
+        assert_eq!(d2, flip_y(&d2));
+        assert_ne!(d2, flip_x(&d2));
+
+        let d2_diagonal = generate_soup(Symmetry::D2X, Dimensions { width: 9, height: 9 });
+        assert_eq!(d2_diagonal, transpose(&d2_diagonal));
+
+        let d4 = generate_soup(Symmetry::D4Plus1, Dimensions { width: 9, height: 7 });
+        assert_eq!(d4, flip_x(&d4));
+        assert_eq!(d4, flip_y(&d4));
+
+        let d8 = generate_soup(Symmetry::D8_4, Dimensions { width: 10, height: 10 });
+        assert_eq!(d8, rotate_90(&d8));
+        assert_eq!(d8, transpose(&d8));
+    }
+
+    #[test]
+    fn cells_outside_the_soup_are_dead() {
+        let mut gen = SymmetricCellGenerator::new(
+            RandomCellGenerator::from_seed(1).with_density(1.0),
+            Symmetry::C2_1,
+            Point { x: 2, y: 2 },
+            Dimensions { width: 3, height: 3 },
+        );
+
+        assert_eq!(Cell::Dead, gen.generate(Point { x: 1, y: 2 }));
+        assert_eq!(Cell::Alive, gen.generate(Point { x: 2, y: 2 }));
+        assert_eq!(Cell::Alive, gen.generate(Point { x: 4, y: 4 }));
+        assert_eq!(Cell::Dead, gen.generate(Point { x: 5, y: 4 }));
+    }
+}
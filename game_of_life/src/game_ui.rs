@@ -0,0 +1,300 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The controls of a game that is played in the terminal: the keys, the
+//! command line, and the overlays that are drawn over the game board (the
+//! help, the pattern picker and the pattern browser).
+
+use tui::components::{Help, TextInput};
+use tui::event::{KeyBindings, KeyCode, KeyEvent};
+use tui::{Component, Paintbrush, Plotter};
+use xy_utils::{Dimensions, Point};
+
+use crate::command_line::{Command, CommandLine, CommandLineAction};
+use crate::game::library::LibraryPattern;
+use crate::pattern_browser::{BrowserAction, PatternBrowser};
+use crate::pattern_picker::{PatternPicker, PickerAction};
+
+/// Actions that can be bound to keys while the game is being played.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GameAction {
+    Quit,
+    ShowHelp,
+    PickPattern,
+    OpenFile,
+}
+
+/// Something that the game needs to do in response to the user's input.
+#[derive(Debug, PartialEq)]
+pub enum UiAction {
+    /// Stop playing the game.
+    Quit,
+
+    /// Insert a pattern from the library in the middle of the game board.
+    InsertPattern(&'static LibraryPattern),
+
+    /// Run a command typed into the command line, or a file chosen in the
+    /// pattern browser (as `Command::Load`).
+    RunCommand(Command),
+
+    /// Show a message to the user, such as an error.
+    ShowMessage(String),
+}
+
+/// The state of the game's controls.
+///
+/// Key presses go to whichever control is open: the help, the pattern picker,
+/// the pattern browser or the command line. When none of them are open, keys
+/// are looked up in the key bindings. The renderer draws the open controls
+/// (see `open_controls()`).
+pub struct GameUi {
+    key_bindings: KeyBindings<GameAction>,
+
+    /// The help, while it is open.
+    help: Option<Help>,
+
+    pattern_picker: PatternPicker,
+    pattern_browser: PatternBrowser,
+    command_line: CommandLine,
+}
+
+impl GameUi {
+    pub fn new() -> Self {
+        let mut key_bindings = KeyBindings::new();
+        key_bindings.bind(KeyEvent::with_ctrl(KeyCode::Char('c')), GameAction::Quit, "Quit");
+        key_bindings.bind(
+            KeyEvent::from(KeyCode::Char('?')),
+            GameAction::ShowHelp,
+            "Show this help",
+        );
+        key_bindings.bind(KeyEvent::from(KeyCode::F(1)), GameAction::ShowHelp, "Show this help");
+        key_bindings.bind(
+            KeyEvent::from(KeyCode::Char('p')),
+            GameAction::PickPattern,
+            "Insert a pattern from the library",
+        );
+        key_bindings.bind(
+            KeyEvent::from(KeyCode::Char('o')),
+            GameAction::OpenFile,
+            "Open a pattern file",
+        );
+
+        GameUi {
+            key_bindings,
+            help: None,
+            pattern_picker: PatternPicker::new(),
+            pattern_browser: PatternBrowser::new(),
+            command_line: CommandLine::new(),
+        }
+    }
+
+    /// Handle a key press. The game can be quit whichever control is open.
+    ///
+    /// ## Arguments
+    ///
+    /// * `key_event`: The key that was pressed.
+    ///
+    /// ## Returns
+    ///
+    /// The action that the game needs to take in response to the key, if any.
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> Option<UiAction> {
+        let action = self.key_bindings.action(&key_event).copied();
+        if action == Some(GameAction::Quit) {
+            return Some(UiAction::Quit);
+        }
+
+        if self.help.is_some() {
+            self.handle_help_key(key_event)
+        } else if self.pattern_picker.is_open() {
+            self.handle_picker_key(key_event)
+        } else if self.pattern_browser.is_open() {
+            self.handle_browser_key(key_event)
+        } else if let Some(command_line_action) = self.command_line.handle_key(key_event) {
+            Self::handle_command_line_action(command_line_action)
+        } else {
+            action.and_then(|action| self.run_action(action))
+        }
+    }
+
+    /// Get the command line, if it is open, and the overlay that is open, if
+    /// any, so that they can be drawn.
+    pub fn open_controls<PlotterT: Plotter>(
+        &mut self,
+    ) -> (Option<&mut TextInput>, Option<&mut dyn Component<PlotterT>>) {
+        let overlay: Option<&mut dyn Component<PlotterT>> = if let Some(help) = self.help.as_mut() {
+            Some(help)
+        } else if self.pattern_picker.is_open() {
+            Some(self.pattern_picker.menu_mut())
+        } else {
+            self.pattern_browser
+                .file_browser_mut()
+                .map(|file_browser| file_browser as &mut dyn Component<PlotterT>)
+        };
+
+        (self.command_line.input_mut(), overlay)
+    }
+
+    /// Get the size that the open overlay needs to show all of its content, or
+    /// `None` if no overlay is open.
+    pub fn overlay_size(&self) -> Option<Dimensions> {
+        if let Some(help) = self.help.as_ref() {
+            Some(help.preferred_size())
+        } else if self.pattern_picker.is_open() {
+            Some(self.pattern_picker.menu().preferred_size())
+        } else {
+            self.pattern_browser.file_browser().map(|file_browser| file_browser.preferred_size())
+        }
+    }
+
+    pub fn is_command_line_open(&self) -> bool {
+        self.command_line.input().is_some()
+    }
+
+    /// Scroll the help. Any other key closes it.
+    fn handle_help_key(&mut self, key_event: KeyEvent) -> Option<UiAction> {
+        if !self.help.as_mut().is_some_and(|help| help.handle_key(&key_event)) {
+            self.help = None;
+        }
+        None
+    }
+
+    fn handle_picker_key(&mut self, key_event: KeyEvent) -> Option<UiAction> {
+        match self.pattern_picker.handle_key(key_event)? {
+            PickerAction::Insert(library_pattern) => Some(UiAction::InsertPattern(library_pattern)),
+            PickerAction::Updated | PickerAction::Closed => None,
+        }
+    }
+
+    fn handle_browser_key(&mut self, key_event: KeyEvent) -> Option<UiAction> {
+        match self.pattern_browser.handle_key(key_event)? {
+            BrowserAction::Failed(error) => {
+                Some(UiAction::ShowMessage(format!("Error: {}", error)))
+            }
+            BrowserAction::Open(path) => {
+                Some(UiAction::RunCommand(Command::Load(path.to_string_lossy().to_string())))
+            }
+            BrowserAction::Updated | BrowserAction::Closed => None,
+        }
+    }
+
+    fn handle_command_line_action(action: CommandLineAction) -> Option<UiAction> {
+        match action {
+            CommandLineAction::Run(Ok(command)) => Some(UiAction::RunCommand(command)),
+            CommandLineAction::Run(Err(error)) => {
+                Some(UiAction::ShowMessage(format!("Error: {}", error)))
+            }
+            CommandLineAction::Edited | CommandLineAction::Closed => None,
+        }
+    }
+
+    /// Run the action of a key binding.
+    fn run_action(&mut self, action: GameAction) -> Option<UiAction> {
+        match action {
+            GameAction::Quit => return Some(UiAction::Quit),
+            GameAction::ShowHelp => self.help = Some(self.create_help()),
+            GameAction::PickPattern => self.pattern_picker.open(),
+            GameAction::OpenFile => {
+                if let Err(error) = self.pattern_browser.open() {
+                    return Some(UiAction::ShowMessage(format!("Error: {}", error)));
+                }
+            }
+        }
+        None
+    }
+
+    /// Create the help that lists the keys that can be pressed while the game
+    /// is being played.
+    fn create_help(&self) -> Help {
+        let mut entries: Vec<(String, String)> = self
+            .key_bindings
+            .iter()
+            .map(|(key, _, description)| (key.to_string(), description.to_string()))
+            .collect();
+        entries.push((":".to_string(), "Open the command line".to_string()));
+
+        // The renderer decides where the help goes.
+        Help::new(
+            Paintbrush::create_default(),
+            Point { x: 0, y: 0 },
+            Dimensions::create_empty(),
+            "Keys",
+            entries,
+        )
+    }
+}
+
+#[cfg(test)]
+mod game_ui_tests {
+    use super::*;
+    use crate::game::library::LIBRARY;
+    use tui::mock::MockPlotter;
+
+    fn press(ui: &mut GameUi, code: KeyCode) -> Option<UiAction> {
+        ui.handle_key(KeyEvent::from(code))
+    }
+
+    fn type_text(ui: &mut GameUi, text: &str) {
+        for c in text.chars() {
+            press(ui, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn keys_go_to_the_open_overlay() {
+        let mut ui = GameUi::new();
+        assert_eq!(None, ui.overlay_size());
+
+        assert_eq!(None, press(&mut ui, KeyCode::Char('?')));
+        assert!(ui.overlay_size().is_some());
+        assert!(ui.open_controls::<MockPlotter>().1.is_some());
+
+        // 'p' would open the picker if the help wasn't open.
+        assert_eq!(None, press(&mut ui, KeyCode::Char('p')));
+        assert_eq!(None, ui.overlay_size());
+
+        press(&mut ui, KeyCode::Char('p'));
+        assert_eq!(Some(UiAction::InsertPattern(&LIBRARY[0])), press(&mut ui, KeyCode::Enter));
+        assert_eq!(None, ui.overlay_size());
+    }
+
+    #[test]
+    fn the_game_can_be_quit_while_an_overlay_is_open() {
+        let mut ui = GameUi::new();
+        press(&mut ui, KeyCode::Char('?'));
+
+        assert_eq!(Some(UiAction::Quit), ui.handle_key(KeyEvent::with_ctrl(KeyCode::Char('c'))));
+    }
+
+    #[test]
+    fn commands_are_run_from_the_command_line() {
+        let mut ui = GameUi::new();
+        type_text(&mut ui, ":goto 10");
+        assert!(ui.is_command_line_open());
+        assert_eq!(Some("goto 10"), ui.open_controls::<MockPlotter>().0.map(|input| input.text()));
+
+        assert_eq!(Some(UiAction::RunCommand(Command::Goto(10))), press(&mut ui, KeyCode::Enter));
+        assert!(!ui.is_command_line_open());
+
+        type_text(&mut ui, ":jump");
+        assert!(matches!(press(&mut ui, KeyCode::Enter), Some(UiAction::ShowMessage(_))));
+    }
+}
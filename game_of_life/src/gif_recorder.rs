@@ -0,0 +1,304 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::io::Write;
+
+use crate::game::{Cell, Renderer};
+use xy_utils::{Dimensions, Point};
+
+/// Palette used for every frame. Index 0 is a dead cell, index 1 is a live
+/// cell.
+const PALETTE: [u8; 6] = [0x10, 0x10, 0x10, 0xf0, 0xf0, 0xf0];
+const DEAD_PIXEL: u8 = 0;
+const ALIVE_PIXEL: u8 = 1;
+
+/// Renderer implementation that records the game as an animated GIF.
+///
+/// Every call to `apply_changes()` produces one frame of the animation, so the
+/// first frame is the initial seed, and each subsequent frame is one
+/// generation of the game.
+///
+/// Since renderers must handle their own errors, a recorder that fails to
+/// write a frame will stop recording. The error is reported by `finish()` once
+/// the game has ended.
+pub struct GifRecorder<OutputStream: Write> {
+    /// The GIF encoder. Set to `None` if recording was aborted due to an error.
+    encoder: Option<gif::Encoder<OutputStream>>,
+
+    /// Size of the recorded game board (in cells).
+    size: Dimensions,
+
+    /// Width and height of each cell in the output image (in pixels).
+    cell_size: usize,
+
+    /// Delay between frames in hundredths of a second.
+    frame_delay: u16,
+
+    /// Palette indices for the current frame, row-major.
+    pixels: Vec<u8>,
+
+    /// The first error that was encountered while recording.
+    error: Option<String>,
+}
+
+impl<OutputStream: Write> GifRecorder<OutputStream> {
+    /// Create a new recorder.
+    ///
+    /// ## Arguments
+    ///
+    /// * `outstream`: Where the GIF data will be written (e.g. a file).
+    /// * `size`: The size of the game board that will be recorded.
+    /// * `cell_size`: The width and height of each cell in pixels.
+    /// * `update_frequency`: The frequency that the game board is updated
+    ///   in hertz. The frame delay of the animation is derived from this.
+    pub fn new(
+        outstream: OutputStream,
+        size: Dimensions,
+        cell_size: usize,
+        update_frequency: u32,
+    ) -> Result<Self, String> {
+        let image_width = Self::to_image_dimension(size.width * cell_size)?;
+        let image_height = Self::to_image_dimension(size.height * cell_size)?;
+
+        let mut encoder = gif::Encoder::new(outstream, image_width, image_height, &PALETTE)
+            .map_err(|error| error.to_string())?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(|error| error.to_string())?;
+
+        Ok(GifRecorder {
+            encoder: Some(encoder),
+            size,
+            cell_size,
+            frame_delay: Self::calculate_frame_delay(update_frequency),
+            pixels: vec![DEAD_PIXEL; size.total_area() * cell_size * cell_size],
+            error: None,
+        })
+    }
+
+    /// Change the frequency that the game board is updated, e.g. when the user
+    /// changes the speed of the game. Frames recorded from now on use the new
+    /// frame delay.
+    ///
+    /// ## Arguments
+    ///
+    /// * `update_frequency`: The new frequency in hertz.
+    pub fn set_update_frequency(&mut self, update_frequency: u32) {
+        self.frame_delay = Self::calculate_frame_delay(update_frequency);
+    }
+
+    /// Finish the recording, writing the GIF trailer, flushing the output
+    /// stream and returning it.
+    pub fn finish(mut self) -> Result<OutputStream, String> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        match self.encoder.take() {
+            Some(encoder) => {
+                let mut outstream = encoder.into_inner().map_err(|error| error.to_string())?;
+                outstream.flush().map_err(|error| error.to_string())?;
+                Ok(outstream)
+            }
+            None => Err("Recording was aborted".to_string()),
+        }
+    }
+
+    /// Convert the frequency that the game updates into a GIF frame delay.
+    ///
+    /// GIF delays are measured in hundredths of a second, and most viewers
+    /// treat delays below 2 as "as fast as possible" (often slowing the
+    /// animation down instead), so the delay is clamped to that minimum.
+    fn calculate_frame_delay(update_frequency: u32) -> u16 {
+        let delay = 100 / update_frequency.max(1);
+        delay.clamp(2, u16::MAX as u32) as u16
+    }
+
+    fn to_image_dimension(pixels: usize) -> Result<u16, String> {
+        match u16::try_from(pixels) {
+            Ok(0) | Err(_) => Err(format!(
+                "Cannot record a GIF with a dimension of {} pixels (must be 1-{})",
+                pixels,
+                u16::MAX
+            )),
+            Ok(value) => Ok(value),
+        }
+    }
+
+    fn set_cell(&mut self, cell_address: Point, new_value: Cell) {
+        if (cell_address.x >= self.size.width) || (cell_address.y >= self.size.height) {
+            return;
+        }
+
+        let pixel = match new_value {
+            Cell::Alive => ALIVE_PIXEL,
+            Cell::Dead => DEAD_PIXEL,
+        };
+        let image_width = self.size.width * self.cell_size;
+        let first_pixel_x = cell_address.x * self.cell_size;
+        let first_pixel_y = cell_address.y * self.cell_size;
+        for y in first_pixel_y..(first_pixel_y + self.cell_size) {
+            let row_start = (y * image_width) + first_pixel_x;
+            self.pixels[row_start..(row_start + self.cell_size)].fill(pixel);
+        }
+    }
+
+    fn write_frame(&mut self) {
+        let Some(encoder) = self.encoder.as_mut() else {
+            return;
+        };
+
+        let mut frame = gif::Frame::from_indexed_pixels(
+            (self.size.width * self.cell_size) as u16,
+            (self.size.height * self.cell_size) as u16,
+            self.pixels.clone(),
+            None,
+        );
+        frame.delay = self.frame_delay;
+
+        if let Err(error) = encoder.write_frame(&frame) {
+            // Stop recording, there is no guarantee that any future frames
+            // would produce a valid image.
+            self.error = Some(error.to_string());
+            self.encoder = None;
+        }
+    }
+}
+
+impl<OutputStream: Write> Renderer for GifRecorder<OutputStream> {
+    fn initialize(&mut self) {}
+
+    fn get_grid_size(&self) -> Dimensions {
+        self.size
+    }
+
+    fn apply_changes(&mut self, changes: Vec<(Point, Cell)>) {
+        for (cell_address, cell_state) in changes {
+            self.set_cell(cell_address, cell_state);
+        }
+
+        self.write_frame();
+    }
+}
+
+#[cfg(test)]
+mod gif_recorder_tests {
+    use super::*;
+
+    fn decode_frames(data: &[u8]) -> Vec<(u16, Vec<u8>)> {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(data).unwrap();
+
+        let mut frames = vec![];
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            frames.push((frame.delay, frame.buffer.to_vec()));
+        }
+        frames
+    }
+
+    #[test]
+    fn records_one_frame_per_set_of_changes() {
+        let mut recorder =
+            GifRecorder::new(vec![], Dimensions { width: 3, height: 2 }, 1, 4).unwrap();
+
+        recorder.apply_changes(vec![(Point { x: 0, y: 0 }, Cell::Alive)]);
+        recorder.apply_changes(vec![
+            (Point { x: 0, y: 0 }, Cell::Dead),
+            (Point { x: 2, y: 1 }, Cell::Alive),
+        ]);
+
+        let frames = decode_frames(&recorder.finish().unwrap());
+
+        assert_eq!(2, frames.len());
+        assert_eq!(vec![1, 0, 0, 0, 0, 0], frames[0].1);
+        assert_eq!(vec![0, 0, 0, 0, 0, 1], frames[1].1);
+    }
+
+    #[test]
+    fn cells_are_scaled_to_the_cell_size() {
+        let mut recorder =
+            GifRecorder::new(vec![], Dimensions { width: 2, height: 1 }, 2, 4).unwrap();
+
+        recorder.apply_changes(vec![(Point { x: 1, y: 0 }, Cell::Alive)]);
+
+        let frames = decode_frames(&recorder.finish().unwrap());
+
+        #[rustfmt::skip]
+        let expected = vec![
+            0, 0, 1, 1,
+            0, 0, 1, 1,
+        ];
+        assert_eq!(expected, frames[0].1);
+    }
+
+    #[test]
+    fn frame_delay_is_derived_from_the_update_frequency() {
+        let mut recorder =
+            GifRecorder::new(vec![], Dimensions { width: 1, height: 1 }, 1, 4).unwrap();
+        recorder.apply_changes(vec![]);
+
+        let frames = decode_frames(&recorder.finish().unwrap());
+        assert_eq!(25, frames[0].0);
+
+        // Very high frequencies are clamped to the smallest delay that GIF
+        // viewers will reliably honour.
+        assert_eq!(2, GifRecorder::<Vec<u8>>::calculate_frame_delay(1000));
+    }
+
+    #[test]
+    fn frame_delay_follows_changes_to_the_update_frequency() {
+        let mut recorder =
+            GifRecorder::new(vec![], Dimensions { width: 1, height: 1 }, 1, 4).unwrap();
+        recorder.apply_changes(vec![]);
+        recorder.set_update_frequency(10);
+        recorder.apply_changes(vec![]);
+
+        let frames = decode_frames(&recorder.finish().unwrap());
+        assert_eq!(vec![25, 10], frames.iter().map(|(delay, _)| *delay).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn cells_outside_the_recorded_area_are_ignored() {
+        let mut recorder =
+            GifRecorder::new(vec![], Dimensions { width: 1, height: 1 }, 1, 4).unwrap();
+        recorder.apply_changes(vec![(Point { x: 5, y: 5 }, Cell::Alive)]);
+
+        let frames = decode_frames(&recorder.finish().unwrap());
+        assert_eq!(vec![0], frames[0].1);
+    }
+
+    #[test]
+    fn the_output_stream_is_flushed_when_the_recording_finishes() {
+        let output = std::io::BufWriter::new(vec![]);
+        let mut recorder =
+            GifRecorder::new(output, Dimensions { width: 1, height: 1 }, 1, 4).unwrap();
+        recorder.apply_changes(vec![(Point { x: 0, y: 0 }, Cell::Alive)]);
+
+        let output = recorder.finish().unwrap();
+        assert!(output.buffer().is_empty());
+        assert_eq!(1, decode_frames(output.get_ref()).len());
+    }
+
+    #[test]
+    fn empty_boards_cannot_be_recorded() {
+        assert!(GifRecorder::new(vec![], Dimensions::create_empty(), 4, 4).is_err());
+    }
+}
@@ -20,40 +20,240 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use std::{fs::File, io::Read, path::Path, thread, time::Duration};
+use std::{
+    cell::RefCell,
+    fs::{self, File, OpenOptions},
+    io::BufWriter,
+    process::ExitCode,
+    thread,
+    time::{Duration, Instant},
+};
 
 use clap::Parser;
-use crossterm::event;
-use rand;
 
-use game::{CellGenerator, GameBoard, RandomCellGenerator, Renderer, UserCellGenerator};
-use tui::DefaultPlotter;
-use tui_renderer::TuiRenderer;
-use xy_utils::Dimensions;
+use analysis::{life, Census};
+use cli::PatternPlacement;
+use command_line::Command;
+use game::{
+    library::{self, Category},
+    pattern_file::macrocell,
+    Board, CellGenerator, GameBoard, MultiRenderer, Pattern, PatternFile, PatternFormat, QuadTree,
+    QuadTreeBoard, Renderer, Rule, SoupSettings, Symmetry, Topology, UserCellGenerator,
+};
+use game_ui::UiAction;
+use gif_recorder::GifRecorder;
+use search::SearchSettings;
+use statistics::{StatisticsFormat, StatisticsRecorder};
+use tui::{
+    event::{Event, EventLoop, EventSource, Flow, TerminalEventSource},
+    BufferedPlotter, DefaultPlotter, Plotter,
+};
+use tui_renderer::{ChartSettings, TuiRenderer};
+use xy_utils::{Dimensions, Point};
 
+mod analysis;
 mod cli;
+mod command_line;
 mod game;
+mod game_ui;
+mod gif_recorder;
+mod pattern_browser;
+mod pattern_picker;
+mod search;
+mod statistics;
 mod tui_renderer;
 
-fn get_game_board_seed_from_file(file_path_str: &str) -> String {
-    if file_path_str.is_empty() {
-        String::new()
+/// Size of the game board in headless mode if the user does not provide one.
+const DEFAULT_HEADLESS_GRID_SIZE: Dimensions = Dimensions { width: 128, height: 128 };
+
+/// Size of each soup in a search if the user does not provide one.
+const DEFAULT_SEARCH_SOUP_SIZE: Dimensions = Dimensions { width: 16, height: 16 };
+
+/// Apply the transforms requested by the user to a pattern.
+fn transform_pattern(pattern: Pattern, placement: &PatternPlacement) -> Pattern {
+    placement.transforms.iter().fold(pattern, |pattern, transform| pattern.transformed(*transform))
+}
+
+/// Read a pattern file in the given format.
+fn read_pattern_file(path: &str, format: PatternFormat) -> Result<PatternFile, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read \"{}\": {}", path, error))?;
+    PatternFile::parse(&contents, format)
+        .map_err(|error| format!("Failed to parse \"{}\": {}", path, error))
+}
+
+/// Read the quadtree of a macrocell file without expanding it.
+fn read_macrocell_tree(
+    path: &str,
+) -> Result<(PatternFile, Vec<Option<macrocell::MacrocellNode>>), String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read \"{}\": {}", path, error))?;
+    macrocell::read_tree(&contents)
+        .map_err(|error| format!("Failed to parse \"{}\": {}", path, error))
+}
+
+/// Read a pattern file and apply the transforms requested by the user. The
+/// format of the file is guessed from its name.
+fn load_pattern_file(placement: &PatternPlacement) -> Result<Pattern, String> {
+    let format = PatternFormat::from_file_name(&placement.name);
+    let pattern_file = read_pattern_file(&placement.name, format)?;
+
+    Ok(transform_pattern(pattern_file.pattern, placement))
+}
+
+/// Look up a pattern in the library and apply the transforms requested by the
+/// user.
+fn load_library_pattern(placement: &PatternPlacement) -> Result<Pattern, String> {
+    match library::find(&placement.name) {
+        Some(library_pattern) => Ok(transform_pattern(library_pattern.pattern(), placement)),
+        None => Err(format!(
+            "Unknown pattern \"{}\" (use --list-patterns to see the available patterns)",
+            placement.name
+        )),
+    }
+}
+
+/// Load every pattern that the user has asked for, along with the position
+/// of each pattern.
+fn load_patterns(args: &cli::PatternArgs) -> Result<Vec<(Pattern, Point)>, String> {
+    let files = args
+        .pattern_placements
+        .iter()
+        .map(|placement| Ok((load_pattern_file(placement)?, placement.position)));
+    let library_patterns = args
+        .library_placements
+        .iter()
+        .map(|placement| Ok((load_library_pattern(placement)?, placement.position)));
+
+    files.chain(library_patterns).collect()
+}
+
+/// Place the patterns that the user has asked for on the game board. If
+/// requested, the whole arrangement of patterns is moved to the centre of the
+/// board.
+fn create_pattern_cell_generator(
+    patterns: &[(Pattern, Point)],
+    center: bool,
+    game_board_size: Dimensions,
+) -> UserCellGenerator {
+    // Positions are moved so that the top-left of the arrangement is at the
+    // returned origin.
+    let origin = if center {
+        let min_x = patterns.iter().map(|(_, position)| position.x).min().unwrap_or(0);
+        let min_y = patterns.iter().map(|(_, position)| position.y).min().unwrap_or(0);
+        let max_x = patterns.iter().map(|(p, position)| position.x + p.size().width).max();
+        let max_y = patterns.iter().map(|(p, position)| position.y + p.size().height).max();
+        let width = max_x.unwrap_or(0) - min_x;
+        let height = max_y.unwrap_or(0) - min_y;
+
+        Some((
+            min_x,
+            min_y,
+            Point {
+                x: game_board_size.width.saturating_sub(width) / 2,
+                y: game_board_size.height.saturating_sub(height) / 2,
+            },
+        ))
     } else {
-        let path = Path::new(file_path_str);
+        None
+    };
 
-        let mut file = File::open(path).unwrap();
-        let mut output = String::new();
-        file.read_to_string(&mut output).unwrap();
-        output
+    let mut cell_generator = UserCellGenerator::new();
+    for (pattern, position) in patterns {
+        let position = match origin {
+            Some((min_x, min_y, origin)) => {
+                Point { x: origin.x + position.x - min_x, y: origin.y + position.y - min_y }
+            }
+            None => *position,
+        };
+        cell_generator.add_pattern(pattern, position);
+    }
+
+    cell_generator
+}
+
+/// Print the patterns in the library, grouped by category.
+fn list_patterns() {
+    for category in Category::ALL {
+        println!("{}:", category);
+        for library_pattern in library::LIBRARY.iter().filter(|p| p.category == category) {
+            println!("  {:<20} {}", library_pattern.name, library_pattern.description);
+        }
     }
 }
 
-fn create_game_board<CellGeneratorT: CellGenerator, RendererT: Renderer>(
+/// An error that stops a command, along with the exit code to report it
+/// with.
+enum CommandError {
+    /// The user asked for something that cannot be done, such as loading a
+    /// pattern that does not exist.
+    Usage(String),
+
+    /// The command failed while it was running, such as when an output file
+    /// cannot be written.
+    Failed(String),
+}
+
+impl CommandError {
+    /// Exit code for invalid input. This matches the exit code that clap uses
+    /// for invalid arguments.
+    const USAGE_EXIT_CODE: u8 = 2;
+
+    /// Exit code for failures while running a command.
+    const FAILED_EXIT_CODE: u8 = 1;
+
+    fn message(&self) -> &str {
+        match self {
+            CommandError::Usage(message) | CommandError::Failed(message) => message,
+        }
+    }
+
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            CommandError::Usage(_) => ExitCode::from(Self::USAGE_EXIT_CODE),
+            CommandError::Failed(_) => ExitCode::from(Self::FAILED_EXIT_CODE),
+        }
+    }
+}
+
+fn create_game_board<'a, CellGeneratorT: CellGenerator, RendererT: Renderer>(
     cell_generator: CellGeneratorT,
     size: Dimensions,
-    renderer: &mut RendererT,
-) -> GameBoard<RendererT> {
+    world: &cli::WorldArgs,
+    renderer: &'a mut RendererT,
+) -> GameBoard<'a, RendererT> {
     GameBoard::new_from_seed(size, cell_generator, renderer)
+        .with_rule(world.rule())
+        .with_topology(world.topology())
+}
+
+/// Create the game board of a game on an unbounded plane. The renderer only
+/// sees the visible area, so the statistics are recorded from the population
+/// of the whole game.
+fn create_quadtree_board<'a, RendererT: Renderer>(
+    tree: QuadTree,
+    size: Dimensions,
+    renderer: &'a mut RendererT,
+    statistics: Option<&'a mut StatisticsRecorder<BufWriter<File>>>,
+) -> QuadTreeBoard<'a, RendererT> {
+    let board = QuadTreeBoard::new(tree, size, renderer);
+    match statistics {
+        Some(statistics) => board.with_population_observer(Box::new(|population| {
+            statistics.record_population(population)
+        })),
+        None => board,
+    }
+}
+
+fn create_gif_recorder(
+    file_path_str: &str,
+    size: Dimensions,
+    cell_size: usize,
+    update_frequency: u32,
+) -> Result<GifRecorder<BufWriter<File>>, String> {
+    let file = File::create(file_path_str)
+        .map_err(|error| format!("Failed to create \"{}\": {}", file_path_str, error))?;
+    GifRecorder::new(BufWriter::new(file), size, cell_size, update_frequency)
 }
 
 fn calculate_game_board_size(
@@ -77,46 +277,786 @@ fn calculate_game_board_size(
     Dimensions { width, height }
 }
 
-fn main() {
-    let args = cli::Args::parse();
+/// Calculate the area of the game board that the random soup occupies. The
+/// soup is centred on the board, and fills the board in any dimension that the
+/// user does not define (reduced as necessary to fit the soup's symmetry).
+fn calculate_soup_area(
+    soup_size: Dimensions,
+    symmetry: Symmetry,
+    game_board_size: Dimensions,
+) -> (Point, Dimensions) {
+    let size = calculate_game_board_size(soup_size, game_board_size);
+    let size = symmetry.adjust_soup_size(Dimensions {
+        width: size.width.min(game_board_size.width),
+        height: size.height.min(game_board_size.height),
+    });
+    let position = Point {
+        x: (game_board_size.width - size.width) / 2,
+        y: (game_board_size.height - size.height) / 2,
+    };
+
+    (position, size)
+}
 
-    // Set up the TUI graphics renderer.
-    let mut renderer = TuiRenderer::new(DefaultPlotter::create_from_stdout(), args.grid_size);
-    renderer.initialize();
+fn create_statistics_recorder(
+    file_path_str: &str,
+    format: Option<StatisticsFormat>,
+) -> Result<StatisticsRecorder<BufWriter<File>>, String> {
+    let file = File::create(file_path_str)
+        .map_err(|error| format!("Failed to create \"{}\": {}", file_path_str, error))?;
+    let format = format.unwrap_or_else(|| StatisticsFormat::from_file_name(file_path_str));
+    Ok(StatisticsRecorder::new(BufWriter::new(file), format))
+}
 
-    // If the user has provided their own game seed, we should try to use it.
-    let game_board_seed = get_game_board_seed_from_file(&args.game_board_file);
-    let game_board_size = calculate_game_board_size(args.grid_size, renderer.get_grid_size());
+/// The patterns that a game starts with.
+enum BoardPatterns {
+    /// Patterns that are placed on a game board of a fixed size.
+    Patterns(Vec<(Pattern, Point)>),
 
-    let mut game_board = if game_board_seed.is_empty() {
-        create_game_board(
-            RandomCellGenerator { rng: rand::thread_rng() },
-            game_board_size,
-            &mut renderer,
-        )
-    } else {
-        create_game_board(
-            UserCellGenerator::from_str(&game_board_seed).unwrap(),
-            game_board_size,
-            &mut renderer,
-        )
+    /// A macrocell pattern, along with any other patterns, on an unbounded
+    /// plane.
+    QuadTree(QuadTree),
+}
+
+/// How a game starts.
+enum GameSeed {
+    /// Creates the initial cells of a game board of a fixed size.
+    Cells(Box<dyn CellGenerator>),
+
+    /// A game on an unbounded plane.
+    QuadTree(QuadTree),
+}
+
+/// A game that is ready to be played.
+struct Game {
+    seed: GameSeed,
+
+    /// Size of the game board (or the visible part of an unbounded plane).
+    size: Dimensions,
+
+    /// Shown when the game starts, such as the seed of a random soup (so that
+    /// it can be reproduced).
+    message: Option<String>,
+}
+
+/// Load the patterns that the user has asked for, and check that the options
+/// for a game board can be used together. This is done before the terminal
+/// user interface takes over the screen, so that errors are easy to read.
+///
+/// Macrocell files are loaded straight into a quadtree, as they can hold far
+/// more cells than a game board of a fixed size.
+fn load_board_patterns(board: &cli::BoardArgs) -> Result<BoardPatterns, CommandError> {
+    if board.census && board.world.rule() != Rule::CONWAY {
+        return Err(CommandError::Usage(format!(
+            "The census is only available for {} (the rule is {})",
+            Rule::CONWAY,
+            board.world.rule()
+        )));
+    }
+
+    let (macrocell_placements, other_placements): (Vec<_>, Vec<_>) =
+        board.patterns.pattern_placements.iter().cloned().partition(|placement| {
+            PatternFormat::from_file_name(&placement.name) == PatternFormat::Macrocell
+        });
+    let [macrocell_placement] = &macrocell_placements[..] else {
+        if macrocell_placements.is_empty() {
+            return load_patterns(&board.patterns)
+                .map(BoardPatterns::Patterns)
+                .map_err(CommandError::Usage);
+        }
+        return Err(CommandError::Usage(
+            "Only one macrocell file can be loaded at a time".to_string(),
+        ));
     };
+    if !macrocell_placement.transforms.is_empty() {
+        return Err(CommandError::Usage("Macrocell patterns cannot be transformed".to_string()));
+    }
+    if board.world.topology.is_some() {
+        return Err(CommandError::Usage(
+            "Macrocell patterns are played on an unbounded plane (--topology cannot be used)"
+                .to_string(),
+        ));
+    }
+    if board.center {
+        return Err(CommandError::Usage("Macrocell patterns cannot be centred".to_string()));
+    }
+    if board.census {
+        return Err(CommandError::Usage(
+            "The census is not available for macrocell patterns".to_string(),
+        ));
+    }
 
-    let nanos_per_iteration = ((1.0 / args.update_frequency as f64) * 1000000000.0) as u32;
-    let mut exiting = false;
-    let ctrl_c_keyevent =
-        event::KeyEvent::new(event::KeyCode::Char('c'), event::KeyModifiers::CONTROL);
+    let (file, nodes) =
+        read_macrocell_tree(&macrocell_placement.name).map_err(CommandError::Usage)?;
+    let rule = board.world.rule.or(file.rule).unwrap_or(Rule::CONWAY);
+    let position = macrocell_placement.position;
+    let mut tree = QuadTree::from_macrocell(&nodes, (position.x as i64, position.y as i64), rule)
+        .map_err(CommandError::Usage)?;
+
+    let other_patterns = cli::PatternArgs {
+        pattern_placements: other_placements,
+        library_placements: board.patterns.library_placements.clone(),
+    };
+    for (pattern, position) in load_patterns(&other_patterns).map_err(CommandError::Usage)? {
+        for cell in pattern.cells() {
+            tree.set_cell((position.x + cell.x) as i64, (position.y + cell.y) as i64);
+        }
+    }
+
+    Ok(BoardPatterns::QuadTree(tree))
+}
+
+/// Set up a game with the user's patterns, or a random soup if there are none.
+///
+/// ## Arguments
+///
+/// * `board`: The user's game board options.
+/// * `patterns`: The user's patterns (see `load_board_patterns()`).
+/// * `default_grid_size`: Size of the game board if the user does not give
+///   one.
+fn create_game(
+    board: &cli::BoardArgs,
+    patterns: BoardPatterns,
+    default_grid_size: Dimensions,
+) -> Game {
+    let size = calculate_game_board_size(board.grid_size, default_grid_size);
+    let patterns = match patterns {
+        BoardPatterns::Patterns(patterns) => patterns,
+        BoardPatterns::QuadTree(tree) => {
+            let message = format!(
+                "{} cells on an unbounded plane, showing the middle of the pattern",
+                tree.population()
+            );
+            return Game { seed: GameSeed::QuadTree(tree), size, message: Some(message) };
+        }
+    };
 
-    while !exiting {
+    if !patterns.is_empty() {
+        let cell_generator = create_pattern_cell_generator(&patterns, board.center, size);
+        return Game { seed: GameSeed::Cells(Box::new(cell_generator)), size, message: None };
+    }
+
+    // Random soups always have a seed, so that the soup can be reproduced.
+    let soup_args = &board.soup;
+    let soup = SoupSettings {
+        seed: soup_args.seed.unwrap_or_else(rand::random),
+        density: soup_args.density,
+        symmetry: soup_args.symmetry,
+    };
+    let (soup_position, soup_size) = calculate_soup_area(soup_args.soup_size, soup.symmetry, size);
+    let soup_message =
+        format!("Soup seed: {} (density {}, symmetry {})", soup.seed, soup.density, soup.symmetry);
+
+    Game {
+        seed: GameSeed::Cells(soup.create_generator(soup_position, soup_size)),
+        size,
+        message: Some(soup_message),
+    }
+}
+
+/// Create the statistics recorder for a game, if the user asked for one.
+fn create_board_statistics_recorder(
+    board: &cli::BoardArgs,
+) -> Result<Option<StatisticsRecorder<BufWriter<File>>>, CommandError> {
+    board
+        .stats_out
+        .as_ref()
+        .map(|stats_path| create_statistics_recorder(stats_path, board.stats_format))
+        .transpose()
+        .map_err(CommandError::Failed)
+}
+
+/// Run a game as fast as possible, without reading any input.
+///
+/// ## Returns
+///
+/// The live cells of the final generation.
+fn simulate<'a>(
+    board: &cli::BoardArgs,
+    game: Game,
+    generations: usize,
+    renderers: &mut MultiRenderer<'a>,
+    statistics: Option<&'a mut StatisticsRecorder<BufWriter<File>>>,
+) -> Vec<Point> {
+    match game.seed {
+        GameSeed::Cells(cell_generator) => {
+            if let Some(statistics) = statistics {
+                renderers.add(Box::new(statistics));
+            }
+            simulate_board(
+                create_game_board(cell_generator, game.size, &board.world, renderers),
+                generations,
+            )
+        }
+        GameSeed::QuadTree(tree) => simulate_board(
+            create_quadtree_board(tree, game.size, renderers, statistics),
+            generations,
+        ),
+    }
+}
+
+fn simulate_board<BoardT: Board>(mut game_board: BoardT, generations: usize) -> Vec<Point> {
+    for _ in 0..generations {
         game_board.calculate_iteration();
-        thread::sleep(Duration::new(0, nanos_per_iteration));
+    }
+
+    game_board.get_live_cells()
+}
+
+/// Play the game in the terminal until the user exits or the generation limit
+/// is reached.
+///
+/// ## Returns
+///
+/// The live cells of the final generation.
+fn play<'a, PlotterT: Plotter>(
+    args: &cli::RunArgs,
+    game: Game,
+    tui: &RefCell<TuiRenderer<PlotterT>>,
+    recorder: Option<&RefCell<GifRecorder<BufWriter<File>>>>,
+    renderers: &mut MultiRenderer<'a>,
+    statistics: Option<&'a mut StatisticsRecorder<BufWriter<File>>>,
+) -> Result<Vec<Point>, String> {
+    match game.seed {
+        GameSeed::Cells(cell_generator) => {
+            if let Some(statistics) = statistics {
+                renderers.add(Box::new(statistics));
+            }
+            play_board(
+                args,
+                create_game_board(cell_generator, game.size, &args.board.world, renderers),
+                tui,
+                recorder,
+            )
+        }
+        GameSeed::QuadTree(tree) => {
+            let game_board = create_quadtree_board(tree, game.size, renderers, statistics);
+            play_board(args, game_board, tui, recorder)
+        }
+    }
+}
+
+fn play_board<BoardT: Board, PlotterT: Plotter>(
+    args: &cli::RunArgs,
+    mut game_board: BoardT,
+    tui: &RefCell<TuiRenderer<PlotterT>>,
+    recorder: Option<&RefCell<GifRecorder<BufWriter<File>>>>,
+) -> Result<Vec<Point>, String> {
+    let mut source = TerminalEventSource::new();
+    play_board_with_events(args, &mut game_board, tui, recorder, &mut source)
+        .map_err(|error| format!("Failed to read from the terminal: {}", error))?;
+
+    Ok(game_board.get_live_cells())
+}
+
+/// Play a game, one generation per tick, until the generation limit is
+/// reached or the user quits.
+///
+/// ## Arguments
+///
+/// * `args`: The arguments of the `run` subcommand.
+/// * `game_board`: The board to play.
+/// * `tui`: The terminal user interface, which handles the user's keys. The
+///   game board draws into it too, so it is only borrowed for a moment.
+/// * `recorder`: The GIF recorder, if the game is being recorded.
+/// * `source`: Where the user's input comes from.
+///
+/// ## Returns
+///
+/// An error if the user's input could not be read.
+fn play_board_with_events<BoardT: Board, PlotterT: Plotter, SourceT: EventSource>(
+    args: &cli::RunArgs,
+    game_board: &mut BoardT,
+    tui: &RefCell<TuiRenderer<PlotterT>>,
+    recorder: Option<&RefCell<GifRecorder<BufWriter<File>>>>,
+    source: &mut SourceT,
+) -> Result<(), std::io::Error> {
+    if args.generations == Some(0) {
+        return Ok(());
+    }
+
+    let mut generation = 0;
+    let event_loop = EventLoop::new(Duration::from_secs_f64(1.0 / args.update_frequency as f64));
+
+    event_loop.run(source, |event| {
+        match event {
+            Event::Tick => {
+                game_board.calculate_iteration();
+                generation += 1;
+            }
+            Event::Key(key_event) => {
+                // The borrow ends before the game board draws anything.
+                let action = tui.borrow_mut().handle_key(key_event);
+                if let Some(action) = action {
+                    let flow = run_ui_action(
+                        action,
+                        game_board,
+                        &mut generation,
+                        args.generations,
+                        &event_loop,
+                        recorder,
+                    );
+                    if flow == Flow::Exit {
+                        return flow;
+                    }
+                }
+            }
+            // The renderer notices when the terminal is resized by itself.
+            _ => {}
+        }
+
+        if args.generations.is_some_and(|limit| generation >= limit) {
+            Flow::Exit
+        } else {
+            Flow::Continue
+        }
+    })
+}
+
+/// Do what the user asked for with the game's controls (see `GameUi`), and
+/// show them what happened.
+///
+/// ## Arguments
+///
+/// * `action`: The action to take.
+/// * `game_board`: The board being played.
+/// * `generation`: The number of generations that have been played.
+/// * `generation_limit`: The number of generations to run before exiting, if
+///   any.
+/// * `event_loop`: The event loop that plays the game.
+/// * `recorder`: The GIF recorder, if the game is being recorded.
+///
+/// ## Returns
+///
+/// Whether to keep playing the game.
+fn run_ui_action<BoardT: Board>(
+    action: UiAction,
+    game_board: &mut BoardT,
+    generation: &mut usize,
+    generation_limit: Option<usize>,
+    event_loop: &EventLoop,
+    recorder: Option<&RefCell<GifRecorder<BufWriter<File>>>>,
+) -> Flow {
+    let message = match action {
+        UiAction::Quit => return Flow::Exit,
+        UiAction::InsertPattern(library_pattern) => {
+            insert_pattern_at_center(game_board, &library_pattern.pattern());
+            format!("Inserted {}.", library_pattern.name)
+        }
+        UiAction::RunCommand(command) => {
+            run_command(command, game_board, generation, generation_limit, event_loop, recorder)
+                .unwrap_or_else(|error| format!("Error: {}", error))
+        }
+        UiAction::ShowMessage(message) => message,
+    };
+
+    game_board.get_renderer().show_message(&message);
+    Flow::Continue
+}
 
-        if event::poll(Duration::from_secs(0)).unwrap() {
-            // User made a keypress, check if they CTRL+C'd...
-            match event::read().unwrap() {
-                event::Event::Key(key_event) => exiting = key_event == ctrl_c_keyevent,
-                _ => {}
+/// Run a command typed into the command line.
+///
+/// ## Arguments
+///
+/// * `command`: The command to run.
+/// * `game_board`: The board being played.
+/// * `generation`: The number of generations that have been played.
+/// * `generation_limit`: The number of generations to run before exiting, if
+///   any. `:goto` stops at the limit.
+/// * `event_loop`: The event loop that plays the game.
+/// * `recorder`: The GIF recorder, if the game is being recorded. `:speed`
+///   changes its frame delay so that the recording keeps up with the game.
+///
+/// ## Returns
+///
+/// A message describing what the command did, or an error.
+fn run_command<BoardT: Board>(
+    command: Command,
+    game_board: &mut BoardT,
+    generation: &mut usize,
+    generation_limit: Option<usize>,
+    event_loop: &EventLoop,
+    recorder: Option<&RefCell<GifRecorder<BufWriter<File>>>>,
+) -> Result<String, String> {
+    match command {
+        Command::Rule(rule) => {
+            game_board.set_rule(rule)?;
+            Ok(format!("Playing by {}.", rule))
+        }
+        Command::Load(path) => {
+            let pattern_file = read_pattern_file(&path, PatternFormat::from_file_name(&path))?;
+            insert_pattern_at_center(game_board, &pattern_file.pattern);
+            Ok(format!("Loaded {}.", path))
+        }
+        Command::Goto(target) => {
+            if target < *generation {
+                return Err(format!("Generation {} has already been played", target));
+            }
+            let target = generation_limit.map_or(target, |limit| target.min(limit));
+            while *generation < target {
+                game_board.calculate_iteration();
+                *generation += 1;
             }
+            Ok(format!("Reached generation {}.", generation))
+        }
+        Command::Speed(speed) => {
+            event_loop.set_tick_interval(Duration::from_secs_f64(1.0 / speed as f64));
+            if let Some(recorder) = recorder {
+                recorder.borrow_mut().set_update_frequency(speed);
+            }
+            Ok(format!("Playing {} generations per second.", speed))
+        }
+    }
+}
+
+/// Insert a pattern in the middle of the game board.
+fn insert_pattern_at_center<BoardT: Board>(game_board: &mut BoardT, pattern: &Pattern) {
+    let board_size = game_board.get_size();
+    let pattern_size = pattern.size();
+    let position = Point {
+        x: board_size.width.saturating_sub(pattern_size.width) / 2,
+        y: board_size.height.saturating_sub(pattern_size.height) / 2,
+    };
+
+    game_board.insert_pattern(pattern, position);
+}
+
+/// Finish writing a game's outputs, and print the census if it was requested.
+/// Every output is finished even if an earlier one fails.
+fn finish_game(
+    board: &cli::BoardArgs,
+    board_size: Dimensions,
+    final_cells: &[Point],
+    recorder: Option<GifRecorder<BufWriter<File>>>,
+    statistics: Option<StatisticsRecorder<BufWriter<File>>>,
+) -> Result<(), CommandError> {
+    let recorder_result = recorder
+        .map_or(Ok(()), |recorder| recorder.finish().map(drop))
+        .map_err(|error| format!("Failed to record GIF: {}", error));
+    let statistics_result = statistics
+        .map_or(Ok(()), |statistics| statistics.finish().map(drop))
+        .map_err(|error| format!("Failed to write statistics: {}", error));
+
+    let mut census_result = Ok(());
+    if board.census {
+        // Objects on a torus can straddle the edges of the board.
+        let cells = match board.world.topology() {
+            Topology::Torus => life::from_torus_points(final_cells, board_size),
+            Topology::Bounded => Some(life::from_points(final_cells)),
+        };
+        match cells {
+            Some(cells) => {
+                let census = Census::from_cells(&cells);
+                println!("Census ({} objects):", census.total());
+                print!("{}", census);
+            }
+            None => {
+                census_result = Err("Failed to take a census: live cells wrap all of the way \
+                    around the board"
+                    .to_string())
+            }
+        }
+    }
+
+    recorder_result.and(statistics_result).and(census_result).map_err(CommandError::Failed)
+}
+
+/// Run the `run` subcommand, playing the game in the terminal.
+fn run_in_terminal(args: &cli::RunArgs) -> Result<(), CommandError> {
+    if args.list_patterns {
+        list_patterns();
+        return Ok(());
+    }
+
+    let patterns = load_board_patterns(&args.board)?;
+
+    let chart_settings =
+        ChartSettings { height: args.chart_height, show_births_and_deaths: args.chart_overlays };
+    let mut plotter = DefaultPlotter::create_from_stdout();
+    if let Some(color_support) = args.colors {
+        plotter.set_color_support(color_support);
+    }
+    let mut tui =
+        TuiRenderer::new(BufferedPlotter::new(plotter), args.board.grid_size, chart_settings);
+    tui.initialize();
+
+    let game = create_game(&args.board, patterns, tui.get_grid_size());
+    if let Some(message) = &game.message {
+        tui.print_message(message);
+    }
+
+    let recorder = args
+        .record_gif
+        .as_ref()
+        .map(|gif_path| {
+            create_gif_recorder(gif_path, game.size, args.gif_cell_size, args.update_frequency)
+        })
+        .transpose()
+        .map_err(CommandError::Failed)?
+        .map(RefCell::new);
+    let mut statistics = create_board_statistics_recorder(&args.board)?;
+
+    // Every renderer observes the same game board.
+    let tui = RefCell::new(tui);
+    let board_size = game.size;
+    let final_cells = {
+        let mut renderers = MultiRenderer::new();
+        renderers.add(Box::new(&tui));
+        if let Some(recorder) = &recorder {
+            renderers.add(Box::new(recorder));
+        }
+
+        play(args, game, &tui, recorder.as_ref(), &mut renderers, statistics.as_mut())
+    };
+
+    // The terminal user interface is shut down before anything is printed.
+    drop(tui);
+    let final_cells = final_cells.map_err(CommandError::Failed)?;
+    finish_game(
+        &args.board,
+        board_size,
+        &final_cells,
+        recorder.map(RefCell::into_inner),
+        statistics,
+    )
+}
+
+/// Run the `headless` subcommand, playing the game without the terminal user
+/// interface.
+fn run_headless(args: &cli::HeadlessArgs) -> Result<(), CommandError> {
+    let patterns = load_board_patterns(&args.board)?;
+    let game = create_game(&args.board, patterns, DEFAULT_HEADLESS_GRID_SIZE);
+    if let Some(message) = &game.message {
+        println!("{}", message);
+    }
+
+    let mut statistics = create_board_statistics_recorder(&args.board)?;
+    let board_size = game.size;
+    let final_cells = simulate(
+        &args.board,
+        game,
+        args.generations,
+        &mut MultiRenderer::new(),
+        statistics.as_mut(),
+    );
+
+    finish_game(&args.board, board_size, &final_cells, None, statistics)
+}
+
+/// Run the `render` subcommand, recording the game as an animated GIF without
+/// the terminal user interface.
+fn render_gif(args: &cli::RenderArgs) -> Result<(), CommandError> {
+    let patterns = load_board_patterns(&args.board)?;
+    let game = create_game(&args.board, patterns, DEFAULT_HEADLESS_GRID_SIZE);
+    if let Some(message) = &game.message {
+        println!("{}", message);
+    }
+
+    let mut recorder =
+        create_gif_recorder(&args.output, game.size, args.gif_cell_size, args.update_frequency)
+            .map_err(CommandError::Failed)?;
+    let mut statistics = create_board_statistics_recorder(&args.board)?;
+    let board_size = game.size;
+    let final_cells = {
+        let mut renderers = MultiRenderer::new();
+        renderers.add(Box::new(&mut recorder));
+
+        simulate(&args.board, game, args.generations, &mut renderers, statistics.as_mut())
+    };
+
+    finish_game(&args.board, board_size, &final_cells, Some(recorder), statistics)
+}
+
+/// Run the `convert` subcommand, converting a pattern file to another format.
+fn convert_pattern_file(args: &cli::ConvertArgs) -> Result<(), CommandError> {
+    let input_format = args.from.unwrap_or_else(|| PatternFormat::from_file_name(&args.input));
+    let output_format = match &args.output {
+        Some(path) => args.to.unwrap_or_else(|| PatternFormat::from_file_name(path)),
+        None => args.to.unwrap_or(PatternFormat::Rle),
+    };
+
+    // Macrocell files are copied node by node, so that patterns too large to
+    // expand can still be converted.
+    let contents =
+        if input_format == PatternFormat::Macrocell && output_format == PatternFormat::Macrocell {
+            let (file, nodes) = read_macrocell_tree(&args.input).map_err(CommandError::Usage)?;
+            macrocell::write_tree(&file, &nodes)
+        } else {
+            read_pattern_file(&args.input, input_format)
+                .map_err(CommandError::Usage)?
+                .write(output_format)
+        };
+
+    match &args.output {
+        Some(path) => fs::write(path, contents).map_err(|error| {
+            CommandError::Failed(format!("Failed to write \"{}\": {}", path, error))
+        }),
+        None => {
+            print!("{}", contents);
+            Ok(())
         }
     }
 }
+
+/// Load the user's patterns onto an unbounded plane for analysis.
+fn load_pattern_cells(args: &cli::PatternArgs) -> Result<life::CellSet, CommandError> {
+    let patterns = load_patterns(args).map_err(CommandError::Usage)?;
+    if patterns.is_empty() {
+        return Err(CommandError::Usage(
+            "No pattern given (use -f or -p to choose one)".to_string(),
+        ));
+    }
+
+    let mut cells = life::CellSet::default();
+    for (pattern, position) in &patterns {
+        cells.extend(
+            pattern
+                .cells()
+                .iter()
+                .map(|cell| ((position.x + cell.x) as i64, (position.y + cell.y) as i64)),
+        );
+    }
+
+    Ok(cells)
+}
+
+/// Run the `analyze` subcommand, printing how the user's pattern behaves.
+fn analyze_pattern(args: &cli::AnalyzeArgs) -> Result<(), CommandError> {
+    let cells = load_pattern_cells(&args.patterns)?;
+    print!("{}", analysis::analyze(&cells, args.generations));
+    Ok(())
+}
+
+/// Run the `measure` subcommand, printing the lifespan of the user's pattern.
+fn measure_pattern(args: &cli::MeasureArgs) -> Result<(), CommandError> {
+    let cells = load_pattern_cells(&args.patterns)?;
+    print!("{}", analysis::lifespan::measure(&cells, args.generations));
+    Ok(())
+}
+
+/// Run the `search` subcommand, logging rare objects until the requested
+/// number of soups have been searched (or forever).
+fn search_soups(args: &cli::SearchArgs) -> Result<(), CommandError> {
+    let soup_size = calculate_game_board_size(args.soup.soup_size, DEFAULT_SEARCH_SOUP_SIZE);
+    let settings = SearchSettings {
+        soup_size: args.soup.symmetry.adjust_soup_size(soup_size),
+        density: args.soup.density,
+        symmetry: args.soup.symmetry,
+        max_generations: args.generations,
+        min_rare_period: args.min_period,
+    };
+    let first_seed = args.soup.seed.unwrap_or_else(rand::random);
+    let threads = args
+        .threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |threads| threads.get()));
+    let results =
+        OpenOptions::new().create(true).append(true).open(&args.results).map_err(|error| {
+            CommandError::Failed(format!("Failed to open \"{}\": {}", args.results, error))
+        })?;
+
+    eprintln!(
+        "Searching {} {} soups from seed {} on {} threads, logging rare objects to \"{}\".",
+        settings.soup_size, settings.symmetry, first_seed, threads, args.results
+    );
+    let start = Instant::now();
+    let census =
+        search::run_search(&settings, first_seed, args.soups, threads, results, |soups, census| {
+            let soups_per_second = soups as f64 / start.elapsed().as_secs_f64();
+            eprintln!("{} soups ({:.1}/s), {} objects.", soups, soups_per_second, census.total());
+        })
+        .map_err(CommandError::Failed)?;
+
+    println!("Census ({} objects):", census.total());
+    print!("{}", census);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = match cli::Args::parse().into_command() {
+        cli::Command::Run(run_args) => run_in_terminal(&run_args),
+        cli::Command::Headless(headless_args) => run_headless(&headless_args),
+        cli::Command::Convert(convert_args) => convert_pattern_file(&convert_args),
+        cli::Command::Analyze(analyze_args) => analyze_pattern(&analyze_args),
+        cli::Command::Measure(measure_args) => measure_pattern(&measure_args),
+        cli::Command::Search(search_args) => search_soups(&search_args),
+        cli::Command::Render(render_args) => render_gif(&render_args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {}", error.message());
+            error.exit_code()
+        }
+    }
+}
+
+#[cfg(test)]
+mod main_tests {
+    use super::*;
+    use crate::game::renderer::mock::MockRenderer;
+
+    /// Write a macrocell file whose pattern is far too large to expand: a
+    /// block repeated along the diagonal of the tree, so that the file is tiny
+    /// but the pattern has 2^30 cells.
+    fn write_huge_macrocell_file(name: &str) -> String {
+        let mut contents = "[M2]\n**$**$\n4 1 1 1 1\n".to_string();
+        for level in 5..=30 {
+            contents.push_str(&format!("{} {} {} {} {}\n", level, level - 3, 0, 0, level - 3));
+        }
+        let path =
+            std::env::temp_dir().join(format!("game_of_life_{}_{}.mc", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn loading_a_macrocell_file_too_large_to_expand_fails() {
+        let path = write_huge_macrocell_file("load");
+
+        let mut renderer = MockRenderer::new();
+        let mut game_board = GameBoard::new_from_seed(
+            renderer.get_grid_size(),
+            UserCellGenerator::new(),
+            &mut renderer,
+        );
+        let result = run_command(
+            Command::Load(path.clone()),
+            &mut game_board,
+            &mut 0,
+            None,
+            &EventLoop::new(Duration::from_millis(100)),
+            None,
+        );
+        let _ = fs::remove_file(&path);
+
+        assert!(result.unwrap_err().contains("too large"));
+    }
+
+    #[test]
+    fn macrocell_files_too_large_to_expand_are_only_converted_to_macrocell() {
+        let input = write_huge_macrocell_file("convert");
+        let output = input.replace(".mc", "_copy.mc");
+        let convert = |output: &str| {
+            convert_pattern_file(&cli::ConvertArgs {
+                input: input.clone(),
+                output: Some(output.to_string()),
+                from: None,
+                to: None,
+            })
+        };
+
+        let to_rle = convert(&input.replace(".mc", ".rle"));
+        let to_macrocell = convert(&output);
+        let copy = fs::read_to_string(&output);
+        let _ = fs::remove_file(&input);
+        let _ = fs::remove_file(&output);
+
+        assert!(matches!(to_rle, Err(CommandError::Usage(_))));
+        assert!(to_macrocell.is_ok());
+        assert_eq!(
+            1 << 30,
+            macrocell::count_cells(&macrocell::read_tree(&copy.unwrap()).unwrap().1)
+        );
+    }
+}
@@ -0,0 +1,303 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tui::components::FileBrowser;
+use tui::event::{KeyCode, KeyEvent};
+use tui::Paintbrush;
+use xy_utils::{Dimensions, Point};
+
+use crate::game::{Pattern, PatternFile, PatternFormat};
+
+/// Largest file that is previewed, so that moving the selection over a large
+/// file does not hold up the game.
+const MAX_PREVIEW_FILE_SIZE: u64 = 1 << 20;
+
+/// The extensions of the pattern files that can be opened (see
+/// `PatternFormat::from_file_name()`).
+const PATTERN_EXTENSIONS: [&str; 6] = ["cells", "rle", "lif", "life", "mc", "txt"];
+
+/// Something that the game needs to do in response to the pattern browser.
+#[derive(Debug, PartialEq)]
+pub enum BrowserAction {
+    /// The browser has changed (e.g. another file is selected), so it needs
+    /// to be shown again.
+    Updated,
+
+    /// The browser was closed without choosing a file.
+    Closed,
+
+    /// A directory could not be read. The browser stays open.
+    Failed(String),
+
+    /// Open the chosen pattern file. The browser is closed.
+    Open(PathBuf),
+}
+
+/// Browser that lets the user open a pattern file while the game is running.
+///
+/// The browser lists the directories and pattern files in a directory, with a
+/// preview of the selected pattern, and is shown over the game board while it
+/// is open. The arrow keys choose a file, `Enter` opens a directory or file,
+/// `Backspace` goes up a directory and `Esc` closes the browser.
+pub struct PatternBrowser {
+    /// The browser, which is created when it is first opened.
+    file_browser: Option<FileBrowser>,
+    is_open: bool,
+}
+
+impl PatternBrowser {
+    pub fn new() -> Self {
+        PatternBrowser { file_browser: None, is_open: false }
+    }
+
+    /// Open the browser. It reopens where it was last closed, or in the
+    /// current directory when it is opened for the first time.
+    ///
+    /// ## Returns
+    ///
+    /// An error if the current directory could not be read.
+    pub fn open(&mut self) -> Result<(), String> {
+        self.open_in(Path::new("."))
+    }
+
+    /// Open the browser, in the given directory if it is opened for the first
+    /// time.
+    fn open_in(&mut self, directory: &Path) -> Result<(), String> {
+        if self.file_browser.is_none() {
+            // The renderer decides where the browser goes.
+            let file_browser = FileBrowser::new(
+                Paintbrush::create_default(),
+                Point { x: 0, y: 0 },
+                Dimensions::create_empty(),
+                directory,
+                &PATTERN_EXTENSIONS,
+            )
+            .map_err(|error| format!("Failed to read \"{}\": {}", directory.display(), error))?;
+            self.file_browser = Some(file_browser);
+            self.show_preview();
+        }
+
+        self.is_open = true;
+        Ok(())
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Get the file browser to show while the browser is open.
+    pub fn file_browser(&self) -> Option<&FileBrowser> {
+        self.file_browser.as_ref().filter(|_| self.is_open)
+    }
+
+    pub fn file_browser_mut(&mut self) -> Option<&mut FileBrowser> {
+        self.file_browser.as_mut().filter(|_| self.is_open)
+    }
+
+    /// Handle a key press.
+    ///
+    /// ## Arguments
+    ///
+    /// * `key_event`: The key that was pressed.
+    ///
+    /// ## Returns
+    ///
+    /// The action to take in response to the key, if any. Keys are ignored
+    /// while the browser is closed.
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> Option<BrowserAction> {
+        let file_browser = self.file_browser.as_mut().filter(|_| self.is_open)?;
+        if key_event.code == KeyCode::Escape {
+            self.is_open = false;
+            return Some(BrowserAction::Closed);
+        }
+
+        let directory = file_browser.directory().to_path_buf();
+        match file_browser.handle_key(&key_event) {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(error) => {
+                return Some(BrowserAction::Failed(format!(
+                    "Failed to open a directory in \"{}\": {}",
+                    directory.display(),
+                    error
+                )))
+            }
+        }
+
+        if let Some(path) = file_browser.take_chosen() {
+            self.is_open = false;
+            return Some(BrowserAction::Open(path));
+        }
+        self.show_preview();
+        Some(BrowserAction::Updated)
+    }
+
+    /// Show a preview of the selected file, if it is a pattern that can be
+    /// read. Nothing is shown for a directory, a file that can't be read, or a
+    /// file that is too large to preview.
+    fn show_preview(&mut self) {
+        let Some(file_browser) = self.file_browser.as_mut() else {
+            return;
+        };
+        let Some(path) = file_browser.selected_file() else {
+            return;
+        };
+
+        if let Ok(pattern) = read_preview(path) {
+            file_browser.set_preview(pattern.size(), pattern.cells());
+        }
+    }
+}
+
+/// Read the pattern of a file to preview. Patterns are read the same way as
+/// when the file is opened, so macrocell files too large to open (see
+/// `macrocell::MAX_EXPANDED_CELLS`) are not previewed either.
+fn read_preview(path: &Path) -> Result<Pattern, String> {
+    let file_size = fs::metadata(path).map_err(|error| error.to_string())?.len();
+    if file_size > MAX_PREVIEW_FILE_SIZE {
+        return Err(format!("The file is too large to preview ({} bytes)", file_size));
+    }
+
+    let contents = fs::read_to_string(path).map_err(|error| error.to_string())?;
+    let format = PatternFormat::from_file_name(&path.to_string_lossy());
+    Ok(PatternFile::parse(&contents, format)?.pattern)
+}
+
+#[cfg(test)]
+mod pattern_browser_tests {
+    use super::*;
+    use tui::layout::Rect;
+    use tui::mock::ScreenPlotter;
+    use tui::Component;
+
+    /// A directory of pattern files for a test, which is deleted when the test
+    /// ends.
+    struct TestDirectory(PathBuf);
+
+    impl TestDirectory {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "game_of_life_pattern_browser_{}_{}",
+                name,
+                std::process::id()
+            ));
+            fs::create_dir_all(&path).unwrap();
+            fs::write(path.join("blinker.cells"), "OOO\n").unwrap();
+            fs::write(path.join("notes.md"), "").unwrap();
+            TestDirectory(path)
+        }
+    }
+
+    impl Drop for TestDirectory {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn press(browser: &mut PatternBrowser, code: KeyCode) -> Option<BrowserAction> {
+        browser.handle_key(KeyEvent::from(code))
+    }
+
+    #[test]
+    fn keys_are_ignored_until_the_browser_is_opened() {
+        let directory = TestDirectory::new("ignored");
+        let mut browser = PatternBrowser::new();
+
+        assert_eq!(None, press(&mut browser, KeyCode::Down));
+        assert!(browser.file_browser().is_none());
+
+        browser.open_in(&directory.0).unwrap();
+        assert!(browser.is_open());
+        assert_eq!(Some(BrowserAction::Updated), press(&mut browser, KeyCode::Down));
+    }
+
+    #[test]
+    fn enter_opens_the_selected_pattern_file() {
+        let directory = TestDirectory::new("enter");
+        let mut browser = PatternBrowser::new();
+        browser.open_in(&directory.0).unwrap();
+        press(&mut browser, KeyCode::End);
+
+        let path = fs::canonicalize(directory.0.join("blinker.cells")).unwrap();
+        assert_eq!(Some(BrowserAction::Open(path)), press(&mut browser, KeyCode::Enter));
+        assert!(!browser.is_open());
+        assert_eq!(None, press(&mut browser, KeyCode::Enter));
+    }
+
+    #[test]
+    fn escape_closes_the_browser_where_it_was() {
+        let directory = TestDirectory::new("escape");
+        let mut browser = PatternBrowser::new();
+        browser.open_in(&directory.0).unwrap();
+        press(&mut browser, KeyCode::End);
+
+        assert_eq!(Some(BrowserAction::Closed), press(&mut browser, KeyCode::Escape));
+        browser.open().unwrap();
+        let file_browser = browser.file_browser().unwrap();
+        assert_eq!(fs::canonicalize(&directory.0).unwrap(), file_browser.directory());
+        assert!(file_browser.selected_file().is_some());
+    }
+
+    #[test]
+    fn previews_the_selected_pattern() {
+        let directory = TestDirectory::new("preview");
+        let mut browser = PatternBrowser::new();
+        browser.open_in(&directory.0).unwrap();
+        press(&mut browser, KeyCode::End);
+
+        let mut file_browser = browser.file_browser().unwrap().clone();
+        let size = Dimensions { width: 52, height: 5 };
+        let mut plotter = ScreenPlotter::new(size);
+        Component::<ScreenPlotter>::set_area(
+            &mut file_browser,
+            Rect::new(Point { x: 0, y: 0 }, size),
+        );
+        file_browser.render(&mut plotter).unwrap();
+
+        let preview_row = plotter.text().lines().nth(2).unwrap().to_string();
+        assert!(preview_row.ends_with("│          ▀▀▀           │"), "{}", preview_row);
+    }
+
+    #[test]
+    fn large_patterns_are_not_previewed() {
+        let directory = TestDirectory::new("large");
+        let read = |name: &str, contents: &str| {
+            let path = directory.0.join(name);
+            fs::write(&path, contents).unwrap();
+            read_preview(&path)
+        };
+
+        assert!(read("small.mc", "[M2]\n*$\n4 1 1 1 1\n").is_ok());
+        // A block repeated over a square of 2^30 by 2^30 cells.
+        let mut huge_tree = "[M2]\n**$**$\n".to_string();
+        for level in 4..=30 {
+            huge_tree.push_str(&format!("{} {1} {1} {1} {1}\n", level, level - 3));
+        }
+        assert!(read("huge.mc", &huge_tree).is_err());
+        // Files too large to preview cannot be opened either.
+        assert!(PatternFile::parse(&huge_tree, PatternFormat::Macrocell).is_err());
+        assert!(read("huge.cells", &"O".repeat(2 << 20)).is_err());
+    }
+}
@@ -0,0 +1,181 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use tui::components::Menu;
+use tui::event::{KeyCode, KeyEvent};
+use tui::Paintbrush;
+use xy_utils::{Dimensions, Point};
+
+use crate::game::library::{LibraryPattern, LIBRARY};
+
+/// Something that the game needs to do in response to the pattern picker.
+#[derive(Debug, PartialEq)]
+pub enum PickerAction {
+    /// The picker has changed (e.g. another pattern is selected), so it needs
+    /// to be shown again.
+    Updated,
+
+    /// The picker was closed without choosing a pattern.
+    Closed,
+
+    /// Insert the chosen pattern into the game. The picker is closed.
+    Insert(&'static LibraryPattern),
+}
+
+/// Picker that lets the user choose a pattern from the library while the game
+/// is running.
+///
+/// The picker is a menu of every pattern in the library, which is shown over
+/// the game board while it is open. The arrow keys choose a pattern, `Enter`
+/// inserts the pattern and `Esc` closes the picker.
+pub struct PatternPicker {
+    menu: Menu,
+    is_open: bool,
+}
+
+impl PatternPicker {
+    pub fn new() -> Self {
+        let items: Vec<String> = LIBRARY
+            .iter()
+            .map(|pattern| format!("{} ({})", pattern.name, pattern.category))
+            .collect();
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+
+        // The renderer decides where the menu goes.
+        let menu = Menu::new(
+            Paintbrush::create_default(),
+            Point { x: 0, y: 0 },
+            Dimensions::create_empty(),
+            "Insert a pattern",
+            &items,
+        );
+        PatternPicker { menu, is_open: false }
+    }
+
+    /// Open the picker. It reopens on the last pattern that was selected.
+    pub fn open(&mut self) {
+        self.is_open = true;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Get the menu to show while the picker is open.
+    pub fn menu(&self) -> &Menu {
+        &self.menu
+    }
+
+    pub fn menu_mut(&mut self) -> &mut Menu {
+        &mut self.menu
+    }
+
+    /// Handle a key press.
+    ///
+    /// ## Arguments
+    ///
+    /// * `key_event`: The key that was pressed.
+    ///
+    /// ## Returns
+    ///
+    /// The action to take in response to the key, if any. Keys are ignored
+    /// while the picker is closed.
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> Option<PickerAction> {
+        if !self.is_open {
+            return None;
+        }
+
+        if key_event.code == KeyCode::Escape {
+            self.is_open = false;
+            return Some(PickerAction::Closed);
+        }
+        if !self.menu.handle_key(&key_event) {
+            return None;
+        }
+
+        match self.menu.take_chosen() {
+            Some(index) => {
+                self.is_open = false;
+                Some(PickerAction::Insert(&LIBRARY[index]))
+            }
+            None => Some(PickerAction::Updated),
+        }
+    }
+}
+
+#[cfg(test)]
+mod pattern_picker_tests {
+    use super::*;
+
+    fn press(picker: &mut PatternPicker, code: KeyCode) -> Option<PickerAction> {
+        picker.handle_key(KeyEvent::from(code))
+    }
+
+    #[test]
+    fn keys_are_ignored_until_the_picker_is_opened() {
+        let mut picker = PatternPicker::new();
+
+        assert_eq!(None, press(&mut picker, KeyCode::Enter));
+        assert_eq!(None, press(&mut picker, KeyCode::Down));
+
+        picker.open();
+        assert!(picker.is_open());
+        assert_eq!(0, picker.menu().selected());
+    }
+
+    #[test]
+    fn arrow_keys_cycle_through_the_library() {
+        let mut picker = PatternPicker::new();
+        picker.open();
+
+        assert_eq!(Some(PickerAction::Updated), press(&mut picker, KeyCode::Down));
+        assert_eq!(1, picker.menu().selected());
+        press(&mut picker, KeyCode::Up);
+        press(&mut picker, KeyCode::Up);
+        assert_eq!(LIBRARY.len() - 1, picker.menu().selected());
+        assert_eq!(None, press(&mut picker, KeyCode::Char('x')));
+    }
+
+    #[test]
+    fn enter_inserts_the_selected_pattern() {
+        let mut picker = PatternPicker::new();
+        picker.open();
+        press(&mut picker, KeyCode::Down);
+
+        assert_eq!(Some(PickerAction::Insert(&LIBRARY[1])), press(&mut picker, KeyCode::Enter));
+
+        // The picker is closed, but reopens on the same pattern.
+        assert!(!picker.is_open());
+        assert_eq!(None, press(&mut picker, KeyCode::Enter));
+        picker.open();
+        assert_eq!(1, picker.menu().selected());
+    }
+
+    #[test]
+    fn escape_closes_the_picker() {
+        let mut picker = PatternPicker::new();
+        picker.open();
+
+        assert_eq!(Some(PickerAction::Closed), press(&mut picker, KeyCode::Escape));
+        assert_eq!(None, press(&mut picker, KeyCode::Down));
+    }
+}
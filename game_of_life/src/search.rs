@@ -0,0 +1,283 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use std::{
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    analysis::{
+        apgcode::{self, Classification, ObjectKind},
+        components, life,
+        stabilisation::run_until_stable,
+        Census,
+    },
+    game::{SoupSettings, Symmetry},
+};
+use xy_utils::Dimensions;
+
+/// apgcode of the glider, which is too common to be interesting.
+const GLIDER: &str = "xq4_153";
+
+/// How often progress is reported while searching.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Settings shared by every soup in a search.
+#[derive(Clone, Copy, Debug)]
+pub struct SearchSettings {
+    /// Size of each soup. This should already have been adjusted with
+    /// `Symmetry::adjust_soup_size()`.
+    pub soup_size: Dimensions,
+    pub density: f64,
+    pub symmetry: Symmetry,
+
+    /// Number of generations to wait for each soup to stabilise.
+    pub max_generations: usize,
+
+    /// Oscillators with at least this period are reported as rare.
+    pub min_rare_period: usize,
+}
+
+/// The result of searching a single soup.
+#[derive(Clone, Debug)]
+pub struct SoupResult {
+    pub seed: u64,
+
+    /// The objects that the soup left behind.
+    pub census: Census,
+
+    /// Objects that are rare enough to be logged.
+    pub rare_objects: Vec<Classification>,
+}
+
+/// Is an object rare enough to be logged? Spaceships (other than the glider)
+/// and high period oscillators are rare.
+///
+/// ## Arguments
+///
+/// * `classification`: The object.
+/// * `min_period`: The smallest period of a rare oscillator.
+pub fn is_rare(classification: &Classification, min_period: usize) -> bool {
+    match classification.kind {
+        ObjectKind::Oscillator => classification.period >= min_period,
+        ObjectKind::Spaceship => classification.apgcode != GLIDER,
+        ObjectKind::StillLife | ObjectKind::Unidentified => false,
+    }
+}
+
+/// Generate a soup, run it until it stabilises and take a census of the
+/// objects that it leaves behind.
+///
+/// ## Arguments
+///
+/// * `settings`: The search settings.
+/// * `seed`: The seed of the soup.
+pub fn search_soup(settings: &SearchSettings, seed: u64) -> SoupResult {
+    let soup = SoupSettings { seed, density: settings.density, symmetry: settings.symmetry };
+    let cells = life::from_points(&soup.generate_cells(settings.soup_size));
+    let stabilised = run_until_stable(&cells, settings.max_generations);
+
+    let mut census = Census::default();
+    let mut rare_objects = vec![];
+    for component in components::find_components(&stabilised.cells) {
+        let classification = apgcode::classify(&component);
+        census.add_object(&classification);
+        if is_rare(&classification, settings.min_rare_period) {
+            rare_objects.push(classification);
+        }
+    }
+
+    SoupResult { seed, census, rare_objects }
+}
+
+/// Search soups on several threads, sharing a combined census.
+///
+/// Soups use consecutive seeds, starting from `first_seed`. Every rare object
+/// is written to `results` as a line containing the object's apgcode and the
+/// settings needed to reproduce the soup.
+///
+/// ## Arguments
+///
+/// * `settings`: The search settings.
+/// * `first_seed`: Seed of the first soup.
+/// * `soup_limit`: Number of soups to search, or `None` to search forever.
+/// * `threads`: Number of worker threads.
+/// * `results`: Where rare objects are logged.
+/// * `report_progress`: Called periodically with the number of soups
+///   searched and the combined census so far.
+///
+/// ## Returns
+///
+/// The combined census of every soup, or an error if the results could not
+/// be written.
+pub fn run_search<W: Write + Send>(
+    settings: &SearchSettings,
+    first_seed: u64,
+    soup_limit: Option<u64>,
+    threads: usize,
+    results: W,
+    report_progress: impl Fn(u64, &Census),
+) -> Result<Census, String> {
+    let next_soup = AtomicU64::new(0);
+    let soups_searched = AtomicU64::new(0);
+    let census = Mutex::new(Census::default());
+    let results = Mutex::new(results);
+    let error = Mutex::new(None);
+    let finished = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let workers = (0..threads.max(1))
+            .map(|_| {
+                scope.spawn(|| loop {
+                    let soup = next_soup.fetch_add(1, Ordering::Relaxed);
+                    if soup_limit.is_some_and(|limit| soup >= limit)
+                        || finished.load(Ordering::Relaxed)
+                    {
+                        return;
+                    }
+
+                    let result = search_soup(settings, first_seed.wrapping_add(soup));
+                    census.lock().unwrap().merge(&result.census);
+                    if let Err(log_error) = log_rare_objects(settings, &result, &results) {
+                        *error.lock().unwrap() = Some(log_error);
+                        finished.store(true, Ordering::Relaxed);
+                    }
+                    soups_searched.fetch_add(1, Ordering::Relaxed);
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut last_report = Instant::now();
+        while !workers.iter().all(|worker| worker.is_finished()) {
+            thread::sleep(Duration::from_millis(100));
+            if last_report.elapsed() >= PROGRESS_INTERVAL {
+                last_report = Instant::now();
+                report_progress(soups_searched.load(Ordering::Relaxed), &census.lock().unwrap());
+            }
+        }
+    });
+
+    match error.into_inner().unwrap() {
+        Some(error) => Err(error),
+        None => Ok(census.into_inner().unwrap()),
+    }
+}
+
+fn log_rare_objects<W: Write>(
+    settings: &SearchSettings,
+    result: &SoupResult,
+    results: &Mutex<W>,
+) -> Result<(), String> {
+    if result.rare_objects.is_empty() {
+        return Ok(());
+    }
+
+    let mut results = results.lock().unwrap();
+    for object in &result.rare_objects {
+        writeln!(
+            results,
+            "{} --seed {} --soup-size {} --density {} --symmetry {}",
+            object.apgcode, result.seed, settings.soup_size, settings.density, settings.symmetry
+        )
+        .map_err(|error| format!("Failed to write search results: {}", error))?;
+    }
+
+    // Flush straight away, long searches are usually stopped by killing them.
+    results.flush().map_err(|error| format!("Failed to write search results: {}", error))
+}
+
+#[cfg(test)]
+mod search_tests {
+    use super::*;
+
+    fn create_settings() -> SearchSettings {
+        SearchSettings {
+            soup_size: Dimensions { width: 6, height: 6 },
+            density: 0.5,
+            symmetry: Symmetry::C1,
+            max_generations: 1000,
+            min_rare_period: 3,
+        }
+    }
+
+    fn create_classification(kind: ObjectKind, period: usize, apgcode: &str) -> Classification {
+        Classification { kind, period, apgcode: apgcode.to_string() }
+    }
+
+    #[test]
+    fn spaceships_and_high_period_oscillators_are_rare() {
+        assert!(is_rare(&create_classification(ObjectKind::Oscillator, 3, "xp3_a"), 3));
+        assert!(is_rare(&create_classification(ObjectKind::Spaceship, 4, "xq4_6frc"), 3));
+
+        assert!(!is_rare(&create_classification(ObjectKind::Oscillator, 2, "xp2_7"), 3));
+        assert!(!is_rare(&create_classification(ObjectKind::Spaceship, 4, GLIDER), 3));
+        assert!(!is_rare(&create_classification(ObjectKind::StillLife, 1, "xs4_33"), 3));
+        assert!(!is_rare(&create_classification(ObjectKind::Unidentified, 0, "PATHOLOGICAL"), 3));
+    }
+
+    #[test]
+    fn soups_are_reproducible() {
+        let settings = create_settings();
+        let first = search_soup(&settings, 11);
+        let second = search_soup(&settings, 11);
+
+        assert_eq!(first.census, second.census);
+        assert!(first.census.total() > 0);
+    }
+
+    #[test]
+    fn rare_objects_are_logged_with_their_seed() {
+        let mut settings = create_settings();
+        // Make blinkers "rare" so that something is logged.
+        settings.min_rare_period = 2;
+
+        let soups = (0..10).map(|seed| search_soup(&settings, seed)).collect::<Vec<_>>();
+        let results = Mutex::new(Vec::<u8>::new());
+        for result in &soups {
+            log_rare_objects(&settings, result, &results).unwrap();
+        }
+
+        let results = String::from_utf8(results.into_inner().unwrap()).unwrap();
+        let expected_lines = soups.iter().map(|soup| soup.rare_objects.len()).sum::<usize>();
+        assert!(expected_lines > 0);
+        assert_eq!(expected_lines, results.lines().count());
+        assert!(results.lines().all(|line| line.contains(" --seed ")
+            && line.ends_with("--soup-size 6x6 --density 0.5 --symmetry C1")));
+    }
+
+    #[test]
+    fn workers_share_a_combined_census() {
+        let settings = create_settings();
+        let mut expected = Census::default();
+        for seed in 100..106 {
+            expected.merge(&search_soup(&settings, seed).census);
+        }
+
+        let census = run_search(&settings, 100, Some(6), 3, vec![], |_, _| {}).unwrap();
+        assert_eq!(expected, census);
+    }
+}
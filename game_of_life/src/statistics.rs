@@ -0,0 +1,460 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+
+use clap::ValueEnum;
+
+use crate::game::{Cell, Renderer};
+use xy_utils::{Dimensions, Point};
+
+const CSV_HEADER: &str = "generation,population,births,deaths,min_x,min_y,max_x,max_y,hash";
+
+/// Output formats supported by the statistics recorder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum StatisticsFormat {
+    /// Comma separated values, with a header row.
+    Csv,
+
+    /// One JSON object per line.
+    Json,
+}
+
+impl StatisticsFormat {
+    /// Guess the output format from a file name. Files ending in `.json`,
+    /// `.jsonl` or `.ndjson` are written as JSON lines, everything else is
+    /// written as CSV.
+    pub fn from_file_name(file_name: &str) -> StatisticsFormat {
+        let lowercase_name = file_name.to_lowercase();
+        if [".json", ".jsonl", ".ndjson"].iter().any(|ext| lowercase_name.ends_with(ext)) {
+            StatisticsFormat::Json
+        } else {
+            StatisticsFormat::Csv
+        }
+    }
+}
+
+/// The statistics of a single generation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenerationStatistics {
+    /// Generation number. The initial seed is generation zero.
+    pub generation: usize,
+
+    /// Number of live cells.
+    pub population: usize,
+
+    /// Number of cells that were born in this generation.
+    pub births: usize,
+
+    /// Number of cells that died in this generation.
+    pub deaths: usize,
+
+    /// Smallest rectangle containing every live cell, as the top-left and
+    /// bottom-right cells (inclusive). `None` if there are no live cells.
+    pub bounding_box: Option<(Point, Point)>,
+
+    /// Hash of the set of live cells. Two generations with identical cells
+    /// will have identical hashes, so repeating patterns can be spotted.
+    pub hash: u64,
+}
+
+/// Renderer implementation that records the statistics of each generation.
+///
+/// Every call to `apply_changes()` is treated as a new generation, and one
+/// record is written to the output stream in the chosen format. Since the
+/// renderer only sees changes, it keeps track of the live cells itself so that
+/// the bounding box and hash can be calculated.
+///
+/// Like the other renderers, errors are handled internally. The first write
+/// error stops the recording, and is reported by `finish()`.
+pub struct StatisticsRecorder<OutputStream: Write> {
+    /// Output stream. Set to `None` if recording was aborted due to an error.
+    outstream: Option<OutputStream>,
+
+    /// Format to write the statistics in.
+    format: StatisticsFormat,
+
+    /// The currently live cells.
+    live_cells: HashSet<Point>,
+
+    /// Number of live cells in each row and column, used to find the bounding
+    /// box without scanning every cell.
+    row_counts: BTreeMap<usize, usize>,
+    column_counts: BTreeMap<usize, usize>,
+
+    /// Order-independent hash of the live cells, updated as cells change.
+    hash: u64,
+
+    /// The next generation number.
+    generation: usize,
+
+    /// The first error that was encountered while recording.
+    error: Option<String>,
+}
+
+impl<OutputStream: Write> StatisticsRecorder<OutputStream> {
+    /// Create a new statistics recorder.
+    ///
+    /// ## Arguments
+    ///
+    /// * `outstream`: Where the statistics will be written (e.g. a file).
+    /// * `format`: The format to write the statistics in.
+    pub fn new(outstream: OutputStream, format: StatisticsFormat) -> Self {
+        StatisticsRecorder {
+            outstream: Some(outstream),
+            format,
+            live_cells: HashSet::new(),
+            row_counts: BTreeMap::new(),
+            column_counts: BTreeMap::new(),
+            hash: 0,
+            generation: 0,
+            error: None,
+        }
+    }
+
+    /// Finish recording, flushing any buffered statistics and returning the
+    /// underlying output stream.
+    pub fn finish(mut self) -> Result<OutputStream, String> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        match self.outstream.take() {
+            Some(mut outstream) => {
+                outstream.flush().map_err(|error| error.to_string())?;
+                Ok(outstream)
+            }
+            None => Err("Recording was aborted".to_string()),
+        }
+    }
+
+    /// Apply a set of changes, and return the statistics of the resulting
+    /// generation.
+    fn record_generation(&mut self, changes: Vec<(Point, Cell)>) -> GenerationStatistics {
+        let mut births = 0;
+        let mut deaths = 0;
+        for (cell_address, cell_state) in changes {
+            match cell_state {
+                Cell::Alive => {
+                    if self.live_cells.insert(cell_address) {
+                        births += 1;
+                        self.hash = self.hash.wrapping_add(hash_point(cell_address));
+                        *self.row_counts.entry(cell_address.y).or_insert(0) += 1;
+                        *self.column_counts.entry(cell_address.x).or_insert(0) += 1;
+                    }
+                }
+                Cell::Dead => {
+                    if self.live_cells.remove(&cell_address) {
+                        deaths += 1;
+                        self.hash = self.hash.wrapping_sub(hash_point(cell_address));
+                        decrement_count(&mut self.row_counts, cell_address.y);
+                        decrement_count(&mut self.column_counts, cell_address.x);
+                    }
+                }
+            }
+        }
+
+        let bounding_box = match (
+            self.column_counts.keys().next(),
+            self.row_counts.keys().next(),
+            self.column_counts.keys().next_back(),
+            self.row_counts.keys().next_back(),
+        ) {
+            (Some(&min_x), Some(&min_y), Some(&max_x), Some(&max_y)) => {
+                Some((Point { x: min_x, y: min_y }, Point { x: max_x, y: max_y }))
+            }
+            _ => None,
+        };
+
+        let statistics = GenerationStatistics {
+            generation: self.generation,
+            population: self.live_cells.len(),
+            births,
+            deaths,
+            bounding_box,
+            hash: self.hash,
+        };
+        self.generation += 1;
+        statistics
+    }
+
+    /// Record a generation of which only the population is known, such as a
+    /// game on an unbounded plane that is too large to track cell by cell.
+    /// The births, deaths, bounding box and hash are left empty.
+    pub fn record_population(&mut self, population: u64) {
+        let generation = self.generation;
+        let record = match self.format {
+            StatisticsFormat::Csv => format!("{},{},,,,,,,", generation, population),
+            StatisticsFormat::Json => format!(
+                "{{\"generation\":{},\"population\":{},\"births\":null,\"deaths\":null,\"bounding_box\":null,\"hash\":null}}",
+                generation, population
+            ),
+        };
+        self.generation += 1;
+        self.write_record(generation, &record);
+    }
+
+    fn write_statistics(&mut self, statistics: &GenerationStatistics) {
+        let record = match self.format {
+            StatisticsFormat::Csv => format_csv(statistics),
+            StatisticsFormat::Json => format_json(statistics),
+        };
+        self.write_record(statistics.generation, &record);
+    }
+
+    /// Write one generation's record, after the CSV header if it is the
+    /// first generation.
+    fn write_record(&mut self, generation: usize, record: &str) {
+        let Some(outstream) = self.outstream.as_mut() else {
+            return;
+        };
+
+        let result = if generation == 0 && self.format == StatisticsFormat::Csv {
+            writeln!(outstream, "{}", CSV_HEADER).and_then(|_| writeln!(outstream, "{}", record))
+        } else {
+            writeln!(outstream, "{}", record)
+        };
+
+        if let Err(error) = result {
+            self.error = Some(error.to_string());
+            self.outstream = None;
+        }
+    }
+}
+
+impl<OutputStream: Write> Renderer for StatisticsRecorder<OutputStream> {
+    fn initialize(&mut self) {}
+
+    fn get_grid_size(&self) -> Dimensions {
+        // Statistics can be collected for any size of board.
+        Dimensions::create_empty()
+    }
+
+    fn apply_changes(&mut self, changes: Vec<(Point, Cell)>) {
+        let statistics = self.record_generation(changes);
+        self.write_statistics(&statistics);
+    }
+}
+
+/// Format generation statistics as a CSV row.
+fn format_csv(statistics: &GenerationStatistics) -> String {
+    let bounding_box = match statistics.bounding_box {
+        Some((min, max)) => format!("{},{},{},{}", min.x, min.y, max.x, max.y),
+        None => ",,,".to_string(),
+    };
+
+    format!(
+        "{},{},{},{},{},{:016x}",
+        statistics.generation,
+        statistics.population,
+        statistics.births,
+        statistics.deaths,
+        bounding_box,
+        statistics.hash
+    )
+}
+
+/// Format generation statistics as a single-line JSON object.
+fn format_json(statistics: &GenerationStatistics) -> String {
+    let bounding_box = match statistics.bounding_box {
+        Some((min, max)) => format!(
+            "{{\"min_x\":{},\"min_y\":{},\"max_x\":{},\"max_y\":{}}}",
+            min.x, min.y, max.x, max.y
+        ),
+        None => "null".to_string(),
+    };
+
+    format!(
+        "{{\"generation\":{},\"population\":{},\"births\":{},\"deaths\":{},\"bounding_box\":{},\"hash\":\"{:016x}\"}}",
+        statistics.generation,
+        statistics.population,
+        statistics.births,
+        statistics.deaths,
+        bounding_box,
+        statistics.hash
+    )
+}
+
+/// Hash a single cell address. The hashes of live cells are summed to give a
+/// hash of the whole board that can be updated incrementally, and is stable
+/// between runs (unlike `std::collections::hash_map::DefaultHasher`).
+fn hash_point(point: Point) -> u64 {
+    // SplitMix64 finalizer over the packed coordinates.
+    let mut z = (point.x as u64).wrapping_mul(0x9e3779b97f4a7c15) ^ (point.y as u64);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+fn decrement_count(counts: &mut BTreeMap<usize, usize>, key: usize) {
+    if let Some(count) = counts.get_mut(&key) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod statistics_tests {
+    use super::*;
+
+    fn record(changes: &[Vec<(Point, Cell)>], format: StatisticsFormat) -> String {
+        let mut recorder = StatisticsRecorder::new(vec![], format);
+        for generation in changes {
+            recorder.apply_changes(generation.clone());
+        }
+        String::from_utf8(recorder.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn format_is_guessed_from_the_file_name() {
+        assert_eq!(StatisticsFormat::Json, StatisticsFormat::from_file_name("stats.json"));
+        assert_eq!(StatisticsFormat::Json, StatisticsFormat::from_file_name("stats.JSONL"));
+        assert_eq!(StatisticsFormat::Json, StatisticsFormat::from_file_name("stats.ndjson"));
+        assert_eq!(StatisticsFormat::Csv, StatisticsFormat::from_file_name("stats.csv"));
+        assert_eq!(StatisticsFormat::Csv, StatisticsFormat::from_file_name("stats"));
+    }
+
+    #[test]
+    fn records_population_births_and_deaths() {
+        let mut recorder = StatisticsRecorder::new(vec![], StatisticsFormat::Csv);
+
+        let first = recorder.record_generation(vec![
+            (Point { x: 1, y: 1 }, Cell::Alive),
+            (Point { x: 2, y: 1 }, Cell::Alive),
+            (Point { x: 3, y: 1 }, Cell::Alive),
+        ]);
+        assert_eq!(0, first.generation);
+        assert_eq!(3, first.population);
+        assert_eq!(3, first.births);
+        assert_eq!(0, first.deaths);
+
+        let second = recorder.record_generation(vec![
+            (Point { x: 1, y: 1 }, Cell::Dead),
+            (Point { x: 3, y: 1 }, Cell::Dead),
+            (Point { x: 2, y: 0 }, Cell::Alive),
+            (Point { x: 2, y: 2 }, Cell::Alive),
+        ]);
+        assert_eq!(1, second.generation);
+        assert_eq!(3, second.population);
+        assert_eq!(2, second.births);
+        assert_eq!(2, second.deaths);
+    }
+
+    #[test]
+    fn tracks_the_bounding_box_of_live_cells() {
+        let mut recorder = StatisticsRecorder::new(vec![], StatisticsFormat::Csv);
+
+        let statistics = recorder.record_generation(vec![
+            (Point { x: 4, y: 1 }, Cell::Alive),
+            (Point { x: 2, y: 6 }, Cell::Alive),
+        ]);
+        assert_eq!(Some((Point { x: 2, y: 1 }, Point { x: 4, y: 6 })), statistics.bounding_box);
+
+        let statistics = recorder.record_generation(vec![(Point { x: 2, y: 6 }, Cell::Dead)]);
+        assert_eq!(Some((Point { x: 4, y: 1 }, Point { x: 4, y: 1 })), statistics.bounding_box);
+
+        let statistics = recorder.record_generation(vec![(Point { x: 4, y: 1 }, Cell::Dead)]);
+        assert_eq!(None, statistics.bounding_box);
+    }
+
+    #[test]
+    fn identical_generations_have_identical_hashes() {
+        let mut recorder = StatisticsRecorder::new(vec![], StatisticsFormat::Csv);
+
+        // A blinker returns to its original phase every two generations.
+        let horizontal = recorder.record_generation(vec![
+            (Point { x: 1, y: 2 }, Cell::Alive),
+            (Point { x: 2, y: 2 }, Cell::Alive),
+            (Point { x: 3, y: 2 }, Cell::Alive),
+        ]);
+        let vertical = recorder.record_generation(vec![
+            (Point { x: 1, y: 2 }, Cell::Dead),
+            (Point { x: 3, y: 2 }, Cell::Dead),
+            (Point { x: 2, y: 1 }, Cell::Alive),
+            (Point { x: 2, y: 3 }, Cell::Alive),
+        ]);
+        let horizontal_again = recorder.record_generation(vec![
+            (Point { x: 2, y: 1 }, Cell::Dead),
+            (Point { x: 2, y: 3 }, Cell::Dead),
+            (Point { x: 1, y: 2 }, Cell::Alive),
+            (Point { x: 3, y: 2 }, Cell::Alive),
+        ]);
+
+        assert_eq!(horizontal.hash, horizontal_again.hash);
+        assert_ne!(horizontal.hash, vertical.hash);
+    }
+
+    #[test]
+    fn writes_csv_with_a_header() {
+        let output = record(
+            &[vec![(Point { x: 1, y: 2 }, Cell::Alive)], vec![(Point { x: 1, y: 2 }, Cell::Dead)]],
+            StatisticsFormat::Csv,
+        );
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(3, lines.len());
+        assert_eq!(CSV_HEADER, lines[0]);
+        assert!(lines[1].starts_with("0,1,1,0,1,2,1,2,"));
+        assert_eq!("1,0,0,1,,,,,0000000000000000", lines[2]);
+    }
+
+    #[test]
+    fn records_the_population_alone() {
+        let mut recorder = StatisticsRecorder::new(vec![], StatisticsFormat::Csv);
+        recorder.record_population(1 << 40);
+        recorder.record_population(0);
+        let output = String::from_utf8(recorder.finish().unwrap()).unwrap();
+        assert_eq!(format!("{}\n0,1099511627776,,,,,,,\n1,0,,,,,,,\n", CSV_HEADER), output);
+
+        let mut recorder = StatisticsRecorder::new(vec![], StatisticsFormat::Json);
+        recorder.record_population(5);
+        let output = String::from_utf8(recorder.finish().unwrap()).unwrap();
+        assert_eq!(
+            "{\"generation\":0,\"population\":5,\"births\":null,\"deaths\":null,\
+             \"bounding_box\":null,\"hash\":null}\n",
+            output
+        );
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        let output = record(
+            &[vec![(Point { x: 1, y: 2 }, Cell::Alive)], vec![(Point { x: 1, y: 2 }, Cell::Dead)]],
+            StatisticsFormat::Json,
+        );
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(2, lines.len());
+        assert!(lines[0].starts_with(
+            "{\"generation\":0,\"population\":1,\"births\":1,\"deaths\":0,\
+             \"bounding_box\":{\"min_x\":1,\"min_y\":2,\"max_x\":1,\"max_y\":2},\"hash\":\""
+        ));
+        assert_eq!(
+            "{\"generation\":1,\"population\":0,\"births\":0,\"deaths\":1,\
+             \"bounding_box\":null,\"hash\":\"0000000000000000\"}",
+            lines[1]
+        );
+    }
+}
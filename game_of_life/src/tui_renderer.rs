@@ -21,25 +21,75 @@
 // SOFTWARE.
 
 use crate::game::{Cell, Renderer};
-use tui::components::{Border, Canvas, Count, TextLabel};
-use tui::{Color, Paintbrush, Plotter};
+use crate::game_ui::{GameUi, UiAction};
+use tui::components::{Border, Canvas, Chart, Count, TextLabel};
+use tui::event::KeyEvent;
+use tui::layout::{Constraint, Layout, Padding, Rect};
+use tui::{redraw_background, render_layers, Color, Component, Paintbrush, Plotter};
 use xy_utils::{Dimensions, Point};
 
+/// Settings for the population chart that is drawn beneath the game board.
+#[derive(Clone, Copy, Debug)]
+pub struct ChartSettings {
+    /// Total height of the chart (including the X-axis). Zero disables the
+    /// chart.
+    pub height: usize,
+
+    /// When true, the births and deaths of each generation are overlaid on
+    /// the population chart.
+    pub show_births_and_deaths: bool,
+}
+
+/// The areas of the terminal used by each part of the user interface.
+struct ScreenLayout {
+    command_line: Rect,
+    message_key: Rect,
+    message: Rect,
+    game_area: Rect,
+    canvas: Rect,
+    chart: Rect,
+    population: Rect,
+    generation: Rect,
+    births: Rect,
+    deaths: Rect,
+
+    /// Everything beneath the message row, where overlays are centred.
+    overlays: Rect,
+}
+
 /// Renderer implementation that renders the game board to a terminal user
 /// interface.
 pub struct TuiRenderer<PlotterT: Plotter> {
     plotter: PlotterT,
 
+    // The size of the terminal that the user interface was laid out for.
+    ui_size: Dimensions,
+
+    // The size of the game board in terminal characters. The game area may be
+    // smaller than this if the terminal is resized.
+    board_size: Dimensions,
+
     // Need to keep track of the current cell states internally for rendering
-    // purposes.
+    // purposes. Includes cells that are outside of the visible game area.
     current_cell_states: Vec<&'static str>,
 
     // Message field, provides any informational stuff about errors etc.
     message_field: (TextLabel, TextLabel),
 
+    // The game's controls. The command line is drawn over the whole message
+    // row while it is open, and the messages are hidden until it is closed.
+    // Overlays (e.g. help) are drawn over the middle of the user interface.
+    ui: GameUi,
+    command_line_area: Rect,
+    overlay_bounds: Rect,
+
     // Game canvas. Where the game of life is rendered.
     game_area: (Border, Canvas),
 
+    // Population history, drawn beneath the game area (if enabled).
+    population_chart: Option<Chart>,
+    chart_settings: ChartSettings,
+
     // Game stats.
     population_field: Count,
     generation_field: Count,
@@ -55,62 +105,150 @@ impl<PlotterT: Plotter> TuiRenderer<PlotterT> {
     /// * `plotter`: The plotter object that will be used to create the
     ///   rendered user interface.
     /// * `game_size`: The grid dimensions to use for the actual game.
-    pub fn new(plotter: PlotterT, game_size: Dimensions) -> Self {
+    /// * `chart_settings`: Settings for the population chart.
+    pub fn new(plotter: PlotterT, game_size: Dimensions, chart_settings: ChartSettings) -> Self {
         let ui_size = plotter.get_plot_area();
+        let layout = Self::calculate_layout(ui_size, game_size, chart_settings.height);
+        let board_size = layout.canvas.size;
+
+        Self {
+            plotter,
+            ui_size,
+            board_size,
+            current_cell_states: vec![" "; board_size.total_area()],
+            message_field: Self::create_message_field(&layout),
+            ui: GameUi::new(),
+            command_line_area: layout.command_line,
+            overlay_bounds: layout.overlays,
+            game_area: Self::create_game_area(&layout),
+            population_chart: Self::create_population_chart(
+                layout.chart,
+                chart_settings.show_births_and_deaths,
+            ),
+            chart_settings,
+            population_field: Self::create_stats_field(layout.population, "Population", true),
+            generation_field: Self::create_stats_field(layout.generation, "Generation", false),
+            total_births_field: Self::create_stats_field(layout.births, "Births", false),
+            total_deaths_field: Self::create_stats_field(layout.deaths, "Deaths", false),
+        }
+    }
 
+    pub fn print_message(&mut self, message: &str) {
+        self.message_field.1.update(message);
+
+        // The message is drawn once the command line is closed.
+        if !self.ui.is_command_line_open() {
+            self.message_field.1.render(&mut self.plotter).unwrap();
+        }
+    }
+
+    /// Pass a key press to the game's controls (see `GameUi`). Any change to
+    /// the controls is drawn straight away, rather than with the next
+    /// generation, so that typing isn't slowed down by the game.
+    ///
+    /// ## Arguments
+    ///
+    /// * `key_event`: The key that was pressed.
+    ///
+    /// ## Returns
+    ///
+    /// The action that the game needs to take in response to the key, if any.
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> Option<UiAction> {
+        let overlay_area =
+            |ui: &mut GameUi| ui.open_controls::<PlotterT>().1.map(|overlay| overlay.area());
+        let old_overlay_area = overlay_area(&mut self.ui);
+        let was_command_line_open = self.ui.is_command_line_open();
+
+        let action = self.ui.handle_key(key_event);
+        self.place_controls();
+
+        // Whatever the overlay was covering is drawn again if it has closed or
+        // moved.
+        let new_overlay_area = overlay_area(&mut self.ui);
+        let uncovered = old_overlay_area.filter(|old_area| Some(*old_area) != new_overlay_area);
+        let closed_command_line = was_command_line_open && !self.ui.is_command_line_open();
+        let result = if closed_command_line { self.hide_command_line() } else { Ok(()) };
+        if let Err(error) = result
+            .and_then(|_| self.render_dirty_components(uncovered))
+            .and_then(|_| self.plotter.flush().map(drop))
+        {
+            self.print_message(&format!("Error: {}", error));
+        }
+
+        action
+    }
+
+    /// Calculate where each part of the user interface goes. Messages are on
+    /// the top row, followed by the game area with the chart beneath it. The
+    /// stats take up the bottom two rows.
+    ///
+    /// ## Arguments
+    ///
+    /// * `ui_size`: The size of the terminal.
+    /// * `game_size`: The grid dimensions of the game.
+    /// * `chart_height`: The height of the population chart.
+    fn calculate_layout(
+        ui_size: Dimensions,
+        game_size: Dimensions,
+        chart_height: usize,
+    ) -> ScreenLayout {
         // Some of the area needs to be reserved for the labels.
-        let num_top_labels_rows = 1; // Messages are on the top row.
-        let num_bottom_labels_rows = 2; // Stats take up the bottom two rows.
+        let num_top_labels_rows = 1;
+        let num_bottom_labels_rows = 2;
         let border_total_size = 2; // Two chars required for border (one on each opposing side).
-        let total_reserved_rows = num_top_labels_rows + num_bottom_labels_rows + border_total_size;
-        let total_reserved_columns = border_total_size;
+        let total_reserved_rows =
+            num_top_labels_rows + num_bottom_labels_rows + border_total_size + chart_height;
         let game_dimensions = Self::create_game_dimensions(
             &game_size,
             &ui_size,
             total_reserved_rows,
-            total_reserved_columns,
+            border_total_size,
         );
 
-        let mut initial_cell_states = Vec::with_capacity(game_dimensions.total_area());
-        initial_cell_states.resize(initial_cell_states.capacity(), " ");
-
-        Self {
-            plotter,
-            current_cell_states: initial_cell_states,
-            message_field: Self::create_message_field(ui_size.width),
-            game_area: Self::create_game_area(Point { x: 0, y: 1 }, game_dimensions),
-            population_field: Self::create_stats_field(
-                Point { x: 0, y: ui_size.height - 2 },
-                Dimensions { height: 1, width: ui_size.width / 2 },
-                "Population",
-                true,
-            ),
-            generation_field: Self::create_stats_field(
-                Point { x: ui_size.width / 2, y: ui_size.height - 2 },
-                Dimensions { height: 1, width: ui_size.width / 2 },
-                "Generation",
-                false,
-            ),
-            total_births_field: Self::create_stats_field(
-                Point { x: 0, y: ui_size.height - 1 },
-                Dimensions { height: 1, width: ui_size.width / 2 },
-                "Births",
-                false,
-            ),
-            total_deaths_field: Self::create_stats_field(
-                Point { x: ui_size.width / 2, y: ui_size.height - 1 },
-                Dimensions { height: 1, width: ui_size.width / 2 },
-                "Deaths",
-                false,
+        let rows = Layout::vertical(vec![
+            Constraint::Fixed(num_top_labels_rows),
+            Constraint::Fixed(game_dimensions.height + border_total_size),
+            Constraint::Fixed(chart_height),
+            Constraint::Fill(1),
+            Constraint::Fixed(1),
+            Constraint::Fixed(1),
+        ])
+        .split(Rect::new(Point { x: 0, y: 0 }, ui_size));
+
+        let message_columns = Layout::horizontal(vec![
+            Constraint::Fixed(9),
+            Constraint::Fixed(1),
+            Constraint::Fill(1),
+        ])
+        .split(rows[0]);
+        let game_area = Layout::horizontal(vec![
+            Constraint::Fixed(game_dimensions.width + border_total_size),
+            Constraint::Fill(1),
+        ])
+        .split(rows[1])[0];
+        let stats_columns =
+            Layout::horizontal(vec![Constraint::Percentage(50), Constraint::Fill(1)]);
+        let top_stats = stats_columns.split(rows[4]);
+        let bottom_stats = stats_columns.split(rows[5]);
+
+        ScreenLayout {
+            command_line: rows[0],
+            message_key: message_columns[0],
+            message: message_columns[2],
+            game_area,
+            canvas: game_area.inner(Padding::uniform(1)),
+            chart: rows[2],
+            population: top_stats[0],
+            generation: top_stats[1],
+            births: bottom_stats[0],
+            deaths: bottom_stats[1],
+            overlays: Rect::new(
+                Point { x: 0, y: 1 },
+                Dimensions { width: ui_size.width, height: ui_size.height.saturating_sub(1) },
             ),
         }
     }
 
-    pub fn print_message(&mut self, message: &str) {
-        self.message_field.1.update(message);
-        self.message_field.1.render(&mut self.plotter).unwrap();
-    }
-
     fn create_game_dimensions(
         game_area: &Dimensions,
         ui_size: &Dimensions,
@@ -124,11 +262,11 @@ impl<PlotterT: Plotter> TuiRenderer<PlotterT> {
         Dimensions {
             height: Self::calculate_optimal_game_area_dimension(
                 actual_game_area_height,
-                ui_size.height - reserved_rows,
+                ui_size.height.saturating_sub(reserved_rows),
             ),
             width: Self::calculate_optimal_game_area_dimension(
                 game_area.width,
-                ui_size.width - reserved_columns,
+                ui_size.width.saturating_sub(reserved_columns),
             ),
         }
     }
@@ -144,52 +282,109 @@ impl<PlotterT: Plotter> TuiRenderer<PlotterT> {
         }
     }
 
-    fn create_message_field(total_width: usize) -> (TextLabel, TextLabel) {
+    fn create_message_field(layout: &ScreenLayout) -> (TextLabel, TextLabel) {
         let default_paintbrush = Paintbrush::create_default();
         (
             TextLabel::new(
                 default_paintbrush.clone(),
-                Point { x: 0, y: 0 },
-                Dimensions { width: 9, height: 1 },
+                layout.message_key.position,
+                layout.message_key.size,
                 "Messages:",
             ),
-            TextLabel::new(
-                default_paintbrush,
-                Point { x: 10, y: 0 },
-                Dimensions { width: total_width - 10, height: 1 },
-                "",
-            ),
+            TextLabel::new(default_paintbrush, layout.message.position, layout.message.size, ""),
         )
     }
 
-    fn create_game_area(position: Point, game_playable_area: Dimensions) -> (Border, Canvas) {
-        // This function assumes that the border has already been subtracted.
-        let game_board_size =
-            Dimensions { height: game_playable_area.height, width: game_playable_area.width };
-        let game_board_position = Point { x: position.x + 1, y: position.y + 1 };
-
+    fn create_game_area(layout: &ScreenLayout) -> (Border, Canvas) {
         (
             Border::new(
                 Paintbrush { fg: Color::Cyan, bg: Color::Unset, bold: false },
-                position,
-                Dimensions {
-                    width: game_playable_area.width + 2,
-                    height: game_playable_area.height + 2,
-                },
+                layout.game_area.position,
+                layout.game_area.size,
             ),
-            Canvas::new(game_board_position, game_board_size),
+            Canvas::new(layout.canvas.position, layout.canvas.size),
         )
     }
 
-    fn create_stats_field(
-        position: Point,
-        size: Dimensions,
-        key_text: &str,
-        color_coded: bool,
-    ) -> Count {
+    fn create_population_chart(area: Rect, show_births_and_deaths: bool) -> Option<Chart> {
+        if area.size.height == 0 {
+            return None;
+        }
+
+        let mut chart = Chart::new(area.position, area.size);
+        chart.add_series(
+            "Population",
+            Paintbrush { fg: Color::Cyan, ..Paintbrush::create_default() },
+        );
+        if show_births_and_deaths {
+            chart.add_series(
+                "Births",
+                Paintbrush { fg: Color::Green, ..Paintbrush::create_default() },
+            );
+            chart.add_series(
+                "Deaths",
+                Paintbrush { fg: Color::Red, ..Paintbrush::create_default() },
+            );
+        }
+
+        Some(chart)
+    }
+
+    fn create_stats_field(area: Rect, key_text: &str, color_coded: bool) -> Count {
         let paintbrush = Paintbrush::create_default();
         const KEY_WIDTH: usize = 12;
-        Count::new(paintbrush, position, size, KEY_WIDTH, key_text, color_coded)
+        Count::new(paintbrush, area.position, area.size, KEY_WIDTH, key_text, color_coded)
+    }
+
+    /// Lay the user interface out again if the terminal has been resized. The
+    /// terminal is cleared, and every component is drawn again when it is next
+    /// rendered.
+    fn resize_if_needed(&mut self) -> Result<(), std::io::Error> {
+        let ui_size = self.plotter.get_plot_area();
+        if ui_size == self.ui_size {
+            return Ok(());
+        }
+
+        self.ui_size = ui_size;
+        let game_size =
+            Dimensions { width: self.board_size.width, height: self.board_size.height * 2 };
+        let layout = Self::calculate_layout(ui_size, game_size, self.chart_settings.height);
+
+        let mut components: Vec<(&mut dyn Component<PlotterT>, Rect)> = vec![
+            (&mut self.message_field.0, layout.message_key),
+            (&mut self.message_field.1, layout.message),
+            (&mut self.game_area.0, layout.game_area),
+            (&mut self.game_area.1, layout.canvas),
+            (&mut self.population_field, layout.population),
+            (&mut self.generation_field, layout.generation),
+            (&mut self.total_births_field, layout.births),
+            (&mut self.total_deaths_field, layout.deaths),
+        ];
+        if let Some(chart) = self.population_chart.as_mut() {
+            components.push((chart, layout.chart));
+        }
+        for (component, area) in components {
+            component.set_area(area);
+        }
+        self.command_line_area = layout.command_line;
+        self.overlay_bounds = layout.overlays;
+        self.place_controls();
+
+        // Cells that were hidden by a smaller terminal need to be drawn again.
+        let canvas = &mut self.game_area.1;
+        for y in 0..canvas.size.height.min(self.board_size.height) {
+            let row_start = y * self.board_size.width;
+            let visible_width = canvas.size.width.min(self.board_size.width);
+            let row = self.current_cell_states[row_start..row_start + visible_width].concat();
+            canvas.draw_str(Paintbrush::create_default(), Point { x: 0, y }, &row)?;
+        }
+
+        self.plotter.set_paintbrush(&Paintbrush::create_default())?;
+        let blank_row = " ".repeat(ui_size.width);
+        for y in 0..ui_size.height {
+            self.plotter.plot(Point { x: 0, y }, &blank_row)?;
+        }
+        Ok(())
     }
 
     fn set_game_cell(&mut self, cell_address: Point, new_value: Cell) -> Result<(), String> {
@@ -197,8 +392,8 @@ impl<PlotterT: Plotter> TuiRenderer<PlotterT> {
             self.print_message(&format!(
                 "Ignored cell outside of printable area {:?} (max: {}x{})",
                 cell_address,
-                self.game_area.1.size.width,
-                self.game_area.1.size.height * 2
+                self.board_size.width,
+                self.board_size.height * 2
             ));
             return Ok(());
         }
@@ -211,7 +406,11 @@ impl<PlotterT: Plotter> TuiRenderer<PlotterT> {
         // * "▄"
         let (ui_address, new_ui_value) = self.get_new_ui_value(cell_address, new_value);
 
+        // The cell may be hidden if the terminal is smaller than the board.
         let canvas = &mut self.game_area.1;
+        if ui_address.x >= canvas.size.width || ui_address.y >= canvas.size.height {
+            return Ok(());
+        }
         let result = canvas.draw_str(Paintbrush::create_default(), ui_address, new_ui_value);
         if let Err(error) = result {
             Err(error.to_string())
@@ -221,10 +420,9 @@ impl<PlotterT: Plotter> TuiRenderer<PlotterT> {
     }
 
     fn cell_is_renderable(&self, cell_address: &Point) -> bool {
-        let canvas = &self.game_area.1;
         // Ignore cells outside the renderable area.
-        let max_x_address = canvas.size.width;
-        let max_y_address = canvas.size.height * 2; // Two cells per TUI character on the Y axis.
+        let max_x_address = self.board_size.width;
+        let max_y_address = self.board_size.height * 2; // Two cells per TUI character on the Y axis.
 
         (cell_address.x < max_x_address) && (cell_address.y < max_y_address)
     }
@@ -232,9 +430,11 @@ impl<PlotterT: Plotter> TuiRenderer<PlotterT> {
     fn get_new_ui_value(&mut self, cell_address: Point, new_value: Cell) -> (Point, &'static str) {
         let ui_point = Point { x: cell_address.x, y: cell_address.y / 2 };
 
-        let ui_point_index = (ui_point.y * self.game_area.1.size.width) + ui_point.x;
+        let ui_point_index = (ui_point.y * self.board_size.width) + ui_point.x;
         let current_ui_value = self.current_cell_states[ui_point_index];
-        let is_top_half_of_character = (cell_address.y % 2) == 0;
+        // Not `is_multiple_of()`, which needs Rust 1.87.
+        #[allow(clippy::manual_is_multiple_of)]
+        let is_top_half_of_character = cell_address.y % 2 == 0;
 
         let new_char = if is_top_half_of_character {
             match new_value {
@@ -266,6 +466,67 @@ impl<PlotterT: Plotter> TuiRenderer<PlotterT> {
         (ui_point, new_char)
     }
 
+    /// Place the open overlay (if any) in the middle of the user interface,
+    /// and the command line over the messages.
+    fn place_controls(&mut self) {
+        let overlay_area = self.ui.overlay_size().map(|size| self.overlay_bounds.centered(size));
+        let (command_line, overlay) = self.ui.open_controls::<PlotterT>();
+        if let Some(command_line) = command_line {
+            Component::<PlotterT>::set_area(command_line, self.command_line_area);
+        }
+        if let (Some(overlay), Some(area)) = (overlay, overlay_area) {
+            overlay.set_area(area);
+        }
+    }
+
+    /// Blank the row that the command line was drawn on, and draw the
+    /// messages over it again, once the command line is closed.
+    fn hide_command_line(&mut self) -> Result<(), std::io::Error> {
+        let area = self.command_line_area;
+        self.plotter.set_paintbrush(&Paintbrush::create_default())?;
+        self.plotter.plot(area.position, " ".repeat(area.size.width))?;
+        Component::<PlotterT>::mark_dirty(&mut self.message_field.0);
+        Component::<PlotterT>::mark_dirty(&mut self.message_field.1);
+        Ok(())
+    }
+
+    /// Render every component that has changed since it was last rendered,
+    /// with the overlay (if any) drawn over the top.
+    ///
+    /// ## Arguments
+    ///
+    /// * `uncovered`: An area that an overlay has been removed from, if any.
+    ///   It is blanked, and the components beneath it are drawn again.
+    fn render_dirty_components(&mut self, uncovered: Option<Rect>) -> Result<(), std::io::Error> {
+        let mut components: Vec<&mut dyn Component<PlotterT>> = vec![
+            &mut self.game_area.0,
+            &mut self.game_area.1,
+            &mut self.population_field,
+            &mut self.generation_field,
+            &mut self.total_births_field,
+            &mut self.total_deaths_field,
+        ];
+        if let Some(chart) = self.population_chart.as_mut() {
+            components.push(chart);
+        }
+        let (command_line, overlay) = self.ui.open_controls();
+        match command_line {
+            Some(command_line) => components.push(command_line),
+            None => {
+                components.push(&mut self.message_field.0);
+                components.push(&mut self.message_field.1);
+            }
+        }
+
+        if let Some(area) = uncovered {
+            redraw_background(&mut self.plotter, area, &mut components)?;
+        }
+        if let Some(overlay) = overlay {
+            components.push(overlay);
+        }
+        render_layers(&mut self.plotter, &mut components)
+    }
+
     fn increase_population(&mut self) {
         self.population_field.increment();
         self.total_births_field.increment();
@@ -281,25 +542,30 @@ impl<PlotterT: Plotter> Renderer for TuiRenderer<PlotterT> {
     fn initialize(&mut self) {
         self.print_message("Game board is initialized.");
 
-        self.message_field.0.render(&mut self.plotter).unwrap();
-        self.game_area.0.render(&mut self.plotter).unwrap();
-
         // Render the UI with zero changes initially.
         self.apply_changes(vec![]);
     }
 
     fn get_grid_size(&self) -> Dimensions {
-        Dimensions { width: self.game_area.1.size.width, height: self.game_area.1.size.height * 2 }
+        Dimensions { width: self.board_size.width, height: self.board_size.height * 2 }
+    }
+
+    fn show_message(&mut self, message: &str) {
+        self.print_message(message);
     }
 
     fn apply_changes(&mut self, changes: Vec<(Point, Cell)>) {
         self.generation_field.increment();
+        let mut births = 0;
+        let mut deaths = 0;
         for (cell_address, cell_state) in changes {
             match cell_state {
                 Cell::Alive => {
+                    births += 1;
                     self.increase_population();
                 }
                 Cell::Dead => {
+                    deaths += 1;
                     self.decrease_population();
                 }
             };
@@ -309,25 +575,205 @@ impl<PlotterT: Plotter> Renderer for TuiRenderer<PlotterT> {
             }
         }
 
-        if let Err(error) = self.population_field.render(&mut self.plotter) {
-            self.print_message(&format!("Error: {}", error.to_string()));
-        }
-        if let Err(error) = self.generation_field.render(&mut self.plotter) {
-            self.print_message(&format!("Error: {}", error.to_string()));
+        if let Some(chart) = self.population_chart.as_mut() {
+            let population = self.population_field.value();
+            if self.chart_settings.show_births_and_deaths {
+                chart.push_sample(&[population, births, deaths]);
+            } else {
+                chart.push_sample(&[population]);
+            }
         }
-        if let Err(error) = self.total_births_field.render(&mut self.plotter) {
-            self.print_message(&format!("Error: {}", error.to_string()));
+
+        if let Err(error) = self.resize_if_needed().and_then(|_| self.render_dirty_components(None))
+        {
+            self.print_message(&format!("Error: {}", error));
         }
-        if let Err(error) = self.total_deaths_field.render(&mut self.plotter) {
-            self.print_message(&format!("Error: {}", error.to_string()));
+
+        if let Err(error) = self.plotter.flush() {
+            self.print_message(&format!("Error: {}", error));
         }
+    }
+}
+
+#[cfg(test)]
+mod tui_renderer_tests {
+    use super::*;
+    use crate::game::{GameBoard, UserCellGenerator};
+    use tui::event::KeyCode;
+    use tui::mock::{MockPlotter, ScreenPlotter};
+
+    const NO_CHART: ChartSettings = ChartSettings { height: 0, show_births_and_deaths: false };
+
+    #[test]
+    fn lays_out_the_user_interface_around_the_game_area() {
+        let layout = TuiRenderer::<MockPlotter>::calculate_layout(
+            Dimensions { width: 40, height: 20 },
+            Dimensions::create_empty(),
+            3,
+        );
+
+        assert_eq!(
+            Rect::new(Point { x: 10, y: 0 }, Dimensions { width: 30, height: 1 }),
+            layout.message
+        );
+        assert_eq!(
+            Rect::new(Point { x: 1, y: 2 }, Dimensions { width: 38, height: 12 }),
+            layout.canvas
+        );
+        assert_eq!(
+            Rect::new(Point { x: 0, y: 15 }, Dimensions { width: 40, height: 3 }),
+            layout.chart
+        );
+        assert_eq!(Point { x: 20, y: 18 }, layout.generation.position);
+        assert_eq!(Point { x: 0, y: 19 }, layout.births.position);
+    }
+
+    #[test]
+    fn keeps_the_board_size_when_the_terminal_is_resized() {
+        let mut renderer =
+            TuiRenderer::new(MockPlotter::new(), Dimensions::create_empty(), NO_CHART);
+        renderer.initialize();
+        let grid_size = renderer.get_grid_size();
+
+        renderer.plotter.plot_area = Dimensions { width: 10, height: 8 };
+        renderer.apply_changes(vec![(Point { x: 15, y: 0 }, Cell::Alive)]);
+
+        assert_eq!(grid_size, renderer.get_grid_size());
+        assert_eq!(Dimensions { width: 8, height: 3 }, renderer.game_area.1.size);
+        assert_eq!(
+            Point { x: 0, y: 7 },
+            Component::<MockPlotter>::position(&renderer.total_births_field)
+        );
+    }
 
-        if let Err(error) = self.game_area.1.render(&mut self.plotter) {
-            self.print_message(&format!("Error: {}", error.to_string()));
+    #[test]
+    fn shows_the_game_after_three_generations() {
+        let plotter = ScreenPlotter::new(Dimensions { width: 36, height: 11 });
+        let chart_settings = ChartSettings { height: 3, show_births_and_deaths: false };
+        let mut renderer =
+            TuiRenderer::new(plotter, Dimensions { width: 6, height: 6 }, chart_settings);
+        renderer.initialize();
+
+        let mut blinker = UserCellGenerator::new();
+        blinker.add_pattern(&"\n\n  *\n  *\n  *".parse().unwrap(), Point { x: 0, y: 0 });
+        let mut board =
+            GameBoard::new_from_seed(Dimensions { width: 6, height: 6 }, blinker, &mut renderer);
+        for _ in 0..3 {
+            board.calculate_iteration();
         }
 
-        if let Err(error) = self.plotter.flush() {
-            self.print_message(&format!("Error: {}", error.to_string()));
+        // The generation count includes the initial render and the seed.
+        renderer.plotter.assert_screen(
+            "
+            |Messages: Game board is initialized.
+            |┌──────┐
+            |│      │
+            |│ ▄▄▄  │
+            |│      │
+            |└──────┘
+            |3┤                              ████
+            |0┤                              ████
+            | └0───────── ■ Population ─────────4
+            |Population:   3   Generation:   5
+            |Births:       9   Deaths:       6
+            ",
+        );
+    }
+
+    #[test]
+    fn the_command_line_replaces_the_messages_while_it_is_open() {
+        let plotter = ScreenPlotter::new(Dimensions { width: 36, height: 8 });
+        let mut renderer = TuiRenderer::new(plotter, Dimensions { width: 4, height: 4 }, NO_CHART);
+        renderer.initialize();
+
+        for character in ":rule B36/S23".chars() {
+            assert_eq!(None, renderer.handle_key(KeyEvent::new(KeyCode::Char(character))));
         }
+        renderer.show_message("Closed.");
+        renderer.apply_changes(vec![]);
+        renderer.plotter.assert_screen(
+            "
+            |:rule B36/S23
+            |┌────┐
+            |│    │
+            |│    │
+            |└────┘
+            |
+            |Population:   0   Generation:   2
+            |Births:       0   Deaths:       0
+            ",
+        );
+
+        assert_eq!(None, renderer.handle_key(KeyEvent::new(KeyCode::Escape)));
+        renderer.plotter.assert_screen(
+            "
+            |Messages: Closed.
+            |┌────┐
+            |│    │
+            |│    │
+            |└────┘
+            |
+            |Population:   0   Generation:   2
+            |Births:       0   Deaths:       0
+            ",
+        );
+    }
+
+    #[test]
+    fn overlays_are_drawn_over_the_game_until_they_are_closed() {
+        let plotter = ScreenPlotter::new(Dimensions { width: 60, height: 16 });
+        let mut renderer =
+            TuiRenderer::new(plotter, Dimensions { width: 58, height: 22 }, NO_CHART);
+        renderer.initialize();
+        renderer.apply_changes(vec![(Point { x: 0, y: 1 }, Cell::Alive)]);
+
+        assert_eq!(None, renderer.handle_key(KeyEvent::new(KeyCode::Char('?'))));
+        renderer.apply_changes(vec![
+            (Point { x: 0, y: 3 }, Cell::Alive),
+            (Point { x: 10, y: 9 }, Cell::Alive),
+        ]);
+        renderer.plotter.assert_screen(
+            "
+            |Messages: Game board is initialized.
+            |┌──────────────────────────────────────────────────────────┐
+            |│▄                                                         │
+            |│▄                                                         │
+            |│      ┌─ Keys ────────────────────────────────────┐       │
+            |│      │ Ctrl+C  Quit                              │       │
+            |│      │ ?       Show this help                    │       │
+            |│      │ F1      Show this help                    │       │
+            |│      │ p       Insert a pattern from the library │       │
+            |│      │ o       Open a pattern file               │       │
+            |│      │ :       Open the command line             │       │
+            |│      └───────────────────────────────────────────┘       │
+            |│                                                          │
+            |└──────────────────────────────────────────────────────────┘
+            |Population:   3               Generation:   3
+            |Births:       3               Deaths:       0
+            ",
+        );
+
+        // Keys that don't scroll the help close it.
+        assert_eq!(None, renderer.handle_key(KeyEvent::new(KeyCode::Char('x'))));
+        renderer.plotter.assert_screen(
+            "
+            |Messages: Game board is initialized.
+            |┌──────────────────────────────────────────────────────────┐
+            |│▄                                                         │
+            |│▄                                                         │
+            |│                                                          │
+            |│                                                          │
+            |│          ▄                                               │
+            |│                                                          │
+            |│                                                          │
+            |│                                                          │
+            |│                                                          │
+            |│                                                          │
+            |│                                                          │
+            |└──────────────────────────────────────────────────────────┘
+            |Population:   3               Generation:   3
+            |Births:       3               Deaths:       0
+            ",
+        );
     }
 }
@@ -0,0 +1,378 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fmt::Display;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::lowlevel::*;
+use xy_utils::{Dimensions, Point};
+
+/// The largest number of unchanged cells that will be redrawn to join two runs
+/// of changed cells. Redrawing a few cells is cheaper than moving the cursor.
+const MAX_REDRAWN_CELLS: usize = 6;
+
+/// A single character cell of the screen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ScreenCell {
+    /// The grapheme shown in the cell. Empty if the content of the cell is
+    /// unknown, so that it never matches anything that is drawn.
+    grapheme: String,
+    paintbrush: Paintbrush,
+}
+
+impl ScreenCell {
+    fn blank() -> Self {
+        ScreenCell { grapheme: " ".to_string(), paintbrush: Paintbrush::create_default() }
+    }
+
+    fn unknown() -> Self {
+        ScreenCell { grapheme: String::new(), paintbrush: Paintbrush::create_default() }
+    }
+}
+
+/// A plotter that draws into an in-memory copy of the screen, and only sends
+/// the cells that have changed to another plotter when it is flushed.
+///
+/// The buffered plotter keeps two copies of the screen. The "back" buffer is
+/// drawn into by `plot()`, and the "front" buffer holds what the wrapped
+/// plotter is showing. When flushed, each row is compared, and runs of changed
+/// cells that share a paintbrush are plotted together. The paintbrush of the
+/// wrapped plotter is only changed when it has to be.
+///
+/// Each grapheme is assumed to take up a single cell, and anything plotted
+/// outside of the plot area is clipped.
+///
+/// The wrapped plotter's screen is assumed to be blank when the buffered
+/// plotter is created (`DefaultPlotter` clears the screen when it is created).
+/// The size of the plot area is checked each time the plotter is flushed. If
+/// it has changed, the next flush will redraw the whole screen.
+///
+/// ## Example
+///
+/// ```
+/// use tui::mock::{MockPlotter, MockPlotterCommand};
+/// use tui::{BufferedPlotter, Paintbrush, Plotter};
+/// use xy_utils::Point;
+///
+/// let mut plotter = BufferedPlotter::new(MockPlotter::new());
+/// plotter.plot(Point { x: 0, y: 0 }, "FOO").unwrap();
+/// plotter.flush().unwrap();
+///
+/// // Plotting the same content again doesn't send anything but the flush.
+/// plotter.inner_mut().command_list.clear();
+/// plotter.plot(Point { x: 0, y: 0 }, "FOO").unwrap();
+/// plotter.flush().unwrap();
+/// assert!(matches!(plotter.inner().command_list[..], [MockPlotterCommand::Flush]));
+/// ```
+pub struct BufferedPlotter<PlotterT: Plotter> {
+    /// The plotter that changes are sent to.
+    plotter: PlotterT,
+
+    /// The size of the buffers.
+    size: Dimensions,
+
+    /// The paintbrush used by `plot()`.
+    paintbrush: Paintbrush,
+
+    /// The paintbrush that the wrapped plotter is using, if it is known.
+    plotter_paintbrush: Option<Paintbrush>,
+
+    /// What the wrapped plotter is showing, row by row.
+    front: Vec<ScreenCell>,
+
+    /// What the wrapped plotter will show after the next flush, row by row.
+    back: Vec<ScreenCell>,
+}
+
+impl<PlotterT: Plotter> BufferedPlotter<PlotterT> {
+    /// Create a buffered plotter.
+    ///
+    /// ## Arguments
+    ///
+    /// * `plotter`: The plotter that changes are sent to.
+    pub fn new(plotter: PlotterT) -> Self {
+        let size = plotter.get_plot_area();
+        BufferedPlotter {
+            plotter,
+            size,
+            paintbrush: Paintbrush::create_default(),
+            plotter_paintbrush: None,
+            front: vec![ScreenCell::blank(); size.total_area()],
+            back: vec![ScreenCell::blank(); size.total_area()],
+        }
+    }
+
+    /// Get the wrapped plotter.
+    pub fn inner(&self) -> &PlotterT {
+        &self.plotter
+    }
+
+    /// Get the wrapped plotter. Anything plotted directly to the wrapped
+    /// plotter may be overwritten when the buffered plotter is flushed.
+    pub fn inner_mut(&mut self) -> &mut PlotterT {
+        &mut self.plotter
+    }
+
+    /// Redraw the whole screen when the plotter is next flushed (e.g. because
+    /// something else has drawn over the screen).
+    pub fn redraw_all(&mut self) {
+        self.front.fill(ScreenCell::unknown());
+        self.plotter_paintbrush = None;
+    }
+
+    /// Send a row of the back buffer to the wrapped plotter.
+    fn flush_row(&mut self, y: usize) -> Result<(), std::io::Error> {
+        let row_start = y * self.size.width;
+        let width = self.size.width;
+        let mut x = 0;
+        while x < width {
+            if self.back[row_start + x] == self.front[row_start + x] {
+                x += 1;
+                continue;
+            }
+
+            // Find the end of the run of changed cells that share this
+            // cell's paintbrush.
+            let paintbrush = self.back[row_start + x].paintbrush.clone();
+            let mut end = x + 1;
+            for next in x + 1..width {
+                let cell = &self.back[row_start + next];
+                if cell.paintbrush != paintbrush || next - end >= MAX_REDRAWN_CELLS {
+                    break;
+                }
+                if *cell != self.front[row_start + next] {
+                    end = next + 1;
+                }
+            }
+
+            if self.plotter_paintbrush.as_ref() != Some(&paintbrush) {
+                self.plotter.set_paintbrush(&paintbrush)?;
+                self.plotter_paintbrush = Some(paintbrush);
+            }
+            let run = row_start + x..row_start + end;
+            let text: String =
+                self.back[run.clone()].iter().map(|cell| cell.grapheme.as_str()).collect();
+            self.plotter.plot(Point { x, y }, text)?;
+            self.front[run.clone()].clone_from_slice(&self.back[run]);
+            x = end;
+        }
+
+        Ok(())
+    }
+
+    /// Resize the buffers. Content inside the new size is kept in the back
+    /// buffer, but the whole screen will be redrawn.
+    fn resize(&mut self, size: Dimensions) {
+        let mut back = vec![ScreenCell::blank(); size.total_area()];
+        let width = size.width.min(self.size.width);
+        for y in 0..size.height.min(self.size.height) {
+            back[y * size.width..y * size.width + width]
+                .clone_from_slice(&self.back[y * self.size.width..y * self.size.width + width]);
+        }
+
+        self.size = size;
+        self.back = back;
+        self.front = vec![ScreenCell::unknown(); size.total_area()];
+        self.plotter_paintbrush = None;
+    }
+}
+
+impl<PlotterT: Plotter> Plotter for BufferedPlotter<PlotterT> {
+    fn get_plot_area(&self) -> Dimensions {
+        self.size
+    }
+
+    fn set_paintbrush(&mut self, pb: &Paintbrush) -> Result<&mut Self, std::io::Error> {
+        self.paintbrush = pb.clone();
+        Ok(self)
+    }
+
+    fn plot<T: Display>(
+        &mut self,
+        location: Point,
+        content: T,
+    ) -> Result<&mut Self, std::io::Error> {
+        if location.y >= self.size.height {
+            return Ok(self);
+        }
+
+        let row_start = location.y * self.size.width;
+        let content = content.to_string();
+        for (x, grapheme) in (location.x..self.size.width).zip(content.graphemes(true)) {
+            let cell = &mut self.back[row_start + x];
+            cell.grapheme.clear();
+            cell.grapheme.push_str(grapheme);
+            cell.paintbrush.clone_from(&self.paintbrush);
+        }
+        Ok(self)
+    }
+
+    fn flush(&mut self) -> Result<&mut Self, std::io::Error> {
+        for y in 0..self.size.height {
+            self.flush_row(y)?;
+        }
+        self.plotter.flush()?;
+
+        let size = self.plotter.get_plot_area();
+        if size != self.size {
+            self.resize(size);
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod buffered_plotter_tests {
+    use super::*;
+    use crate::mock::{MockPlotter, MockPlotterCommand};
+
+    fn create_plotter(width: usize, height: usize) -> BufferedPlotter<MockPlotter> {
+        let mut plotter = MockPlotter::new();
+        plotter.plot_area = Dimensions { width, height };
+        BufferedPlotter::new(plotter)
+    }
+
+    /// Flush the plotter, and get the commands sent to the wrapped plotter.
+    fn flush(plotter: &mut BufferedPlotter<MockPlotter>) -> Vec<MockPlotterCommand> {
+        plotter.flush().unwrap();
+        plotter.inner_mut().command_list.drain(..).collect()
+    }
+
+    fn plots(commands: &[MockPlotterCommand]) -> Vec<(Point, &str)> {
+        commands
+            .iter()
+            .filter_map(|command| match command {
+                MockPlotterCommand::PlotObject(point, s) => Some((*point, s.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn paintbrushes(commands: &[MockPlotterCommand]) -> Vec<&Paintbrush> {
+        commands
+            .iter()
+            .filter_map(|command| match command {
+                MockPlotterCommand::SetPaintbrush(pb) => Some(pb),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sends_nothing_but_the_flush_when_nothing_has_changed() {
+        let mut plotter = create_plotter(10, 2);
+        plotter.plot(Point { x: 0, y: 0 }, "   ").unwrap();
+
+        assert!(matches!(flush(&mut plotter)[..], [MockPlotterCommand::Flush]));
+    }
+
+    #[test]
+    fn only_sends_the_cells_that_have_changed() {
+        let mut plotter = create_plotter(10, 2);
+        plotter.plot(Point { x: 2, y: 1 }, "FOO").unwrap();
+        assert_eq!(vec![(Point { x: 2, y: 1 }, "FOO")], plots(&flush(&mut plotter)));
+
+        plotter.plot(Point { x: 2, y: 1 }, "FXO").unwrap();
+        assert_eq!(vec![(Point { x: 3, y: 1 }, "X")], plots(&flush(&mut plotter)));
+    }
+
+    #[test]
+    fn joins_changes_that_are_close_together() {
+        let mut plotter = create_plotter(20, 1);
+        plotter.plot(Point { x: 0, y: 0 }, "A B").unwrap();
+
+        assert_eq!(vec![(Point { x: 0, y: 0 }, "A B")], plots(&flush(&mut plotter)));
+    }
+
+    #[test]
+    fn moves_the_cursor_past_long_runs_of_unchanged_cells() {
+        let mut plotter = create_plotter(20, 1);
+        plotter.plot(Point { x: 0, y: 0 }, "A").unwrap();
+        plotter.plot(Point { x: 10, y: 0 }, "B").unwrap();
+
+        assert_eq!(
+            vec![(Point { x: 0, y: 0 }, "A"), (Point { x: 10, y: 0 }, "B")],
+            plots(&flush(&mut plotter))
+        );
+    }
+
+    #[test]
+    fn only_changes_the_paintbrush_when_needed() {
+        let mut plotter = create_plotter(20, 2);
+        let red = Paintbrush { fg: Color::Red, ..Paintbrush::create_default() };
+        plotter.set_paintbrush(&red).unwrap();
+        plotter.plot(Point { x: 0, y: 0 }, "AB").unwrap();
+        plotter.plot(Point { x: 0, y: 1 }, "CD").unwrap();
+        plotter.set_paintbrush(&Paintbrush::create_default()).unwrap();
+        plotter.plot(Point { x: 2, y: 1 }, "EF").unwrap();
+
+        let commands = flush(&mut plotter);
+
+        assert_eq!(
+            vec![
+                (Point { x: 0, y: 0 }, "AB"),
+                (Point { x: 0, y: 1 }, "CD"),
+                (Point { x: 2, y: 1 }, "EF")
+            ],
+            plots(&commands)
+        );
+        assert_eq!(vec![&red, &Paintbrush::create_default()], paintbrushes(&commands));
+    }
+
+    #[test]
+    fn clips_content_outside_of_the_plot_area() {
+        let mut plotter = create_plotter(4, 1);
+        plotter.plot(Point { x: 2, y: 0 }, "FOO").unwrap();
+        plotter.plot(Point { x: 0, y: 1 }, "BAR").unwrap();
+        plotter.plot(Point { x: 5, y: 0 }, "BAZ").unwrap();
+
+        assert_eq!(vec![(Point { x: 2, y: 0 }, "FO")], plots(&flush(&mut plotter)));
+    }
+
+    #[test]
+    fn redraws_the_whole_screen_after_it_is_resized() {
+        let mut plotter = create_plotter(3, 1);
+        plotter.plot(Point { x: 0, y: 0 }, "FOO").unwrap();
+        flush(&mut plotter);
+
+        plotter.inner_mut().plot_area = Dimensions { width: 4, height: 2 };
+        flush(&mut plotter);
+        assert_eq!(Dimensions { width: 4, height: 2 }, plotter.get_plot_area());
+
+        assert_eq!(
+            vec![(Point { x: 0, y: 0 }, "FOO "), (Point { x: 0, y: 1 }, "    ")],
+            plots(&flush(&mut plotter))
+        );
+    }
+
+    #[test]
+    fn can_be_told_to_redraw_everything() {
+        let mut plotter = create_plotter(3, 1);
+        plotter.plot(Point { x: 0, y: 0 }, "FOO").unwrap();
+        flush(&mut plotter);
+
+        plotter.redraw_all();
+
+        assert_eq!(vec![(Point { x: 0, y: 0 }, "FOO")], plots(&flush(&mut plotter)));
+    }
+}
@@ -0,0 +1,323 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::lowlevel::Color;
+
+/// The levels of each channel in the 6x6x6 colour cube of the 256-colour
+/// palette (indexes 16 to 231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The named colors, with the RGB values that xterm uses for them.
+const NAMED_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (128, 0, 0)),
+    (Color::DarkGreen, (0, 128, 0)),
+    (Color::DarkYellow, (128, 128, 0)),
+    (Color::DarkBlue, (0, 0, 128)),
+    (Color::DarkMagenta, (128, 0, 128)),
+    (Color::DarkCyan, (0, 128, 128)),
+    (Color::Grey, (192, 192, 192)),
+    (Color::DarkGrey, (128, 128, 128)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (0, 0, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// The colors that a terminal can show. `Color::Rgb` colors are converted to
+/// the nearest color that the terminal can show, and no colors are sent at all
+/// to terminals without color.
+///
+/// ## Example
+///
+/// ```
+/// use tui::ColorSupport;
+///
+/// // Usually detected from the environment with `ColorSupport::detect()`.
+/// let color_support = ColorSupport::from_env(|name| match name {
+///     "TERM" => Some("xterm-256color".to_string()),
+///     _ => None,
+/// });
+///
+/// assert_eq!(ColorSupport::Ansi256, color_support);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// No colors. Components that set a background color are shown in
+    /// reverse video instead, so that they still stand out.
+    None,
+
+    /// The 16 named colors.
+    Ansi16,
+
+    /// The 256-color palette, i.e. the named colors, a 6x6x6 color cube and a
+    /// grey ramp.
+    Ansi256,
+
+    /// Any 24-bit RGB color.
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Detect the colors that the terminal can show from the environment (see
+    /// `from_env()`).
+    pub fn detect() -> ColorSupport {
+        ColorSupport::from_env(|name| std::env::var(name).ok())
+    }
+
+    /// Work out the colors that the terminal can show from environment
+    /// variables:
+    ///
+    /// * `NO_COLOR`: When set (and not empty), no colors are used (see
+    ///   <https://no-color.org>).
+    /// * `COLORTERM`: `truecolor` or `24bit` means that RGB colors can be used.
+    /// * `TERM`: `dumb` terminals have no colors, and names containing
+    ///   `256color` have the 256-color palette. Other terminals are assumed to
+    ///   have the 16 named colors.
+    ///
+    /// ## Arguments
+    ///
+    /// * `get_var`: Gets the value of an environment variable, if it is set.
+    pub fn from_env<GetVarT: Fn(&str) -> Option<String>>(get_var: GetVarT) -> ColorSupport {
+        if get_var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return ColorSupport::None;
+        }
+
+        let color_term = get_var("COLORTERM").unwrap_or_default().to_lowercase();
+        if color_term == "truecolor" || color_term == "24bit" {
+            return ColorSupport::TrueColor;
+        }
+
+        let term = get_var("TERM").unwrap_or_default().to_lowercase();
+        if term == "dumb" {
+            ColorSupport::None
+        } else if term.contains("direct") || term.contains("truecolor") {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    /// Convert a color to the nearest color that the terminal can show.
+    ///
+    /// ## Arguments
+    ///
+    /// * `color`: The color to convert.
+    ///
+    /// ## Returns
+    ///
+    /// The crossterm color to send to the terminal. Every color is reset to
+    /// the terminal's default if the terminal has no colors.
+    pub(crate) fn convert(&self, color: &Color) -> crossterm::style::Color {
+        use crossterm::style::Color as Crossterm;
+
+        match (self, color) {
+            (ColorSupport::None, _) | (_, Color::Unset) => Crossterm::Reset,
+            (ColorSupport::TrueColor, Color::Rgb(r, g, b)) => {
+                Crossterm::Rgb { r: *r, g: *g, b: *b }
+            }
+            (ColorSupport::Ansi256, Color::Rgb(r, g, b)) => {
+                Crossterm::AnsiValue(nearest_ansi256(*r, *g, *b))
+            }
+            (ColorSupport::Ansi16, Color::Rgb(r, g, b)) => {
+                ColorSupport::Ansi16.convert(&nearest_named_color(*r, *g, *b))
+            }
+            (_, Color::Black) => Crossterm::Black,
+            (_, Color::Blue) => Crossterm::Blue,
+            (_, Color::Cyan) => Crossterm::Cyan,
+            (_, Color::DarkBlue) => Crossterm::DarkBlue,
+            (_, Color::DarkCyan) => Crossterm::DarkCyan,
+            (_, Color::DarkGreen) => Crossterm::DarkGreen,
+            (_, Color::DarkGrey) => Crossterm::DarkGrey,
+            (_, Color::DarkMagenta) => Crossterm::DarkMagenta,
+            (_, Color::DarkRed) => Crossterm::DarkRed,
+            (_, Color::DarkYellow) => Crossterm::DarkYellow,
+            (_, Color::Green) => Crossterm::Green,
+            (_, Color::Grey) => Crossterm::Grey,
+            (_, Color::Magenta) => Crossterm::Magenta,
+            (_, Color::Red) => Crossterm::Red,
+            (_, Color::White) => Crossterm::White,
+            (_, Color::Yellow) => Crossterm::Yellow,
+        }
+    }
+}
+
+impl FromStr for ColorSupport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ColorSupport, String> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(ColorSupport::None),
+            "16" => Ok(ColorSupport::Ansi16),
+            "256" => Ok(ColorSupport::Ansi256),
+            "truecolor" => Ok(ColorSupport::TrueColor),
+            _ => Err(format!(
+                "Unknown color support \"{}\" (expected one of: none, 16, 256, truecolor)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ColorSupport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorSupport::None => write!(f, "none"),
+            ColorSupport::Ansi16 => write!(f, "16"),
+            ColorSupport::Ansi256 => write!(f, "256"),
+            ColorSupport::TrueColor => write!(f, "truecolor"),
+        }
+    }
+}
+
+/// The squared distance between two RGB colors.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+/// Find the nearest named color to an RGB color.
+fn nearest_named_color(r: u8, g: u8, b: u8) -> Color {
+    let (color, _) = NAMED_COLORS
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb, (r, g, b)))
+        .expect("There are named colors");
+    color.clone()
+}
+
+/// Find the nearest color to an RGB color in the color cube or grey ramp of
+/// the 256-color palette. The named colors (indexes 0 to 15) are not used,
+/// since terminals show them in different ways.
+fn nearest_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let nearest_level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|i| (CUBE_LEVELS[*i] as i32 - value as i32).abs())
+            .expect("There are cube levels")
+    };
+    let (r_index, g_index, b_index) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube_rgb = (CUBE_LEVELS[r_index], CUBE_LEVELS[g_index], CUBE_LEVELS[b_index]);
+    let cube_index = 16 + 36 * r_index + 6 * g_index + b_index;
+
+    // The grey ramp goes from 8 to 238 in steps of 10.
+    let average = (r as usize + g as usize + b as usize) / 3;
+    let grey_step = (average.saturating_sub(3) / 10).min(23);
+    let grey_level = (8 + 10 * grey_step) as u8;
+    let grey_index = 232 + grey_step;
+
+    if distance((grey_level, grey_level, grey_level), (r, g, b)) < distance(cube_rgb, (r, g, b)) {
+        grey_index as u8
+    } else {
+        cube_index as u8
+    }
+}
+
+#[cfg(test)]
+mod color_support_tests {
+    use super::*;
+    use crossterm::style::Color as Crossterm;
+
+    fn env(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name| vars.iter().find(|(var, _)| *var == name).map(|(_, value)| value.to_string())
+    }
+
+    #[test]
+    fn is_detected_from_the_environment() {
+        assert_eq!(ColorSupport::Ansi16, ColorSupport::from_env(env(&[])));
+        assert_eq!(ColorSupport::Ansi16, ColorSupport::from_env(env(&[("TERM", "xterm")])));
+        assert_eq!(
+            ColorSupport::Ansi256,
+            ColorSupport::from_env(env(&[("TERM", "screen-256color")]))
+        );
+        assert_eq!(
+            ColorSupport::TrueColor,
+            ColorSupport::from_env(env(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")]))
+        );
+        assert_eq!(ColorSupport::TrueColor, ColorSupport::from_env(env(&[("COLORTERM", "24bit")])));
+        assert_eq!(ColorSupport::None, ColorSupport::from_env(env(&[("TERM", "dumb")])));
+    }
+
+    #[test]
+    fn no_color_turns_colors_off() {
+        assert_eq!(
+            ColorSupport::None,
+            ColorSupport::from_env(env(&[("NO_COLOR", "1"), ("COLORTERM", "truecolor")]))
+        );
+
+        // An empty NO_COLOR is ignored.
+        assert_eq!(
+            ColorSupport::TrueColor,
+            ColorSupport::from_env(env(&[("NO_COLOR", ""), ("COLORTERM", "truecolor")]))
+        );
+    }
+
+    #[test]
+    fn rgb_colors_are_converted_to_the_nearest_palette_color() {
+        let orange = Color::Rgb(255, 140, 0);
+        assert_eq!(
+            Crossterm::Rgb { r: 255, g: 140, b: 0 },
+            ColorSupport::TrueColor.convert(&orange)
+        );
+        assert_eq!(Crossterm::AnsiValue(208), ColorSupport::Ansi256.convert(&orange));
+        assert_eq!(Crossterm::Yellow, ColorSupport::Ansi16.convert(&orange));
+        assert_eq!(Crossterm::Reset, ColorSupport::None.convert(&orange));
+    }
+
+    #[test]
+    fn greys_are_converted_to_the_grey_ramp() {
+        assert_eq!(Crossterm::AnsiValue(232), ColorSupport::Ansi256.convert(&Color::Rgb(8, 8, 8)));
+        assert_eq!(
+            Crossterm::AnsiValue(244),
+            ColorSupport::Ansi256.convert(&Color::Rgb(128, 128, 128))
+        );
+        assert_eq!(Crossterm::AnsiValue(16), ColorSupport::Ansi256.convert(&Color::Rgb(0, 0, 0)));
+        assert_eq!(Crossterm::DarkGrey, ColorSupport::Ansi16.convert(&Color::Rgb(120, 120, 130)));
+    }
+
+    #[test]
+    fn named_colors_are_kept_unless_colors_are_off() {
+        assert_eq!(Crossterm::DarkCyan, ColorSupport::Ansi16.convert(&Color::DarkCyan));
+        assert_eq!(Crossterm::DarkCyan, ColorSupport::TrueColor.convert(&Color::DarkCyan));
+        assert_eq!(Crossterm::Reset, ColorSupport::Ansi256.convert(&Color::Unset));
+        assert_eq!(Crossterm::Reset, ColorSupport::None.convert(&Color::DarkCyan));
+    }
+
+    #[test]
+    fn is_parsed_from_its_name() {
+        for color_support in [
+            ColorSupport::None,
+            ColorSupport::Ansi16,
+            ColorSupport::Ansi256,
+            ColorSupport::TrueColor,
+        ] {
+            assert_eq!(Ok(color_support), color_support.to_string().parse());
+        }
+        assert!("millions".parse::<ColorSupport>().is_err());
+    }
+}
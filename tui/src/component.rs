@@ -0,0 +1,234 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::event::Event;
+use crate::layout::Rect;
+use crate::lowlevel::{Paintbrush, Plotter};
+use xy_utils::{Dimensions, Point};
+
+/// The interface shared by all of the widgets in `tui::components`.
+///
+/// The trait is generic over the plotter rather than its methods, so that
+/// components drawn with the same plotter can be held together as trait
+/// objects (e.g. `Vec<Box<dyn Component<MyPlotter>>>`).
+///
+/// Components track whether they have changed since they were last rendered,
+/// so applications only need to render the components that are dirty.
+///
+/// ## Example
+///
+/// ```
+/// # use tui::mock::MockPlotter as MyPlotter;
+/// use tui::components::{Border, TextLabel};
+/// use tui::{Component, Paintbrush, Plotter};
+/// use xy_utils::{Dimensions, Point};
+///
+/// let mut plotter = MyPlotter::new();
+/// let mut components: Vec<Box<dyn Component<MyPlotter>>> = vec![
+///     Box::new(Border::new(
+///         Paintbrush::create_default(),
+///         Point { x: 0, y: 0 },
+///         Dimensions { width: 5, height: 3 },
+///     )),
+///     Box::new(TextLabel::new(
+///         Paintbrush::create_default(),
+///         Point { x: 1, y: 1 },
+///         Dimensions { width: 3, height: 1 },
+///         "FOO",
+///     )),
+/// ];
+///
+/// for component in components.iter_mut().filter(|component| component.is_dirty()) {
+///     component.render(&mut plotter).unwrap();
+/// }
+/// plotter.flush().unwrap();
+///
+/// assert!(components.iter().all(|component| !component.is_dirty()));
+/// ```
+pub trait Component<PlotterT: Plotter> {
+    /// Get the location of the top-left corner of the component in the UI.
+    fn position(&self) -> Point;
+
+    /// Get the size of the area of the UI that the component covers.
+    fn size(&self) -> Dimensions;
+
+    /// Get the area of the UI that the component covers.
+    fn area(&self) -> Rect {
+        Rect::new(self.position(), self.size())
+    }
+
+    /// Move the component to a new area of the UI (e.g. after the terminal is
+    /// resized). The whole component is drawn again the next time it is
+    /// rendered.
+    ///
+    /// ## Arguments
+    ///
+    /// * `area`: The new area of the component, usually calculated with a
+    ///   `tui::layout::Layout`.
+    fn set_area(&mut self, area: Rect);
+
+    /// Render the component using the provided low level UI plotter.
+    ///
+    /// The component is no longer dirty once it has been rendered.
+    fn render(&mut self, plotter: &mut PlotterT) -> Result<(), std::io::Error>;
+
+    /// Check whether the component has changed since it was last rendered.
+    fn is_dirty(&self) -> bool;
+
+    /// Force the whole component to be drawn again the next time it is
+    /// rendered (e.g. because something else was drawn over it).
+    fn mark_dirty(&mut self);
+
+    /// Handle an input event.
+    ///
+    /// ## Arguments
+    ///
+    /// * `event`: The event to handle.
+    ///
+    /// ## Returns
+    ///
+    /// True if the component used the event, in which case it should not be
+    /// passed to any other component. By default components ignore events.
+    fn handle_event(&mut self, _event: &Event) -> bool {
+        false
+    }
+}
+
+/// Render the components that have changed, in order, so that later
+/// components are drawn over earlier ones (e.g. a dialog over the rest of the
+/// user interface).
+///
+/// A component that has not changed is still drawn again if a component
+/// beneath it was drawn over it.
+///
+/// ## Arguments
+///
+/// * `plotter`: The plotter to render the components with.
+/// * `layers`: The components, from the bottom layer to the top.
+pub fn render_layers<PlotterT: Plotter>(
+    plotter: &mut PlotterT,
+    layers: &mut [&mut dyn Component<PlotterT>],
+) -> Result<(), std::io::Error> {
+    let mut drawn_areas: Vec<Rect> = vec![];
+    for layer in layers.iter_mut() {
+        if drawn_areas.iter().any(|area| area.intersects(&layer.area())) {
+            layer.mark_dirty();
+        }
+        if layer.is_dirty() {
+            layer.render(plotter)?;
+            drawn_areas.push(layer.area());
+        }
+    }
+
+    Ok(())
+}
+
+/// Clear an area that a component was drawn over (e.g. a dialog that has
+/// been closed), and mark the components beneath it as dirty, so that they
+/// are drawn again the next time they are rendered.
+///
+/// ## Arguments
+///
+/// * `plotter`: The plotter that the area was drawn with.
+/// * `area`: The area to clear.
+/// * `components`: The components that may be beneath the area.
+pub fn redraw_background<PlotterT: Plotter>(
+    plotter: &mut PlotterT,
+    area: Rect,
+    components: &mut [&mut dyn Component<PlotterT>],
+) -> Result<(), std::io::Error> {
+    plotter.set_paintbrush(&Paintbrush::create_default())?;
+    let blank_row = " ".repeat(area.size.width);
+    for y in area.position.y..area.position.y + area.size.height {
+        plotter.plot(Point { x: area.position.x, y }, &blank_row)?;
+    }
+
+    for component in components.iter_mut() {
+        if component.area().intersects(&area) {
+            component.mark_dirty();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod component_tests {
+    use super::*;
+    use crate::components::{Border, TextLabel};
+    use crate::mock::ScreenPlotter;
+
+    fn label(x: usize, y: usize, width: usize, text: &str) -> TextLabel {
+        TextLabel::new(
+            Paintbrush::create_default(),
+            Point { x, y },
+            Dimensions { width, height: 1 },
+            text,
+        )
+    }
+
+    #[test]
+    fn layers_are_drawn_again_when_something_beneath_them_is_drawn() {
+        let mut plotter = ScreenPlotter::new(Dimensions { width: 8, height: 3 });
+        let mut background = label(0, 1, 8, "........");
+        let mut popup = label(2, 1, 4, "OK");
+        render_layers(&mut plotter, &mut [&mut background, &mut popup]).unwrap();
+
+        background.update("########");
+        render_layers(&mut plotter, &mut [&mut background, &mut popup]).unwrap();
+
+        plotter.assert_screen(
+            "
+            |
+            |##OK  ##
+            |
+            ",
+        );
+    }
+
+    #[test]
+    fn the_background_is_drawn_again_once_a_layer_is_removed() {
+        let mut plotter = ScreenPlotter::new(Dimensions { width: 8, height: 4 });
+        let mut top = label(0, 0, 8, "top");
+        let mut bottom = label(0, 3, 8, "bottom");
+        let mut popup = Border::new(
+            Paintbrush::create_default(),
+            Point { x: 1, y: 0 },
+            Dimensions { width: 6, height: 3 },
+        );
+        render_layers(&mut plotter, &mut [&mut top, &mut bottom, &mut popup]).unwrap();
+
+        let popup_area = Component::<ScreenPlotter>::area(&popup);
+        redraw_background(&mut plotter, popup_area, &mut [&mut top, &mut bottom]).unwrap();
+        assert!(Component::<ScreenPlotter>::is_dirty(&top));
+        assert!(!Component::<ScreenPlotter>::is_dirty(&bottom));
+        render_layers(&mut plotter, &mut [&mut top, &mut bottom]).unwrap();
+
+        plotter.assert_screen(
+            "
+            |top
+            |
+            |
+            |bottom
+            ",
+        );
+    }
+}
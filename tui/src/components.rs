@@ -22,10 +22,23 @@
 
 mod border;
 mod canvas;
+mod chart;
 mod count;
+mod dialog;
+mod file_browser;
+mod frame;
+mod help;
+mod menu;
+mod text_input;
 mod text_label;
 
 pub use border::Border;
 pub use canvas::Canvas;
+pub use chart::Chart;
 pub use count::Count;
+pub use dialog::Dialog;
+pub use file_browser::FileBrowser;
+pub use help::Help;
+pub use menu::Menu;
+pub use text_input::TextInput;
 pub use text_label::TextLabel;
@@ -20,7 +20,9 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
+use crate::layout::Rect;
 use crate::lowlevel::*;
+use crate::Component;
 use xy_utils::{Dimensions, Point};
 
 const TOP_LEFT_CORNER: &str = "┌";
@@ -46,11 +48,11 @@ const VERTICAL_LINE: &str = "│";
 /// ```
 /// # use tui::mock::MockPlotter as MyPlotter;
 /// use tui::components::Border;
-/// use tui::Paintbrush;
+/// use tui::{Component, Paintbrush};
 /// use xy_utils::{Dimensions, Point};
 ///
 /// let mut plotter = MyPlotter::new();
-/// let border = Border::new(
+/// let mut border = Border::new(
 ///     Paintbrush::create_default(),
 ///     Point { x: 0, y: 0 },
 ///     Dimensions { width: 15, height: 10 }
@@ -58,6 +60,7 @@ const VERTICAL_LINE: &str = "│";
 ///
 /// border.render(&mut plotter).unwrap();
 /// ```
+#[derive(Clone)]
 pub struct Border {
     /// The paintbrush used to draw the border.
     pub paintbrush: Paintbrush,
@@ -67,15 +70,34 @@ pub struct Border {
 
     /// The size of the canvas.
     pub size: Dimensions,
+
+    /// True when the border needs to be drawn.
+    dirty: bool,
 }
 
 impl Border {
     pub fn new(paintbrush: Paintbrush, position: Point, size: Dimensions) -> Self {
-        Border { paintbrush, position, size }
+        Border { paintbrush, position, size, dirty: true }
+    }
+}
+
+impl<PlotterT: Plotter> Component<PlotterT> for Border {
+    fn position(&self) -> Point {
+        self.position
+    }
+
+    fn size(&self) -> Dimensions {
+        self.size
     }
 
-    /// Render the text label using the provided low level UI plotter.
-    pub fn render<PlotterT: Plotter>(&self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
+    fn set_area(&mut self, area: Rect) {
+        self.position = area.position;
+        self.size = area.size;
+        self.dirty = true;
+    }
+
+    /// Render the border using the provided low level UI plotter.
+    fn render(&mut self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
         let top_left = self.position;
         let bottom_right = Point {
             x: self.position.x + self.size.width - 1,
@@ -117,8 +139,17 @@ impl Border {
 
         plotter.plot(bottom_left, &row_string)?;
 
+        self.dirty = false;
         Ok(())
     }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
 }
 
 #[cfg(test)]
@@ -140,7 +171,7 @@ mod border_tests {
     #[test]
     fn can_be_rendered_with_a_lowlevel_plotter() {
         let mut plotter = mock::MockPlotter::new();
-        let border = Border::new(
+        let mut border = Border::new(
             Paintbrush::create_default(),
             Point { x: 1, y: 2 },
             Dimensions { width: 3, height: 3 },
@@ -202,4 +233,22 @@ mod border_tests {
             _ => panic!("Incorrect fifth plotter command"),
         }
     }
+
+    #[test]
+    fn is_dirty_until_it_is_rendered() {
+        let mut plotter = mock::MockPlotter::new();
+        let mut border = Border::new(
+            Paintbrush::create_default(),
+            Point { x: 1, y: 2 },
+            Dimensions { width: 3, height: 3 },
+        );
+        let border: &mut dyn Component<mock::MockPlotter> = &mut border;
+        assert!(border.is_dirty());
+
+        border.render(&mut plotter).unwrap();
+        assert!(!border.is_dirty());
+
+        border.mark_dirty();
+        assert!(border.is_dirty());
+    }
 }
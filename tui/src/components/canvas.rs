@@ -22,7 +22,10 @@
 
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::event::{Event, MouseEvent};
+use crate::layout::Rect;
 use crate::lowlevel::*;
+use crate::Component;
 use xy_utils::{Dimensions, Point};
 
 const EMPTY_CHAR: char = ' ';
@@ -38,6 +41,7 @@ enum CanvasCommand {
 }
 
 /// Struct that defines a simple rectangular grid of characters.
+#[derive(Clone)]
 pub struct Canvas {
     /// The location in the UI to render the canvas.
     pub position: Point,
@@ -45,13 +49,23 @@ pub struct Canvas {
     /// The size of the canvas.
     pub size: Dimensions,
 
-    /// The label text (updated via the update() method).
+    /// The current content of each cell of the canvas, row by row. Used to
+    /// redraw the whole canvas when it is marked as dirty.
+    cells: Vec<Vec<(Paintbrush, String)>>,
+
+    /// The changes that have not been rendered yet.
     changes: Vec<CanvasCommand>,
+
+    /// The mouse events that have not been taken yet, or `None` if the canvas
+    /// ignores the mouse.
+    mouse_events: Option<Vec<MouseEvent>>,
 }
 
 impl Canvas {
     pub fn new(position: Point, size: Dimensions) -> Self {
-        let mut result = Canvas { position, size, changes: vec![] };
+        let empty_cell = (Paintbrush::create_default(), EMPTY_CHAR.to_string());
+        let cells = vec![vec![empty_cell; size.width]; size.height];
+        let mut result = Canvas { position, size, cells, changes: vec![], mouse_events: None };
         let size = &result.size;
 
         // Set up the initial grid.
@@ -62,7 +76,7 @@ impl Canvas {
 
         // Clear the canvas the first time it is rendered.
         let mut row_idx = 0;
-        result.changes.resize_with(result.size.height as usize, || {
+        result.changes.resize_with(result.size.height, || {
             row_idx += 1;
             CanvasCommand::Draw(Point { x: 0, y: row_idx - 1 }, columns_str.clone())
         });
@@ -83,7 +97,8 @@ impl Canvas {
     ///   left corner.
     /// * `val`: The value to set to. The val should be zero or more unicode
     ///   characters. If the number of characters goes beyond the width of the
-    ///   canvas, an error is returned.
+    ///   canvas (or the point is below the bottom of the canvas), an error is
+    ///   returned.
     pub fn draw_str(
         &mut self,
         paintbrush: Paintbrush,
@@ -97,16 +112,81 @@ impl Canvas {
                 "String of characters would exceed the width of the canvas",
             ));
         }
+        if position.y >= self.size.height {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "String of characters would be below the bottom of the canvas",
+            ));
+        }
+
+        let row = &mut self.cells[position.y];
+        for (x, grapheme) in (position.x..).zip(val.graphemes(true)) {
+            row[x] = (paintbrush.clone(), grapheme.to_string());
+        }
         self.changes.push(CanvasCommand::ChangeColor(paintbrush));
         self.changes.push(CanvasCommand::Draw(position, val.to_string()));
         Ok(())
     }
 
-    /// Render the text label using the provided low level UI plotter.
-    pub fn render<PlotterT: Plotter>(
-        &mut self,
-        plotter: &mut PlotterT,
-    ) -> Result<(), std::io::Error> {
+    /// Start collecting the mouse events that the canvas is given (see
+    /// `tui::event::MouseDispatcher`). By default the canvas ignores the mouse.
+    pub fn enable_mouse_input(&mut self) {
+        self.mouse_events.get_or_insert_with(Vec::new);
+    }
+
+    /// Take the mouse events that the canvas has been given since they were
+    /// last taken. Event positions are relative to the top-left corner of the
+    /// canvas.
+    pub fn take_mouse_events(&mut self) -> Vec<MouseEvent> {
+        self.mouse_events.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Replace any pending changes with a redraw of the whole canvas. Each row
+    /// is drawn as runs of cells that share a paintbrush.
+    fn redraw(&mut self) {
+        self.changes.clear();
+        for (y, row) in self.cells.iter().enumerate() {
+            let mut run_start = 0;
+            for x in 1..=row.len() {
+                if x < row.len() && row[x].0 == row[run_start].0 {
+                    continue;
+                }
+
+                let text: String = row[run_start..x].iter().map(|(_, s)| s.as_str()).collect();
+                self.changes.push(CanvasCommand::ChangeColor(row[run_start].0.clone()));
+                self.changes.push(CanvasCommand::Draw(Point { x: run_start, y }, text));
+                run_start = x;
+            }
+        }
+    }
+}
+
+impl<PlotterT: Plotter> Component<PlotterT> for Canvas {
+    fn position(&self) -> Point {
+        self.position
+    }
+
+    fn size(&self) -> Dimensions {
+        self.size
+    }
+
+    /// Move the canvas. If the size of the canvas changes, cells that are
+    /// outside the new size are lost, and new cells are empty.
+    fn set_area(&mut self, area: Rect) {
+        self.position = area.position;
+        self.size = area.size;
+
+        let empty_cell = (Paintbrush::create_default(), EMPTY_CHAR.to_string());
+        self.cells.resize(self.size.height, vec![]);
+        for row in &mut self.cells {
+            row.resize(self.size.width, empty_cell.clone());
+        }
+        self.redraw();
+    }
+
+    /// Render the changes made to the canvas since it was last rendered, using
+    /// the provided low level UI plotter.
+    fn render(&mut self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
         for command in self.changes.drain(..) {
             match command {
                 CanvasCommand::Draw(pos, s) => plotter
@@ -117,8 +197,26 @@ impl Canvas {
 
         Ok(())
     }
-}
 
+    fn is_dirty(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    fn mark_dirty(&mut self) {
+        self.redraw();
+    }
+
+    /// Collect mouse events, if mouse input has been enabled.
+    fn handle_event(&mut self, event: &Event) -> bool {
+        match (event, &mut self.mouse_events) {
+            (Event::Mouse(mouse_event), Some(mouse_events)) => {
+                mouse_events.push(*mouse_event);
+                true
+            }
+            _ => false,
+        }
+    }
+}
 #[cfg(test)]
 mod canvas_tests {
     use super::*;
@@ -219,7 +317,7 @@ mod canvas_tests {
 
         // Even though the canvas could technically print the whole string on
         // a single line, the X offset we provide pushes it beyond the edge.
-        if let Ok(_) = canvas.draw_str(Paintbrush::create_default(), Point { x: 1, y: 0 }, "***") {
+        if canvas.draw_str(Paintbrush::create_default(), Point { x: 1, y: 0 }, "***").is_ok() {
             panic!("This test should have failed due to writing out of bounds!");
         }
 
@@ -230,4 +328,108 @@ mod canvas_tests {
         // canvas clearing (x3), then the flush command we just executed above.
         assert_eq!(4, plotter.command_list.len());
     }
+
+    #[test]
+    fn is_dirty_until_its_changes_are_rendered() {
+        let mut plotter = mock::MockPlotter::new();
+        let mut canvas = Canvas::new(Point { x: 1, y: 2 }, Dimensions { width: 3, height: 3 });
+        let canvas: &mut dyn Component<mock::MockPlotter> = &mut canvas;
+        assert!(canvas.is_dirty());
+
+        canvas.render(&mut plotter).unwrap();
+        assert!(!canvas.is_dirty());
+    }
+
+    #[test]
+    fn will_not_allow_writing_data_below_the_canvas() {
+        let mut canvas = Canvas::new(Point { x: 1, y: 2 }, Dimensions { width: 3, height: 3 });
+
+        assert!(canvas.draw_str(Paintbrush::create_default(), Point { x: 0, y: 3 }, "*").is_err());
+    }
+
+    #[test]
+    fn redraws_its_whole_content_when_marked_as_dirty() {
+        let mut plotter = mock::MockPlotter::new();
+        let mut canvas = Canvas::new(Point { x: 1, y: 2 }, Dimensions { width: 3, height: 2 });
+        let red = Paintbrush { fg: Color::Red, ..Paintbrush::create_default() };
+        canvas.draw_str(Paintbrush::create_default(), Point { x: 0, y: 0 }, "**").unwrap();
+        canvas.draw_str(red.clone(), Point { x: 1, y: 1 }, "#").unwrap();
+        let canvas: &mut dyn Component<mock::MockPlotter> = &mut canvas;
+        canvas.render(&mut plotter).unwrap();
+        plotter.command_list.clear();
+
+        canvas.mark_dirty();
+        canvas.render(&mut plotter).unwrap();
+
+        let expected = [
+            (Paintbrush::create_default(), Point { x: 1, y: 2 }, "** "),
+            (Paintbrush::create_default(), Point { x: 1, y: 3 }, " "),
+            (red, Point { x: 2, y: 3 }, "#"),
+            (Paintbrush::create_default(), Point { x: 3, y: 3 }, " "),
+        ];
+        assert_eq!(expected.len() * 2, plotter.command_list.len());
+        for (commands, (expected_pb, expected_point, expected_text)) in
+            plotter.command_list.chunks(2).zip(expected)
+        {
+            match commands {
+                [mock::MockPlotterCommand::SetPaintbrush(pb), mock::MockPlotterCommand::PlotObject(point, s)] =>
+                {
+                    assert_eq!(&expected_pb, pb);
+                    assert_eq!(&expected_point, point);
+                    assert_eq!(expected_text, s);
+                }
+                _ => panic!("Unexpected plotter commands {:?}", commands),
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_its_content_when_it_is_resized() {
+        let mut plotter = mock::MockPlotter::new();
+        let mut canvas = Canvas::new(Point { x: 1, y: 2 }, Dimensions { width: 3, height: 2 });
+        canvas.draw_str(Paintbrush::create_default(), Point { x: 0, y: 0 }, "***").unwrap();
+        let canvas: &mut dyn Component<mock::MockPlotter> = &mut canvas;
+        canvas.render(&mut plotter).unwrap();
+        plotter.command_list.clear();
+
+        canvas.set_area(Rect::new(Point { x: 0, y: 0 }, Dimensions { width: 2, height: 3 }));
+        canvas.render(&mut plotter).unwrap();
+
+        let rows: Vec<(Point, &str)> = plotter
+            .command_list
+            .iter()
+            .filter_map(|command| match command {
+                mock::MockPlotterCommand::PlotObject(point, s) => Some((*point, s.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (Point { x: 0, y: 0 }, "**"),
+                (Point { x: 0, y: 1 }, "  "),
+                (Point { x: 0, y: 2 }, "  "),
+            ],
+            rows
+        );
+    }
+
+    #[test]
+    fn collects_mouse_events_once_mouse_input_is_enabled() {
+        let mut canvas = Canvas::new(Point { x: 0, y: 0 }, Dimensions { width: 3, height: 3 });
+        let component: &mut dyn Component<mock::MockPlotter> = &mut canvas;
+        let mouse_event = MouseEvent {
+            kind: crate::event::MouseEventKind::Moved,
+            position: Point { x: 1, y: 1 },
+        };
+        let event = Event::Mouse(mouse_event);
+
+        assert!(!component.handle_event(&event));
+        canvas.enable_mouse_input();
+        let component: &mut dyn Component<mock::MockPlotter> = &mut canvas;
+        assert!(component.handle_event(&event));
+        assert!(!component.handle_event(&Event::Tick));
+
+        assert_eq!(vec![mouse_event], canvas.take_mouse_events());
+        assert!(canvas.take_mouse_events().is_empty());
+    }
 }
@@ -0,0 +1,484 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::collections::VecDeque;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::layout::Rect;
+use crate::lowlevel::*;
+use crate::Component;
+use xy_utils::{Dimensions, Point};
+
+/// Bar characters, indexed by the number of eighths of the cell to fill.
+const BAR_CHARS: [&str; 9] = [" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+const OVERLAY_CHAR: &str = "•";
+const LEGEND_CHAR: &str = "■";
+const Y_AXIS_LINE: &str = "│";
+const Y_AXIS_TICK: &str = "┤";
+const X_AXIS_LINE: &str = "─";
+const ORIGIN: &str = "└";
+
+/// A single set of values plotted on the chart.
+struct ChartSeries {
+    name: String,
+    paintbrush: Paintbrush,
+}
+
+/// Struct that renders a scrolling time-series chart.
+///
+/// Samples are added with `push_sample()`, one value per series. The newest
+/// sample is shown in the right-most column, and old samples scroll off the
+/// left edge of the chart.
+///
+/// The first series is drawn as a filled bar chart. Any additional series are
+/// drawn as point "overlays" on top of the bars. The Y-axis is automatically
+/// scaled to the largest value currently visible, so the chart will show both
+/// growth and collapse of the plotted values.
+///
+/// The bottom row of the chart holds the X-axis, which is labelled with the
+/// index of the first and last visible samples, and a legend of the series
+/// names (if there is space for it).
+///
+/// ## Example
+///
+/// ```
+/// use tui::{mock, Color, Component, Paintbrush, Plotter};
+/// use tui::components::Chart;
+/// use xy_utils::{Dimensions, Point};
+///
+/// let mut plotter = mock::MockPlotter::new();
+/// let mut chart = Chart::new(Point { x: 0, y: 0 }, Dimensions { width: 20, height: 5 });
+/// chart.add_series("Population", Paintbrush::create_default());
+/// chart.add_series("Births", Paintbrush { fg: Color::Green, ..Paintbrush::create_default() });
+///
+/// chart.push_sample(&[10, 2]);
+/// chart.push_sample(&[12, 3]);
+///
+/// chart.render(&mut plotter).unwrap();
+/// plotter.flush().unwrap();
+/// ```
+pub struct Chart {
+    /// The location in the UI to render the chart.
+    pub position: Point,
+
+    /// The size of the chart, including the axes.
+    pub size: Dimensions,
+
+    /// The paintbrush used to draw the axes and labels.
+    axis_paintbrush: Paintbrush,
+
+    /// The plotted series.
+    series: Vec<ChartSeries>,
+
+    /// The most recent samples. Each sample holds one value per series.
+    samples: VecDeque<Vec<usize>>,
+
+    /// Total number of samples ever pushed to the chart.
+    total_samples: usize,
+
+    /// True when the chart has changed since it was last rendered.
+    dirty: bool,
+}
+
+impl Chart {
+    pub fn new(position: Point, size: Dimensions) -> Self {
+        Chart {
+            position,
+            size,
+            axis_paintbrush: Paintbrush::create_default(),
+            series: vec![],
+            samples: VecDeque::with_capacity(size.width),
+            total_samples: 0,
+            dirty: true,
+        }
+    }
+
+    /// Set the paintbrush used for the axes, labels and legend.
+    pub fn set_axis_paintbrush(&mut self, paintbrush: Paintbrush) {
+        self.axis_paintbrush = paintbrush;
+        self.dirty = true;
+    }
+
+    /// Add a series to the chart.
+    ///
+    /// The first series added is drawn as a bar chart, subsequent series are
+    /// drawn as overlays. Series should be added before any samples are
+    /// pushed.
+    ///
+    /// ## Arguments
+    ///
+    /// * `name`: The name of the series, shown in the legend.
+    /// * `paintbrush`: The colors to draw the series with.
+    pub fn add_series(&mut self, name: &str, paintbrush: Paintbrush) {
+        self.series.push(ChartSeries { name: name.to_string(), paintbrush });
+        self.dirty = true;
+    }
+
+    /// Add a sample to the chart.
+    ///
+    /// Call render() to update the chart in the UI.
+    ///
+    /// ## Arguments
+    ///
+    /// * `values`: One value per series, in the order that the series were
+    ///   added. Missing values are treated as zero, extra values are ignored.
+    pub fn push_sample(&mut self, values: &[usize]) {
+        let mut sample = values.to_vec();
+        sample.resize(self.series.len(), 0);
+
+        self.samples.push_back(sample);
+        self.drop_hidden_samples();
+        self.total_samples += 1;
+        self.dirty = true;
+    }
+
+    /// There can never be more visible samples than the chart is wide, so
+    /// samples that have scrolled off the chart are forgotten.
+    fn drop_hidden_samples(&mut self) {
+        while self.samples.len() > self.size.width {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Build the rendered chart as a list of rows. Each row is made up of
+    /// runs of text that share a paintbrush.
+    fn build_rows(&self) -> Vec<Vec<(Paintbrush, String)>> {
+        let mut rows = vec![];
+        if (self.size.height < 2) || (self.size.width < 3) {
+            // Not enough space to draw axes, so just blank the chart area.
+            for _ in 0..self.size.height {
+                rows.push(vec![(self.axis_paintbrush.clone(), " ".repeat(self.size.width))]);
+            }
+            return rows;
+        }
+
+        // The Y-axis labels need to be wide enough for the largest value, and
+        // the largest value depends on how many samples are visible. The label
+        // width is calculated from all retained samples, which is never
+        // smaller than the visible maximum.
+        let label_width = self.max_value(self.samples.len()).to_string().len();
+        let plot_width = self.size.width.saturating_sub(label_width + 1);
+        let plot_height = self.size.height - 1;
+        let max_value = self.max_value(plot_width).max(1);
+        let visible_samples = self.samples.len().min(plot_width);
+        let first_visible = self.samples.len() - visible_samples;
+
+        // Calculate the content of each plot cell (character and series
+        // index), starting from the top row.
+        let mut cells = vec![vec![(BAR_CHARS[0], None); plot_width]; plot_height];
+        let padding_columns = plot_width - visible_samples;
+        for (i, sample) in self.samples.iter().skip(first_visible).enumerate() {
+            let column = padding_columns + i;
+            for (series_idx, value) in sample.iter().enumerate() {
+                let eighths = (value * plot_height * 8) / max_value;
+                if series_idx == 0 {
+                    for (row_from_bottom, row) in cells.iter_mut().rev().enumerate() {
+                        let fill = eighths.saturating_sub(row_from_bottom * 8).min(8);
+                        if fill > 0 {
+                            row[column] = (BAR_CHARS[fill], Some(series_idx));
+                        }
+                    }
+                } else if *value > 0 {
+                    let row_from_bottom = (eighths.saturating_sub(1) / 8).min(plot_height - 1);
+                    cells[plot_height - 1 - row_from_bottom][column] =
+                        (OVERLAY_CHAR, Some(series_idx));
+                }
+            }
+        }
+
+        for (row_idx, row_cells) in cells.iter().enumerate() {
+            let mut row = vec![];
+            let (label, axis) = if row_idx == 0 {
+                (max_value.to_string(), Y_AXIS_TICK)
+            } else if row_idx == plot_height - 1 {
+                ("0".to_string(), Y_AXIS_TICK)
+            } else {
+                (String::new(), Y_AXIS_LINE)
+            };
+            row.push((
+                self.axis_paintbrush.clone(),
+                format!("{:>1$}{2}", label, label_width, axis),
+            ));
+
+            for (text, series_idx) in row_cells {
+                let paintbrush = match series_idx {
+                    Some(idx) => &self.series[*idx].paintbrush,
+                    None => &self.axis_paintbrush,
+                };
+                Self::push_run(&mut row, paintbrush, text);
+            }
+            rows.push(row);
+        }
+
+        rows.push(self.build_x_axis(label_width, plot_width, visible_samples));
+        rows
+    }
+
+    /// Build the X-axis row, with sample indices at either end, and a legend
+    /// in the middle.
+    fn build_x_axis(
+        &self,
+        label_width: usize,
+        plot_width: usize,
+        visible_samples: usize,
+    ) -> Vec<(Paintbrush, String)> {
+        let mut row =
+            vec![(self.axis_paintbrush.clone(), format!("{}{}", " ".repeat(label_width), ORIGIN))];
+
+        let (first_label, last_label) = if visible_samples == 0 {
+            (String::new(), String::new())
+        } else {
+            let first_index = self.total_samples - visible_samples;
+            (first_index.to_string(), (self.total_samples - 1).to_string())
+        };
+        let labels_width = first_label.len() + last_label.len();
+        if labels_width > plot_width {
+            Self::push_run(&mut row, &self.axis_paintbrush, &X_AXIS_LINE.repeat(plot_width));
+            return row;
+        }
+
+        // The legend is only shown if there is room for it, with at least one
+        // line character either side.
+        let legend_width = self.legend_width();
+        let free_space = plot_width - labels_width;
+        let show_legend = (legend_width > 0) && (legend_width + 2 <= free_space);
+        let line_width = if show_legend { free_space - legend_width } else { free_space };
+        let left_line_width = line_width / 2;
+
+        Self::push_run(&mut row, &self.axis_paintbrush, &first_label);
+        Self::push_run(&mut row, &self.axis_paintbrush, &X_AXIS_LINE.repeat(left_line_width));
+        if show_legend {
+            for (i, series) in self.series.iter().enumerate() {
+                Self::push_run(&mut row, &self.axis_paintbrush, if i == 0 { " " } else { "  " });
+                Self::push_run(&mut row, &series.paintbrush, LEGEND_CHAR);
+                Self::push_run(&mut row, &self.axis_paintbrush, &format!(" {}", series.name));
+            }
+            Self::push_run(&mut row, &self.axis_paintbrush, " ");
+        }
+        Self::push_run(
+            &mut row,
+            &self.axis_paintbrush,
+            &X_AXIS_LINE.repeat(line_width - left_line_width),
+        );
+        Self::push_run(&mut row, &self.axis_paintbrush, &last_label);
+
+        row
+    }
+
+    /// Number of cells taken by the legend (excluding surrounding lines).
+    fn legend_width(&self) -> usize {
+        if self.series.is_empty() {
+            return 0;
+        }
+
+        // Each entry is "■ Name", separated by two spaces, with a space at
+        // either end.
+        let entries: usize =
+            self.series.iter().map(|series| 2 + series.name.graphemes(true).count()).sum();
+        entries + ((self.series.len() - 1) * 2) + 2
+    }
+
+    /// Get the largest value of any series in the last `num_samples` samples.
+    fn max_value(&self, num_samples: usize) -> usize {
+        let skip = self.samples.len().saturating_sub(num_samples);
+        self.samples.iter().skip(skip).flat_map(|sample| sample.iter()).copied().max().unwrap_or(0)
+    }
+
+    /// Add text to a row, merging it with the previous run of text if the
+    /// paintbrush is the same.
+    fn push_run(row: &mut Vec<(Paintbrush, String)>, paintbrush: &Paintbrush, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        match row.last_mut() {
+            Some((last_paintbrush, last_text)) if last_paintbrush == paintbrush => {
+                last_text.push_str(text);
+            }
+            _ => row.push((paintbrush.clone(), text.to_string())),
+        }
+    }
+}
+
+impl<PlotterT: Plotter> Component<PlotterT> for Chart {
+    fn position(&self) -> Point {
+        self.position
+    }
+
+    fn size(&self) -> Dimensions {
+        self.size
+    }
+
+    fn set_area(&mut self, area: Rect) {
+        self.position = area.position;
+        self.size = area.size;
+        self.drop_hidden_samples();
+        self.dirty = true;
+    }
+
+    /// Render the chart using the provided low level UI plotter.
+    fn render(&mut self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
+        for (row_idx, row) in self.build_rows().iter().enumerate() {
+            let mut x = self.position.x;
+            for (paintbrush, text) in row {
+                plotter.set_paintbrush(paintbrush)?;
+                plotter.plot(Point { x, y: self.position.y + row_idx }, text)?;
+                x += text.graphemes(true).count();
+            }
+        }
+
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+}
+
+#[cfg(test)]
+mod chart_tests {
+    use super::*;
+
+    /// Collect the rendered rows of the chart as plain strings.
+    fn render_to_strings(chart: &mut Chart) -> Vec<String> {
+        let mut plotter = mock::MockPlotter::new();
+        chart.render(&mut plotter).unwrap();
+
+        let mut rows: Vec<String> = vec![String::new(); chart.size.height];
+        for command in &plotter.command_list {
+            if let mock::MockPlotterCommand::PlotObject(point, s) = command {
+                rows[point.y - chart.position.y].push_str(s);
+            }
+        }
+        rows
+    }
+
+    #[test]
+    fn has_a_constructor() {
+        let chart = Chart::new(Point { x: 1, y: 2 }, Dimensions { width: 10, height: 4 });
+
+        assert_eq!(Point { x: 1, y: 2 }, chart.position);
+        assert_eq!(Dimensions { width: 10, height: 4 }, chart.size);
+    }
+
+    #[test]
+    fn empty_chart_only_draws_axes() {
+        let mut chart = Chart::new(Point { x: 0, y: 0 }, Dimensions { width: 6, height: 3 });
+
+        let rows = render_to_strings(&mut chart);
+        assert_eq!(vec!["1┤    ", "0┤    ", " └────"], rows);
+    }
+
+    #[test]
+    fn bars_are_scaled_to_the_largest_visible_value() {
+        let mut chart = Chart::new(Point { x: 0, y: 0 }, Dimensions { width: 6, height: 3 });
+        chart.add_series("P", Paintbrush::create_default());
+
+        chart.push_sample(&[2]);
+        chart.push_sample(&[4]);
+        chart.push_sample(&[8]);
+
+        let rows = render_to_strings(&mut chart);
+        assert_eq!(vec!["8┤   █", "0┤ ▄██", " └0──2"], rows);
+    }
+
+    #[test]
+    fn old_samples_scroll_off_the_chart() {
+        let mut chart = Chart::new(Point { x: 0, y: 0 }, Dimensions { width: 4, height: 2 });
+        chart.add_series("P", Paintbrush::create_default());
+
+        // Only two columns are available for plotting. The first large value
+        // scrolls off, so the scale should shrink back down.
+        chart.push_sample(&[9]);
+        chart.push_sample(&[1]);
+        chart.push_sample(&[2]);
+
+        let rows = render_to_strings(&mut chart);
+        assert_eq!(vec!["2┤▄█", " └12"], rows);
+    }
+
+    #[test]
+    fn overlays_are_drawn_in_their_own_color() {
+        let mut chart = Chart::new(Point { x: 0, y: 0 }, Dimensions { width: 5, height: 3 });
+        chart.add_series("P", Paintbrush::create_default());
+        chart.add_series("B", Paintbrush { fg: Color::Green, ..Paintbrush::create_default() });
+
+        chart.push_sample(&[4, 4]);
+
+        let mut plotter = mock::MockPlotter::new();
+        chart.render(&mut plotter).unwrap();
+
+        // The overlay should have been drawn on the top row, which is made up
+        // of the label, and the overlay point.
+        let mut saw_overlay = false;
+        let mut current_paintbrush = Paintbrush::create_default();
+        for command in &plotter.command_list {
+            match command {
+                mock::MockPlotterCommand::SetPaintbrush(pb) => current_paintbrush = pb.clone(),
+                mock::MockPlotterCommand::PlotObject(point, s) if s == OVERLAY_CHAR => {
+                    assert_eq!(Point { x: 4, y: 0 }, *point);
+                    assert_eq!(Color::Green, current_paintbrush.fg);
+                    saw_overlay = true;
+                }
+                _ => {}
+            }
+        }
+        assert!(saw_overlay);
+    }
+
+    #[test]
+    fn legend_is_shown_when_there_is_space() {
+        let mut chart = Chart::new(Point { x: 0, y: 0 }, Dimensions { width: 16, height: 2 });
+        chart.add_series("Pop", Paintbrush::create_default());
+
+        let rows = render_to_strings(&mut chart);
+        assert_eq!(" └─── ■ Pop ────", rows[1]);
+    }
+
+    #[test]
+    fn tiny_charts_are_left_blank() {
+        let mut chart = Chart::new(Point { x: 0, y: 0 }, Dimensions { width: 2, height: 1 });
+
+        let rows = render_to_strings(&mut chart);
+        assert_eq!(vec!["  "], rows);
+    }
+
+    #[test]
+    fn is_dirty_when_a_sample_is_pushed() {
+        let mut plotter = mock::MockPlotter::new();
+        let mut chart = Chart::new(Point { x: 0, y: 0 }, Dimensions { width: 6, height: 3 });
+        chart.add_series("A", Paintbrush::create_default());
+        chart.render(&mut plotter).unwrap();
+
+        chart.push_sample(&[1]);
+        let chart: &mut dyn Component<mock::MockPlotter> = &mut chart;
+        assert!(chart.is_dirty());
+        chart.render(&mut plotter).unwrap();
+        assert!(!chart.is_dirty());
+    }
+}
@@ -21,7 +21,9 @@
 // SOFTWARE.
 
 use crate::components::TextLabel;
+use crate::layout::{Constraint, Layout, Rect};
 use crate::lowlevel::*;
+use crate::Component;
 use xy_utils::{Dimensions, Point};
 
 /// Struct that renders a label that holds a name and a numeric value.
@@ -32,7 +34,7 @@ use xy_utils::{Dimensions, Point};
 /// ### Example
 ///
 /// ```
-/// use tui::{mock, Paintbrush, Plotter};
+/// use tui::{mock, Component, Paintbrush, Plotter};
 /// use tui::components::Count;
 /// use xy_utils::{Dimensions, Point};
 /// let mut plotter = mock::MockPlotter::new();
@@ -51,6 +53,9 @@ use xy_utils::{Dimensions, Point};
 /// // Renders label as "Text label: 0   " (extra spaces are padding)
 /// ```
 pub struct Count {
+    position: Point,
+    size: Dimensions,
+    key_width: usize,
     key: TextLabel,
     value_label: TextLabel,
     last_value: usize,
@@ -83,21 +88,20 @@ impl Count {
     ) -> Self {
         let actual_key_width = key_width + 2;
         let key_text = format!("{}: ", key_text);
-        let key = TextLabel::new(
-            paintbrush.clone(),
-            position,
-            Dimensions { width: actual_key_width, ..size },
-            &key_text,
-        );
-
-        let value_label = TextLabel::new(
-            paintbrush,
-            Point { x: position.x + actual_key_width, ..position },
-            Dimensions { width: size.width - actual_key_width, ..size },
-            "0",
-        );
+        let (key_area, value_area) = Self::split_area(Rect::new(position, size), actual_key_width);
+        let key = TextLabel::new(paintbrush.clone(), key_area.position, key_area.size, &key_text);
+        let value_label = TextLabel::new(paintbrush, value_area.position, value_area.size, "0");
 
-        Count { key, value_label, last_value: 0, value: 0, color_code_value: color_code }
+        Count {
+            position,
+            size,
+            key_width: actual_key_width,
+            key,
+            value_label,
+            last_value: 0,
+            value: 0,
+            color_code_value: color_code,
+        }
     }
 
     /// Update the count.
@@ -112,6 +116,11 @@ impl Count {
         self.value_label.update(&self.value.to_string());
     }
 
+    /// Get the current value of the count.
+    pub fn value(&self) -> usize {
+        self.value
+    }
+
     /// Increment the count by 1.
     pub fn increment(&mut self) {
         self.update(self.value + 1);
@@ -122,11 +131,33 @@ impl Count {
         self.update(self.value - 1);
     }
 
-    /// Render the text label using the provided low level UI plotter.
-    pub fn render<PlotterT: Plotter>(
-        &mut self,
-        plotter: &mut PlotterT,
-    ) -> Result<(), std::io::Error> {
+    /// Split the area of the count into the areas of the key and value labels.
+    fn split_area(area: Rect, key_width: usize) -> (Rect, Rect) {
+        let areas =
+            Layout::horizontal(vec![Constraint::Fixed(key_width), Constraint::Fill(1)]).split(area);
+        (areas[0], areas[1])
+    }
+}
+
+impl<PlotterT: Plotter> Component<PlotterT> for Count {
+    fn position(&self) -> Point {
+        self.position
+    }
+
+    fn size(&self) -> Dimensions {
+        self.size
+    }
+
+    fn set_area(&mut self, area: Rect) {
+        self.position = area.position;
+        self.size = area.size;
+        let (key_area, value_area) = Self::split_area(area, self.key_width);
+        Component::<PlotterT>::set_area(&mut self.key, key_area);
+        Component::<PlotterT>::set_area(&mut self.value_label, value_area);
+    }
+
+    /// Render the count using the provided low level UI plotter.
+    fn render(&mut self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
         self.key.render(plotter)?;
 
         if self.color_code_value {
@@ -142,6 +173,16 @@ impl Count {
         self.last_value = self.value;
         self.value_label.render(plotter)
     }
+
+    fn is_dirty(&self) -> bool {
+        Component::<PlotterT>::is_dirty(&self.key)
+            || Component::<PlotterT>::is_dirty(&self.value_label)
+    }
+
+    fn mark_dirty(&mut self) {
+        Component::<PlotterT>::mark_dirty(&mut self.key);
+        Component::<PlotterT>::mark_dirty(&mut self.value_label);
+    }
 }
 
 #[cfg(test)]
@@ -247,6 +288,7 @@ mod count_tests {
         count.update(10);
         count.render(&mut plotter).unwrap();
         count.decrement();
+        assert_eq!(9, count.value());
         count.render(&mut plotter).unwrap();
 
         plotter.flush().unwrap();
@@ -315,4 +357,56 @@ mod count_tests {
             _ => panic!("Incorrect second plotter command"),
         }
     }
+
+    #[test]
+    fn is_dirty_when_either_label_changes() {
+        let mut plotter = mock::MockPlotter::new();
+        let mut count = Count::new(
+            Paintbrush::create_default(),
+            Point { x: 1, y: 2 },
+            Dimensions { width: 10, height: 1 },
+            3,
+            "FOO",
+            false,
+        );
+        count.render(&mut plotter).unwrap();
+
+        count.increment();
+        let count: &mut dyn Component<mock::MockPlotter> = &mut count;
+        assert!(count.is_dirty());
+        count.render(&mut plotter).unwrap();
+        assert!(!count.is_dirty());
+
+        assert_eq!(Point { x: 1, y: 2 }, count.position());
+        assert_eq!(Dimensions { width: 10, height: 1 }, count.size());
+    }
+
+    #[test]
+    fn can_be_moved() {
+        let mut plotter = mock::MockPlotter::new();
+        let mut count = Count::new(
+            Paintbrush::create_default(),
+            Point { x: 1, y: 2 },
+            Dimensions { width: 10, height: 1 },
+            3,
+            "FOO",
+            false,
+        );
+        let count: &mut dyn Component<mock::MockPlotter> = &mut count;
+        count.render(&mut plotter).unwrap();
+        plotter.command_list.clear();
+
+        count.set_area(Rect::new(Point { x: 0, y: 5 }, Dimensions { width: 7, height: 1 }));
+        assert!(count.is_dirty());
+        count.render(&mut plotter).unwrap();
+
+        match &plotter.command_list[..] {
+            [_, mock::MockPlotterCommand::PlotObject(key_point, key), _, mock::MockPlotterCommand::PlotObject(value_point, value)] =>
+            {
+                assert_eq!((Point { x: 0, y: 5 }, "FOO: "), (*key_point, key.as_str()));
+                assert_eq!((Point { x: 5, y: 5 }, "0 "), (*value_point, value.as_str()));
+            }
+            commands => panic!("Unexpected plotter commands {:?}", commands),
+        }
+    }
 }
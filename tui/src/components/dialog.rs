@@ -0,0 +1,402 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::frame::Frame;
+use crate::components::TextLabel;
+use crate::event::{Event, KeyCode, KeyEvent, MouseButton, MouseEventKind};
+use crate::layout::Rect;
+use crate::lowlevel::*;
+use crate::Component;
+use xy_utils::{Dimensions, Point};
+
+/// The widest that `Dialog::preferred_size()` will make a dialog.
+const MAX_PREFERRED_WIDTH: usize = 48;
+
+/// A bordered box with a title, a message, and a row of buttons, one of which
+/// is selected. Usually drawn over the rest of the user interface (see
+/// `tui::render_layers()`).
+///
+/// The selection is moved with the left and right arrow keys or `Tab`.
+/// `Enter` or a click chooses the selected button (see `take_chosen()`). The
+/// message is wrapped at word boundaries to fit inside the border.
+///
+/// ## Example
+///
+/// ```
+/// use tui::components::Dialog;
+/// use tui::event::{KeyCode, KeyEvent};
+/// use tui::Paintbrush;
+/// use xy_utils::{Dimensions, Point};
+///
+/// let mut dialog = Dialog::new(
+///     Paintbrush::create_default(),
+///     Point { x: 0, y: 0 },
+///     Dimensions { width: 30, height: 6 },
+///     "Quit",
+///     "Are you sure?",
+///     &["Yes", "No"],
+/// );
+///
+/// dialog.handle_key(&KeyEvent::new(KeyCode::Right));
+/// dialog.handle_key(&KeyEvent::new(KeyCode::Enter));
+///
+/// assert_eq!(Some(1), dialog.take_chosen());
+/// ```
+#[derive(Clone)]
+pub struct Dialog {
+    paintbrush: Paintbrush,
+    frame: Frame,
+    body: String,
+
+    /// One label for each line of the wrapped message.
+    body_rows: Vec<TextLabel>,
+
+    buttons: Vec<String>,
+    button_labels: Vec<TextLabel>,
+
+    /// Index of the selected button.
+    selected: usize,
+
+    /// The button that has been chosen, and not yet taken.
+    chosen: Option<usize>,
+
+    /// True when the dialog has changed since it was last rendered.
+    dirty: bool,
+}
+
+impl Dialog {
+    pub fn new(
+        paintbrush: Paintbrush,
+        position: Point,
+        size: Dimensions,
+        title: &str,
+        body: &str,
+        buttons: &[&str],
+    ) -> Self {
+        let area = Rect::new(position, size);
+        let mut result = Dialog {
+            frame: Frame::new(paintbrush.clone(), area, title),
+            paintbrush,
+            body: body.to_string(),
+            body_rows: vec![],
+            buttons: buttons.iter().map(|button| format!("[ {} ]", button)).collect(),
+            button_labels: vec![],
+            selected: 0,
+            chosen: None,
+            dirty: true,
+        };
+        result.layout(area);
+        result
+    }
+
+    /// Get a size that fits the title, the buttons and the whole message
+    /// (wrapped if it is long).
+    pub fn preferred_size(&self) -> Dimensions {
+        let body_width = self.body.graphemes(true).count() + 4;
+        let width = body_width
+            .min(MAX_PREFERRED_WIDTH)
+            .max(self.buttons_width() + 4)
+            .max(self.frame.preferred_width());
+        Dimensions { width, height: wrap_words(&self.body, width - 4).len() + 4 }
+    }
+
+    /// Get the index of the selected button.
+    pub fn selected_button(&self) -> usize {
+        self.selected
+    }
+
+    /// Take the index of the button that was chosen (with `Enter` or a
+    /// click), if any.
+    pub fn take_chosen(&mut self) -> Option<usize> {
+        self.chosen.take()
+    }
+
+    /// Move the selection or choose a button with a key press.
+    ///
+    /// ## Arguments
+    ///
+    /// * `key_event`: The key that was pressed.
+    ///
+    /// ## Returns
+    ///
+    /// True if the key was used.
+    pub fn handle_key(&mut self, key_event: &KeyEvent) -> bool {
+        if key_event.ctrl || key_event.alt || self.buttons.is_empty() {
+            return false;
+        }
+
+        let count = self.buttons.len();
+        match key_event.code {
+            KeyCode::Left => self.select((self.selected + count - 1) % count),
+            KeyCode::Right | KeyCode::Tab => self.select((self.selected + 1) % count),
+            KeyCode::Enter => self.chosen = Some(self.selected),
+            _ => return false,
+        }
+        true
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+        for (i, label) in self.button_labels.iter_mut().enumerate() {
+            label.set_paintbrush(if i == index {
+                self.paintbrush.inverted()
+            } else {
+                self.paintbrush.clone()
+            });
+        }
+        self.dirty = true;
+    }
+
+    /// The width of the buttons, with a space between each one.
+    fn buttons_width(&self) -> usize {
+        let widths = self.buttons.iter().map(|button| button.graphemes(true).count());
+        widths.sum::<usize>() + self.buttons.len().saturating_sub(1)
+    }
+
+    /// Move the dialog, wrap the message to fit, and centre the buttons on the
+    /// bottom row inside the border.
+    fn layout(&mut self, area: Rect) {
+        self.frame.set_area(area);
+        let inner = self.frame.inner();
+
+        // The message has a space on each side, and a blank row between it and
+        // the buttons.
+        let body_width = inner.size.width.saturating_sub(2);
+        let body_height = inner.size.height.saturating_sub(2);
+        self.body_rows = wrap_words(&self.body, body_width)
+            .iter()
+            .take(body_height)
+            .enumerate()
+            .map(|(y, line)| {
+                TextLabel::new(
+                    self.paintbrush.clone(),
+                    Point { x: inner.position.x + 1, y: inner.position.y + y },
+                    Dimensions { width: body_width, height: 1 },
+                    line,
+                )
+            })
+            .collect();
+
+        let mut x = inner.position.x + inner.size.width.saturating_sub(self.buttons_width()) / 2;
+        let y = inner.position.y + inner.size.height.saturating_sub(1);
+        let right = inner.position.x + inner.size.width;
+        self.button_labels = vec![];
+        if inner.size.height > 0 {
+            for button in &self.buttons {
+                let width = button.graphemes(true).count().min(right.saturating_sub(x));
+                self.button_labels.push(TextLabel::new(
+                    self.paintbrush.clone(),
+                    Point { x, y },
+                    Dimensions { width, height: usize::from(width > 0) },
+                    button,
+                ));
+                x = (x + width + 1).min(right);
+            }
+        }
+        self.select(self.selected);
+    }
+}
+
+impl<PlotterT: Plotter> Component<PlotterT> for Dialog {
+    fn position(&self) -> Point {
+        self.frame.area().position
+    }
+
+    fn size(&self) -> Dimensions {
+        self.frame.area().size
+    }
+
+    fn set_area(&mut self, area: Rect) {
+        self.layout(area);
+    }
+
+    /// Render the whole dialog.
+    fn render(&mut self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
+        self.frame.render(plotter)?;
+        for label in self.body_rows.iter_mut().chain(self.button_labels.iter_mut()) {
+            label.render(plotter)?;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Move the selection or choose a button with the keyboard or the mouse.
+    fn handle_event(&mut self, event: &Event) -> bool {
+        let Event::Mouse(mouse_event) = event else {
+            return matches!(event, Event::Key(key_event) if self.handle_key(key_event));
+        };
+
+        // Mouse positions are relative to the dialog, labels are not.
+        let origin = self.frame.area().position;
+        let position =
+            Point { x: origin.x + mouse_event.position.x, y: origin.y + mouse_event.position.y };
+        let button = self.button_labels.iter().position(|label| {
+            Rect::new(Component::<PlotterT>::position(label), Component::<PlotterT>::size(label))
+                .contains(position)
+        });
+        match (mouse_event.kind, button) {
+            (MouseEventKind::Down(MouseButton::Left), Some(button)) => self.select(button),
+            (MouseEventKind::Click(MouseButton::Left), Some(button)) => {
+                self.select(button);
+                self.chosen = Some(button);
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Split text into lines no wider than `width`, breaking between words where
+/// possible. Words that are wider than a line are split.
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    if width == 0 {
+        return lines;
+    }
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut line_width = 0;
+        for word in paragraph.split_whitespace() {
+            let mut graphemes: Vec<&str> = word.graphemes(true).collect();
+            let separator = usize::from(line_width > 0);
+            if line_width + separator + graphemes.len() <= width {
+                if separator > 0 {
+                    line.push(' ');
+                }
+                line.extend(graphemes);
+                line_width += separator + word.graphemes(true).count();
+                continue;
+            }
+
+            if line_width > 0 {
+                lines.push(std::mem::take(&mut line));
+            }
+            while graphemes.len() > width {
+                lines.push(graphemes.drain(..width).collect());
+            }
+            line_width = graphemes.len();
+            line = graphemes.concat();
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod dialog_tests {
+    use super::*;
+    use crate::event::MouseEvent;
+    use crate::mock::ScreenPlotter;
+
+    fn create_dialog(size: Dimensions) -> Dialog {
+        Dialog::new(
+            Paintbrush::create_default(),
+            Point { x: 0, y: 0 },
+            size,
+            "Reset",
+            "Clear every cell on the board?",
+            &["OK", "Cancel"],
+        )
+    }
+
+    fn render(dialog: &mut Dialog) -> ScreenPlotter {
+        let mut plotter = ScreenPlotter::new(Component::<ScreenPlotter>::size(dialog));
+        dialog.render(&mut plotter).unwrap();
+        plotter
+    }
+
+    #[test]
+    fn wraps_the_message_and_highlights_the_selected_button() {
+        let mut dialog = create_dialog(Dimensions { width: 22, height: 7 });
+
+        render(&mut dialog).assert_styled_screen(
+            "
+            |┌─ Reset ────────────┐
+            |│ Clear every cell   │
+            |│ on the board?      │
+            |│                    │
+            |│                    │
+            |│ {Black on White}[ OK ]{} [ Cancel ]  │
+            |└────────────────────┘
+            ",
+        );
+
+        dialog.handle_key(&KeyEvent::new(KeyCode::Tab));
+        render(&mut dialog).assert_styled_screen(
+            "
+            |┌─ Reset ────────────┐
+            |│ Clear every cell   │
+            |│ on the board?      │
+            |│                    │
+            |│                    │
+            |│ [ OK ] {Black on White}[ Cancel ]{}  │
+            |└────────────────────┘
+            ",
+        );
+    }
+
+    #[test]
+    fn buttons_are_chosen_with_enter_or_a_click() {
+        let mut dialog = create_dialog(Dimensions { width: 22, height: 7 });
+        dialog.handle_key(&KeyEvent::new(KeyCode::Left));
+        assert_eq!(1, dialog.selected_button());
+        dialog.handle_key(&KeyEvent::new(KeyCode::Enter));
+        assert_eq!(Some(1), dialog.take_chosen());
+        assert_eq!(None, dialog.take_chosen());
+
+        let click = |x| {
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Click(MouseButton::Left),
+                position: Point { x, y: 5 },
+            })
+        };
+        let component: &mut dyn Component<ScreenPlotter> = &mut dialog;
+        assert!(!component.handle_event(&click(1)));
+        assert!(component.handle_event(&click(3)));
+        assert_eq!(Some(0), dialog.take_chosen());
+    }
+
+    #[test]
+    fn is_big_enough_for_its_message_and_buttons() {
+        let dialog = create_dialog(Dimensions { width: 10, height: 4 });
+
+        assert_eq!(Dimensions { width: 34, height: 5 }, dialog.preferred_size());
+    }
+
+    #[test]
+    fn wraps_words_to_fit_lines() {
+        assert_eq!(vec!["a bb", "ccc", "dddd", "dd", "e"], wrap_words("a bb ccc dddddd\ne", 4));
+        assert!(wrap_words("", 4).is_empty());
+        assert!(wrap_words("text", 0).is_empty());
+    }
+}
@@ -0,0 +1,550 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::frame::Frame;
+use crate::components::{Canvas, Menu};
+use crate::event::{Event, KeyCode, KeyEvent};
+use crate::layout::{Constraint, Layout, Rect};
+use crate::lowlevel::*;
+use crate::Component;
+use xy_utils::{Dimensions, Point};
+
+/// The width of the preview, including its border.
+const PREVIEW_WIDTH: usize = 26;
+
+/// The smallest height that `FileBrowser::preferred_size()` asks for, so
+/// that the preview isn't too small.
+const MIN_HEIGHT: usize = 12;
+
+/// The narrowest that the list of files can be before the preview is hidden.
+const MIN_LIST_WIDTH: usize = 20;
+
+/// A file or directory listed by a `FileBrowser`.
+#[derive(Clone)]
+struct Entry {
+    path: PathBuf,
+    is_directory: bool,
+}
+
+/// A scrolling list of the directories and files in a directory, next to a
+/// thumbnail preview of the selected file. Usually drawn over the rest of the
+/// user interface (see `tui::render_layers()`).
+///
+/// Files are selected as in a `Menu`. `Enter` (or a click) on a directory
+/// opens it, `Backspace` opens the parent directory, and `Enter` on a file
+/// chooses it (see `take_chosen()`). Hidden files, and files without one of
+/// the given extensions, are not listed.
+///
+/// The browser does not know how to read files. Instead, the application
+/// gives it a preview of the selected file (see `set_preview()`), which is
+/// drawn scaled down to fit, two pixels to each character.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::path::Path;
+/// use tui::components::FileBrowser;
+/// use tui::event::{KeyCode, KeyEvent};
+/// use tui::Paintbrush;
+/// use xy_utils::{Dimensions, Point};
+///
+/// let mut browser = FileBrowser::new(
+///     Paintbrush::create_default(),
+///     Point { x: 0, y: 0 },
+///     Dimensions { width: 60, height: 20 },
+///     Path::new("patterns"),
+///     &["rle", "cells"],
+/// )
+/// .unwrap();
+///
+/// browser.handle_key(&KeyEvent::new(KeyCode::Down)).unwrap();
+/// if browser.selected_file().is_some() {
+///     // Read the file, and show its live cells.
+///     browser.set_preview(Dimensions { width: 3, height: 1 }, &[Point { x: 1, y: 0 }]);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct FileBrowser {
+    paintbrush: Paintbrush,
+    area: Rect,
+    directory: PathBuf,
+    extensions: Vec<String>,
+
+    /// The entries in the directory, in the same order as the menu items.
+    entries: Vec<Entry>,
+    menu: Menu,
+
+    preview_frame: Frame,
+    preview_canvas: Canvas,
+
+    /// The size of the previewed pattern, and its live cells.
+    preview: Option<(Dimensions, Vec<Point>)>,
+
+    /// The file that has been chosen, and not yet taken.
+    chosen: Option<PathBuf>,
+}
+
+impl FileBrowser {
+    /// Create a file browser that lists a directory.
+    ///
+    /// ## Arguments
+    ///
+    /// * `paintbrush`: The paintbrush to draw the browser with.
+    /// * `position`: The top-left corner of the browser.
+    /// * `size`: The size of the browser.
+    /// * `directory`: The directory to list.
+    /// * `extensions`: The extensions of the files to list (e.g. `"rle"`),
+    ///   which are matched ignoring case. Every file is listed if this is
+    ///   empty.
+    ///
+    /// ## Returns
+    ///
+    /// The file browser, or an error if the directory could not be read.
+    pub fn new(
+        paintbrush: Paintbrush,
+        position: Point,
+        size: Dimensions,
+        directory: &Path,
+        extensions: &[&str],
+    ) -> Result<Self, std::io::Error> {
+        let area = Rect::new(position, size);
+        let mut result = FileBrowser {
+            menu: Menu::new(paintbrush.clone(), position, size, "", &[]),
+            preview_frame: Frame::new(paintbrush.clone(), area, "Preview"),
+            preview_canvas: Canvas::new(position, Dimensions::create_empty()),
+            paintbrush,
+            area,
+            directory: PathBuf::new(),
+            extensions: extensions.iter().map(|extension| extension.to_lowercase()).collect(),
+            entries: vec![],
+            preview: None,
+            chosen: None,
+        };
+        result.open_directory(directory)?;
+        result.layout(area);
+        Ok(result)
+    }
+
+    /// Get a size that fits the list of files and the preview.
+    pub fn preferred_size(&self) -> Dimensions {
+        let list_size = self.menu.preferred_size();
+        Dimensions {
+            width: list_size.width.max(PREVIEW_WIDTH) + PREVIEW_WIDTH,
+            height: list_size.height.max(MIN_HEIGHT),
+        }
+    }
+
+    /// Get the directory being listed.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// List another directory.
+    ///
+    /// ## Arguments
+    ///
+    /// * `directory`: The directory to list.
+    ///
+    /// ## Returns
+    ///
+    /// An error if the directory could not be read, in which case the
+    /// browser still lists the previous directory.
+    pub fn open_directory(&mut self, directory: &Path) -> Result<(), std::io::Error> {
+        let directory = fs::canonicalize(directory)?;
+        let mut entries = vec![];
+        for dir_entry in fs::read_dir(&directory)? {
+            let dir_entry = dir_entry?;
+            let path = dir_entry.path();
+            let is_directory = path.is_dir();
+            let is_hidden = dir_entry.file_name().to_string_lossy().starts_with('.');
+            if !is_hidden && (is_directory || self.is_listed_file(&path)) {
+                entries.push(Entry { path, is_directory });
+            }
+        }
+
+        // Directories come first, and the parent directory before them.
+        entries.sort_by(|a, b| b.is_directory.cmp(&a.is_directory).then(a.path.cmp(&b.path)));
+        if let Some(parent) = directory.parent() {
+            entries.insert(0, Entry { path: parent.to_path_buf(), is_directory: true });
+        }
+
+        let has_parent = directory.parent().is_some();
+        let items: Vec<String> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| match (i, entry.path.file_name()) {
+                (0, _) if has_parent => "../".to_string(),
+                (_, Some(name)) if entry.is_directory => format!("{}/", name.to_string_lossy()),
+                (_, Some(name)) => name.to_string_lossy().to_string(),
+                (_, None) => entry.path.to_string_lossy().to_string(),
+            })
+            .collect();
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+
+        self.menu.set_items(&items);
+        self.menu.set_title(&directory.to_string_lossy());
+        self.directory = directory;
+        self.entries = entries;
+        self.clear_preview();
+        Ok(())
+    }
+
+    /// Get the selected file, or `None` if a directory is selected.
+    pub fn selected_file(&self) -> Option<&Path> {
+        self.entries
+            .get(self.menu.selected())
+            .filter(|entry| !entry.is_directory)
+            .map(|entry| entry.path.as_path())
+    }
+
+    /// Show a preview of the selected file. The preview is cleared when the
+    /// selection changes.
+    ///
+    /// ## Arguments
+    ///
+    /// * `size`: The size of the pattern in the file.
+    /// * `live_cells`: The cells of the pattern to draw, relative to its
+    ///   top-left corner.
+    pub fn set_preview(&mut self, size: Dimensions, live_cells: &[Point]) {
+        self.preview = Some((size, live_cells.to_vec()));
+        self.draw_preview();
+    }
+
+    pub fn clear_preview(&mut self) {
+        self.preview = None;
+        self.draw_preview();
+    }
+
+    /// Take the file that was chosen (with `Enter` or a click), if any.
+    pub fn take_chosen(&mut self) -> Option<PathBuf> {
+        self.chosen.take()
+    }
+
+    /// Move the selection, open a directory or choose a file with a key
+    /// press.
+    ///
+    /// ## Arguments
+    ///
+    /// * `key_event`: The key that was pressed.
+    ///
+    /// ## Returns
+    ///
+    /// True if the key was used, or an error if a directory could not be
+    /// read.
+    pub fn handle_key(&mut self, key_event: &KeyEvent) -> Result<bool, std::io::Error> {
+        if key_event.code == KeyCode::Backspace && !key_event.ctrl && !key_event.alt {
+            if let Some(parent) = self.directory.parent().map(Path::to_path_buf) {
+                self.open_directory(&parent)?;
+            }
+            return Ok(true);
+        }
+
+        let selected = self.menu.selected();
+        if !self.menu.handle_key(key_event) {
+            return Ok(false);
+        }
+        self.menu_changed(selected)?;
+        Ok(true)
+    }
+
+    /// Open the chosen directory, or take note of the chosen file, once the
+    /// menu has handled an event.
+    ///
+    /// ## Arguments
+    ///
+    /// * `previous_selection`: The item that was selected before the event.
+    fn menu_changed(&mut self, previous_selection: usize) -> Result<(), std::io::Error> {
+        if let Some(index) = self.menu.take_chosen() {
+            let entry = self.entries[index].clone();
+            if entry.is_directory {
+                return self.open_directory(&entry.path);
+            }
+            self.chosen = Some(entry.path);
+        }
+
+        if self.menu.selected() != previous_selection {
+            self.clear_preview();
+        }
+        Ok(())
+    }
+
+    fn is_listed_file(&self, path: &Path) -> bool {
+        let extension =
+            path.extension().map(|extension| extension.to_string_lossy().to_lowercase());
+        self.extensions.is_empty()
+            || extension.is_some_and(|extension| self.extensions.contains(&extension))
+    }
+
+    /// Draw the preview (if any) in the middle of the preview canvas, scaled
+    /// down to fit if it is too big. Each character is two pixels high.
+    fn draw_preview(&mut self) {
+        let canvas_size = self.preview_canvas.size;
+        self.preview_canvas = Canvas::new(self.preview_canvas.position, canvas_size);
+        let Some((size, live_cells)) = &self.preview else {
+            return;
+        };
+        if canvas_size.width == 0 || canvas_size.height == 0 {
+            return;
+        }
+
+        let pixels = Dimensions { width: canvas_size.width, height: canvas_size.height * 2 };
+        let scale =
+            size.width.div_ceil(pixels.width).max(size.height.div_ceil(pixels.height)).max(1);
+        let offset = Point {
+            x: (pixels.width - size.width.div_ceil(scale).min(pixels.width)) / 2,
+            y: (pixels.height - size.height.div_ceil(scale).min(pixels.height)) / 2,
+        };
+        let mut is_alive = vec![vec![false; pixels.width]; pixels.height];
+        for cell in live_cells {
+            let (x, y) = (offset.x + cell.x / scale, offset.y + cell.y / scale);
+            if x < pixels.width && y < pixels.height {
+                is_alive[y][x] = true;
+            }
+        }
+
+        for y in 0..canvas_size.height {
+            let row: String = (0..canvas_size.width)
+                .map(|x| match (is_alive[y * 2][x], is_alive[y * 2 + 1][x]) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect();
+
+            // The row always fits, so drawing it can't fail.
+            let _ = self.preview_canvas.draw_str(self.paintbrush.clone(), Point { x: 0, y }, &row);
+        }
+    }
+
+    /// Move the browser. The preview takes up the right hand side, unless the
+    /// browser is too narrow for it.
+    fn layout(&mut self, area: Rect) {
+        self.area = area;
+        let preview_width = match area.size.width >= MIN_LIST_WIDTH + PREVIEW_WIDTH {
+            true => PREVIEW_WIDTH,
+            false => 0,
+        };
+        let columns =
+            Layout::horizontal(vec![Constraint::Fill(1), Constraint::Fixed(preview_width)])
+                .split(area);
+
+        self.menu.layout(columns[0]);
+        self.preview_frame.set_area(columns[1]);
+        self.preview_canvas.position = self.preview_frame.inner().position;
+        self.preview_canvas.size = self.preview_frame.inner().size;
+        self.draw_preview();
+    }
+}
+
+impl<PlotterT: Plotter> Component<PlotterT> for FileBrowser {
+    fn position(&self) -> Point {
+        self.area.position
+    }
+
+    fn size(&self) -> Dimensions {
+        self.area.size
+    }
+
+    fn set_area(&mut self, area: Rect) {
+        self.layout(area);
+    }
+
+    /// Render the whole browser.
+    fn render(&mut self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
+        Component::<PlotterT>::render(&mut self.menu, plotter)?;
+        self.preview_frame.render(plotter)?;
+        Component::<PlotterT>::mark_dirty(&mut self.preview_canvas);
+        self.preview_canvas.render(plotter)
+    }
+
+    fn is_dirty(&self) -> bool {
+        Component::<PlotterT>::is_dirty(&self.menu)
+            || Component::<PlotterT>::is_dirty(&self.preview_canvas)
+    }
+
+    fn mark_dirty(&mut self) {
+        Component::<PlotterT>::mark_dirty(&mut self.menu);
+    }
+
+    /// Move the selection, open a directory or choose a file with the
+    /// keyboard or the mouse. Directories that can't be read are ignored.
+    fn handle_event(&mut self, event: &Event) -> bool {
+        let Event::Mouse(mouse_event) = event else {
+            return matches!(event, Event::Key(key_event) if self.handle_key(key_event).unwrap_or(true));
+        };
+
+        // Mouse positions are relative to the browser, and the list is in its
+        // top-left corner.
+        let menu_size = Component::<PlotterT>::size(&self.menu);
+        if mouse_event.position.x >= menu_size.width {
+            return false;
+        }
+        let selected = self.menu.selected();
+        if !Component::<PlotterT>::handle_event(&mut self.menu, event) {
+            return false;
+        }
+        let _ = self.menu_changed(selected);
+        true
+    }
+}
+
+#[cfg(test)]
+mod file_browser_tests {
+    use super::*;
+    use crate::mock::ScreenPlotter;
+
+    /// A directory of files for a test, which is deleted when the test ends.
+    struct TestDirectory(PathBuf);
+
+    impl TestDirectory {
+        fn new(name: &str, files: &[&str]) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "tui_file_browser_{}_{}",
+                name,
+                std::process::id()
+            ));
+            fs::create_dir_all(path.join("more")).unwrap();
+            for file in files {
+                fs::write(path.join(file), "").unwrap();
+            }
+            TestDirectory(path)
+        }
+    }
+
+    impl Drop for TestDirectory {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn create_browser(directory: &TestDirectory, size: Dimensions) -> FileBrowser {
+        FileBrowser::new(
+            Paintbrush::create_default(),
+            Point { x: 0, y: 0 },
+            size,
+            &directory.0,
+            &["rle"],
+        )
+        .unwrap()
+    }
+
+    fn press(browser: &mut FileBrowser, code: KeyCode) {
+        browser.handle_key(&KeyEvent::new(code)).unwrap();
+    }
+
+    fn render(browser: &mut FileBrowser) -> ScreenPlotter {
+        let mut plotter = ScreenPlotter::new(Component::<ScreenPlotter>::size(browser));
+        browser.render(&mut plotter).unwrap();
+        plotter
+    }
+
+    #[test]
+    fn lists_directories_then_matching_files() {
+        let directory =
+            TestDirectory::new("lists", &["b.rle", "a.RLE", "notes.txt", ".hidden.rle"]);
+        let mut browser = create_browser(&directory, Dimensions { width: 20, height: 7 });
+        browser.menu.set_title("test");
+
+        render(&mut browser).assert_styled_screen(
+            "
+            |┌─ test ───────────┐
+            |│{Black on White} ../              {}│
+            |│ more/            │
+            |│ a.RLE            │
+            |│ b.rle            │
+            |│                  │
+            |└──────────────────┘
+            ",
+        );
+    }
+
+    #[test]
+    fn enter_opens_directories_and_chooses_files() {
+        let directory = TestDirectory::new("enter", &["a.rle"]);
+        let mut browser = create_browser(&directory, Dimensions { width: 20, height: 7 });
+        let path = browser.directory().to_path_buf();
+        assert_eq!(None, browser.selected_file());
+
+        press(&mut browser, KeyCode::Down);
+        press(&mut browser, KeyCode::Enter);
+        assert_eq!(path.join("more"), browser.directory());
+        assert_eq!(None, browser.take_chosen());
+
+        press(&mut browser, KeyCode::Backspace);
+        assert_eq!(path, browser.directory());
+        press(&mut browser, KeyCode::End);
+        assert_eq!(Some(path.join("a.rle").as_path()), browser.selected_file());
+        press(&mut browser, KeyCode::Enter);
+        assert_eq!(Some(path.join("a.rle")), browser.take_chosen());
+    }
+
+    #[test]
+    fn draws_the_preview_until_the_selection_changes() {
+        let directory = TestDirectory::new("preview", &["a.rle", "b.rle"]);
+        let mut browser = create_browser(&directory, Dimensions { width: 46, height: 6 });
+        browser.menu.set_title("test");
+        press(&mut browser, KeyCode::End);
+
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)].map(|(x, y)| Point { x, y });
+        browser.set_preview(Dimensions { width: 3, height: 3 }, &glider);
+        render(&mut browser).assert_screen(
+            "
+            |┌─ test ───────────┐┌─ Preview ──────────────┐
+            |│ ../              ││                        │
+            |│ more/            ││           ▀▄           │
+            |│ a.rle            ││          ▀▀▀           │
+            |│ b.rle            ││                        │
+            |└──────────────────┘└────────────────────────┘
+            ",
+        );
+
+        press(&mut browser, KeyCode::Up);
+        render(&mut browser).assert_screen(
+            "
+            |┌─ test ───────────┐┌─ Preview ──────────────┐
+            |│ ../              ││                        │
+            |│ more/            ││                        │
+            |│ a.rle            ││                        │
+            |│ b.rle            ││                        │
+            |└──────────────────┘└────────────────────────┘
+            ",
+        );
+    }
+
+    #[test]
+    fn scales_big_previews_down_to_fit() {
+        let directory = TestDirectory::new("scales", &["a.rle"]);
+        let mut browser = create_browser(&directory, Dimensions { width: 46, height: 4 });
+
+        // A 96x16 block fills the 24x4 character preview at a quarter scale.
+        let cells: Vec<Point> =
+            (0..16).flat_map(|y| (0..96).map(move |x| Point { x, y })).collect();
+        browser.set_preview(Dimensions { width: 96, height: 16 }, &cells);
+        let screen = render(&mut browser);
+
+        assert!(screen.text().lines().nth(1).unwrap().ends_with(&format!("│{}│", "█".repeat(24))));
+    }
+}
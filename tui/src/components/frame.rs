@@ -0,0 +1,109 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::components::{Border, TextLabel};
+use crate::layout::{Padding, Rect};
+use crate::lowlevel::*;
+use crate::Component;
+use xy_utils::{Dimensions, Point};
+
+/// A border with a title, and a blank interior. Used by the overlay
+/// components, so that they hide whatever they are drawn over.
+#[derive(Clone)]
+pub(crate) struct Frame {
+    paintbrush: Paintbrush,
+    area: Rect,
+    border: Border,
+    title: String,
+
+    /// The title, drawn over the top edge of the border.
+    title_label: TextLabel,
+}
+
+impl Frame {
+    pub fn new(paintbrush: Paintbrush, area: Rect, title: &str) -> Self {
+        let border = Border::new(paintbrush.clone(), area.position, area.size);
+        let title_label = TextLabel::new(paintbrush.clone(), area.position, area.size, "");
+        let mut result = Frame { paintbrush, area, border, title: title.to_string(), title_label };
+        result.set_area(area);
+        result
+    }
+
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    /// Get the area inside the border.
+    pub fn inner(&self) -> Rect {
+        self.area.inner(Padding::uniform(1))
+    }
+
+    /// Get the width that the frame needs to show its title in full.
+    pub fn preferred_width(&self) -> usize {
+        self.title.graphemes(true).count() + 6
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+        self.set_area(self.area);
+    }
+
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+        self.border.position = area.position;
+        self.border.size = area.size;
+
+        // The title is padded with a space on each side, and starts after the
+        // corner of the border and one line.
+        let text = format!(" {} ", self.title);
+        let width = match self.title.is_empty() {
+            true => 0,
+            false => text.graphemes(true).count().min(area.size.width.saturating_sub(4)),
+        };
+        self.title_label = TextLabel::new(
+            self.paintbrush.clone(),
+            Point { x: area.position.x + 2, y: area.position.y },
+            Dimensions { width, height: usize::from(width > 0) },
+            &text,
+        );
+    }
+
+    /// Draw the border and title, and blank the interior.
+    pub fn render<PlotterT: Plotter>(
+        &mut self,
+        plotter: &mut PlotterT,
+    ) -> Result<(), std::io::Error> {
+        if self.area.size.width < 2 || self.area.size.height < 2 {
+            return Ok(());
+        }
+
+        self.border.render(plotter)?;
+        let inner = self.inner();
+        let blank_row = " ".repeat(inner.size.width);
+        for y in inner.position.y..inner.position.y + inner.size.height {
+            plotter.plot(Point { x: inner.position.x, y }, &blank_row)?;
+        }
+        self.title_label.render(plotter)
+    }
+}
@@ -0,0 +1,278 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::frame::Frame;
+use crate::components::TextLabel;
+use crate::event::{Event, KeyCode, KeyEvent, MouseEventKind};
+use crate::layout::Rect;
+use crate::lowlevel::*;
+use crate::Component;
+use xy_utils::{Dimensions, Point};
+
+/// A bordered box that lists keys (or commands) next to a description of what
+/// they do. Usually drawn over the rest of the user interface (see
+/// `tui::render_layers()`).
+///
+/// Lists that are too long for the box are scrolled with the arrow keys,
+/// `PgUp`, `PgDn`, `Home` and `End`, or the mouse wheel.
+///
+/// ## Example
+///
+/// ```
+/// use tui::components::Help;
+/// use tui::Paintbrush;
+/// use xy_utils::{Dimensions, Point};
+///
+/// let help = Help::new(
+///     Paintbrush::create_default(),
+///     Point { x: 0, y: 0 },
+///     Dimensions { width: 20, height: 4 },
+///     "Keys",
+///     vec![("q".to_string(), "Quit".to_string()), ("?".to_string(), "Help".to_string())],
+/// );
+///
+/// assert_eq!(Dimensions { width: 11, height: 4 }, help.preferred_size());
+/// ```
+#[derive(Clone)]
+pub struct Help {
+    paintbrush: Paintbrush,
+    frame: Frame,
+
+    /// The formatted entries, one per line.
+    lines: Vec<String>,
+
+    /// One label for each visible line.
+    rows: Vec<TextLabel>,
+
+    /// Index of the first visible line.
+    scroll: usize,
+
+    /// True when the help has changed since it was last rendered.
+    dirty: bool,
+}
+
+impl Help {
+    pub fn new(
+        paintbrush: Paintbrush,
+        position: Point,
+        size: Dimensions,
+        title: &str,
+        entries: Vec<(String, String)>,
+    ) -> Self {
+        let key_width = entries.iter().map(|(key, _)| key.graphemes(true).count()).max();
+        let key_width = key_width.unwrap_or(0);
+        let lines = entries
+            .iter()
+            .map(|(key, description)| {
+                let padding = " ".repeat(key_width - key.graphemes(true).count());
+                format!(" {}{}  {} ", key, padding, description)
+            })
+            .collect();
+
+        let area = Rect::new(position, size);
+        let mut result = Help {
+            frame: Frame::new(paintbrush.clone(), area, title),
+            paintbrush,
+            lines,
+            rows: vec![],
+            scroll: 0,
+            dirty: true,
+        };
+        result.layout(area);
+        result
+    }
+
+    /// Get the size that the help needs to show every entry in full.
+    pub fn preferred_size(&self) -> Dimensions {
+        let widest_line = self.lines.iter().map(|line| line.graphemes(true).count()).max();
+        Dimensions {
+            width: (widest_line.unwrap_or(0) + 2).max(self.frame.preferred_width()),
+            height: self.lines.len() + 2,
+        }
+    }
+
+    /// Scroll the entries with a key press.
+    ///
+    /// ## Arguments
+    ///
+    /// * `key_event`: The key that was pressed.
+    ///
+    /// ## Returns
+    ///
+    /// True if the key was used.
+    pub fn handle_key(&mut self, key_event: &KeyEvent) -> bool {
+        if key_event.ctrl || key_event.alt {
+            return false;
+        }
+
+        let page = self.rows.len().max(1);
+        match key_event.code {
+            KeyCode::Up => self.scroll_to(self.scroll.saturating_sub(1)),
+            KeyCode::Down => self.scroll_to(self.scroll + 1),
+            KeyCode::PageUp => self.scroll_to(self.scroll.saturating_sub(page)),
+            KeyCode::PageDown => self.scroll_to(self.scroll + page),
+            KeyCode::Home => self.scroll_to(0),
+            KeyCode::End => self.scroll_to(usize::MAX),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Scroll so that a line is at the top, or as close to the top as it can
+    /// be without leaving blank rows at the bottom.
+    fn scroll_to(&mut self, line: usize) {
+        self.scroll = line.min(self.lines.len().saturating_sub(self.rows.len()));
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            row.update(self.lines.get(self.scroll + i).map_or("", |line| line.as_str()));
+        }
+        self.dirty = true;
+    }
+
+    /// Move the help, and create a label for each row inside the border.
+    fn layout(&mut self, area: Rect) {
+        self.frame.set_area(area);
+        let inner = self.frame.inner();
+        self.rows = (0..inner.size.height)
+            .map(|y| {
+                TextLabel::new(
+                    self.paintbrush.clone(),
+                    Point { x: inner.position.x, y: inner.position.y + y },
+                    Dimensions { width: inner.size.width, height: 1 },
+                    "",
+                )
+            })
+            .collect();
+        self.scroll_to(self.scroll);
+    }
+}
+
+impl<PlotterT: Plotter> Component<PlotterT> for Help {
+    fn position(&self) -> Point {
+        self.frame.area().position
+    }
+
+    fn size(&self) -> Dimensions {
+        self.frame.area().size
+    }
+
+    fn set_area(&mut self, area: Rect) {
+        self.layout(area);
+    }
+
+    /// Render the whole help.
+    fn render(&mut self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
+        self.frame.render(plotter)?;
+        for row in &mut self.rows {
+            row.render(plotter)?;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Scroll the entries with the keyboard or the mouse wheel.
+    fn handle_event(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Key(key_event) => self.handle_key(key_event),
+            Event::Mouse(mouse_event) => match mouse_event.kind {
+                MouseEventKind::ScrollUp => self.handle_key(&KeyEvent::new(KeyCode::Up)),
+                MouseEventKind::ScrollDown => self.handle_key(&KeyEvent::new(KeyCode::Down)),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod help_tests {
+    use super::*;
+    use crate::mock::ScreenPlotter;
+
+    fn create_help(height: usize) -> Help {
+        let entries = [("Ctrl+C", "Quit"), ("?", "Show help"), (":", "Command line")];
+        Help::new(
+            Paintbrush::create_default(),
+            Point { x: 0, y: 0 },
+            Dimensions { width: 24, height },
+            "Keys",
+            entries.iter().map(|(key, text)| (key.to_string(), text.to_string())).collect(),
+        )
+    }
+
+    fn render(help: &mut Help) -> ScreenPlotter {
+        let mut plotter = ScreenPlotter::new(Component::<ScreenPlotter>::size(help));
+        help.render(&mut plotter).unwrap();
+        plotter
+    }
+
+    #[test]
+    fn lines_up_the_descriptions() {
+        let mut help = create_help(5);
+
+        assert_eq!(Dimensions { width: 24, height: 5 }, help.preferred_size());
+        render(&mut help).assert_screen(
+            "
+            |┌─ Keys ───────────────┐
+            |│ Ctrl+C  Quit         │
+            |│ ?       Show help    │
+            |│ :       Command line │
+            |└──────────────────────┘
+            ",
+        );
+    }
+
+    #[test]
+    fn scrolls_long_lists() {
+        let mut help = create_help(4);
+        assert!(help.handle_key(&KeyEvent::new(KeyCode::PageDown)));
+
+        render(&mut help).assert_screen(
+            "
+            |┌─ Keys ───────────────┐
+            |│ ?       Show help    │
+            |│ :       Command line │
+            |└──────────────────────┘
+            ",
+        );
+
+        assert!(help.handle_key(&KeyEvent::new(KeyCode::Home)));
+        assert!(!help.handle_key(&KeyEvent::new(KeyCode::Char('x'))));
+        render(&mut help).assert_screen(
+            "
+            |┌─ Keys ───────────────┐
+            |│ Ctrl+C  Quit         │
+            |│ ?       Show help    │
+            |└──────────────────────┘
+            ",
+        );
+    }
+}
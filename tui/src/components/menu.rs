@@ -0,0 +1,368 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::frame::Frame;
+use crate::components::TextLabel;
+use crate::event::{Event, KeyCode, KeyEvent, MouseButton, MouseEventKind};
+use crate::layout::Rect;
+use crate::lowlevel::*;
+use crate::Component;
+use xy_utils::{Dimensions, Point};
+
+/// A list of items in a bordered box, one of which is selected. Usually drawn
+/// over the rest of the user interface (see `tui::render_layers()`).
+///
+/// The selection is moved with the arrow keys, `PgUp`, `PgDn`, `Home` and
+/// `End`, the mouse wheel, or by clicking an item. `Enter` or a click chooses
+/// the selected item (see `take_chosen()`). Lists that are too long for the
+/// menu scroll to keep the selected item visible.
+///
+/// ## Example
+///
+/// ```
+/// use tui::components::Menu;
+/// use tui::event::{KeyCode, KeyEvent};
+/// use tui::Paintbrush;
+/// use xy_utils::{Dimensions, Point};
+///
+/// let mut menu = Menu::new(
+///     Paintbrush::create_default(),
+///     Point { x: 0, y: 0 },
+///     Dimensions { width: 12, height: 5 },
+///     "Speed",
+///     &["Slow", "Medium", "Fast"],
+/// );
+///
+/// menu.handle_key(&KeyEvent::new(KeyCode::Down));
+/// menu.handle_key(&KeyEvent::new(KeyCode::Enter));
+///
+/// assert_eq!(Some(1), menu.take_chosen());
+/// ```
+#[derive(Clone)]
+pub struct Menu {
+    paintbrush: Paintbrush,
+    frame: Frame,
+    items: Vec<String>,
+
+    /// One label for each visible row of items.
+    rows: Vec<TextLabel>,
+
+    /// Index of the selected item.
+    selected: usize,
+
+    /// Index of the first visible item.
+    scroll: usize,
+
+    /// The item that has been chosen, and not yet taken.
+    chosen: Option<usize>,
+
+    /// True when the menu has changed since it was last rendered.
+    dirty: bool,
+}
+
+impl Menu {
+    pub fn new(
+        paintbrush: Paintbrush,
+        position: Point,
+        size: Dimensions,
+        title: &str,
+        items: &[&str],
+    ) -> Self {
+        let area = Rect::new(position, size);
+        let mut result = Menu {
+            frame: Frame::new(paintbrush.clone(), area, title),
+            paintbrush,
+            items: items.iter().map(|item| item.to_string()).collect(),
+            rows: vec![],
+            selected: 0,
+            scroll: 0,
+            chosen: None,
+            dirty: true,
+        };
+        result.layout(area);
+        result
+    }
+
+    /// Get the size that the menu needs to show every item in full.
+    pub fn preferred_size(&self) -> Dimensions {
+        let widest_item = self.items.iter().map(|item| item.graphemes(true).count()).max();
+        Dimensions {
+            width: (widest_item.unwrap_or(0) + 4).max(self.frame.preferred_width()),
+            height: self.items.len() + 2,
+        }
+    }
+
+    /// Replace the items in the menu, and select the first one.
+    ///
+    /// ## Arguments
+    ///
+    /// * `items`: The new items.
+    pub fn set_items(&mut self, items: &[&str]) {
+        self.items = items.iter().map(|item| item.to_string()).collect();
+        self.scroll = 0;
+        self.select(0);
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.frame.set_title(title);
+        self.dirty = true;
+    }
+
+    /// Get the index of the selected item.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Select an item, scrolling the menu if needed.
+    ///
+    /// ## Arguments
+    ///
+    /// * `index`: The index of the item. Indexes beyond the end of the list
+    ///   select the last item.
+    pub fn select(&mut self, index: usize) {
+        self.selected = index.min(self.items.len().saturating_sub(1));
+        let visible_rows = self.rows.len().max(1);
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + visible_rows {
+            self.scroll = self.selected + 1 - visible_rows;
+        }
+        self.update_rows();
+    }
+
+    /// Take the index of the item that was chosen (with `Enter` or a click),
+    /// if any.
+    pub fn take_chosen(&mut self) -> Option<usize> {
+        self.chosen.take()
+    }
+
+    /// Move the selection or choose an item with a key press.
+    ///
+    /// ## Arguments
+    ///
+    /// * `key_event`: The key that was pressed.
+    ///
+    /// ## Returns
+    ///
+    /// True if the key was used.
+    pub fn handle_key(&mut self, key_event: &KeyEvent) -> bool {
+        if key_event.ctrl || key_event.alt || self.items.is_empty() {
+            return false;
+        }
+
+        let page = self.rows.len().max(1);
+        let last = self.items.len() - 1;
+        match key_event.code {
+            KeyCode::Up => self.select(if self.selected == 0 { last } else { self.selected - 1 }),
+            KeyCode::Down => self.select(if self.selected == last { 0 } else { self.selected + 1 }),
+            KeyCode::PageUp => self.select(self.selected.saturating_sub(page)),
+            KeyCode::PageDown => self.select(self.selected + page),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(last),
+            KeyCode::Enter => self.chosen = Some(self.selected),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Move the menu, and create a label for each row inside the border.
+    pub(crate) fn layout(&mut self, area: Rect) {
+        self.frame.set_area(area);
+        let inner = self.frame.inner();
+        self.rows = (0..inner.size.height)
+            .map(|y| {
+                TextLabel::new(
+                    self.paintbrush.clone(),
+                    Point { x: inner.position.x, y: inner.position.y + y },
+                    Dimensions { width: inner.size.width, height: 1 },
+                    "",
+                )
+            })
+            .collect();
+        self.scroll = 0;
+        self.select(self.selected);
+    }
+
+    /// Update the text and paintbrush of each visible row.
+    fn update_rows(&mut self) {
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            let index = self.scroll + i;
+            let text = self.items.get(index).map_or(String::new(), |item| format!(" {}", item));
+            let paintbrush = if index == self.selected {
+                self.paintbrush.inverted()
+            } else {
+                self.paintbrush.clone()
+            };
+            row.update(&text);
+            row.set_paintbrush(paintbrush);
+        }
+        self.dirty = true;
+    }
+}
+
+impl<PlotterT: Plotter> Component<PlotterT> for Menu {
+    fn position(&self) -> Point {
+        self.frame.area().position
+    }
+
+    fn size(&self) -> Dimensions {
+        self.frame.area().size
+    }
+
+    fn set_area(&mut self, area: Rect) {
+        self.layout(area);
+    }
+
+    /// Render the whole menu.
+    fn render(&mut self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
+        self.frame.render(plotter)?;
+        for row in &mut self.rows {
+            row.render(plotter)?;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Move the selection or choose an item with the keyboard or the mouse.
+    fn handle_event(&mut self, event: &Event) -> bool {
+        let Event::Mouse(mouse_event) = event else {
+            return matches!(event, Event::Key(key_event) if self.handle_key(key_event));
+        };
+
+        // Rows start inside the border.
+        let row = mouse_event.position.y.checked_sub(1).filter(|row| *row < self.rows.len());
+        let item = row.map(|row| self.scroll + row).filter(|item| *item < self.items.len());
+        match (mouse_event.kind, item) {
+            (MouseEventKind::ScrollUp, _) => self.select(self.selected.saturating_sub(1)),
+            (MouseEventKind::ScrollDown, _) => self.select(self.selected + 1),
+            (MouseEventKind::Down(MouseButton::Left), Some(item)) => self.select(item),
+            (MouseEventKind::Click(MouseButton::Left), Some(item)) => {
+                self.select(item);
+                self.chosen = Some(item);
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod menu_tests {
+    use super::*;
+    use crate::event::MouseEvent;
+    use crate::mock::ScreenPlotter;
+
+    fn create_menu(height: usize) -> Menu {
+        Menu::new(
+            Paintbrush::create_default(),
+            Point { x: 0, y: 0 },
+            Dimensions { width: 12, height },
+            "Ships",
+            &["Glider", "LWSS", "MWSS", "HWSS"],
+        )
+    }
+
+    fn render(menu: &mut Menu) -> ScreenPlotter {
+        let mut plotter = ScreenPlotter::new(Component::<ScreenPlotter>::size(menu));
+        menu.render(&mut plotter).unwrap();
+        plotter
+    }
+
+    fn press(menu: &mut Menu, code: KeyCode) {
+        menu.handle_key(&KeyEvent::new(code));
+    }
+
+    #[test]
+    fn highlights_the_selected_item() {
+        let mut menu = create_menu(6);
+        press(&mut menu, KeyCode::Down);
+
+        render(&mut menu).assert_styled_screen(
+            "
+            |┌─ Ships ──┐
+            |│ Glider   │
+            |│{Black on White} LWSS     {}│
+            |│ MWSS     │
+            |│ HWSS     │
+            |└──────────┘
+            ",
+        );
+    }
+
+    #[test]
+    fn scrolls_to_show_the_selected_item() {
+        let mut menu = create_menu(4);
+        press(&mut menu, KeyCode::End);
+
+        render(&mut menu).assert_screen(
+            "
+            |┌─ Ships ──┐
+            |│ MWSS     │
+            |│ HWSS     │
+            |└──────────┘
+            ",
+        );
+
+        press(&mut menu, KeyCode::Down);
+        assert_eq!(0, menu.selected());
+        render(&mut menu).assert_screen(
+            "
+            |┌─ Ships ──┐
+            |│ Glider   │
+            |│ LWSS     │
+            |└──────────┘
+            ",
+        );
+    }
+
+    #[test]
+    fn items_are_chosen_with_enter_or_a_click() {
+        let mut menu = create_menu(6);
+        press(&mut menu, KeyCode::PageDown);
+        press(&mut menu, KeyCode::Enter);
+        assert_eq!(Some(3), menu.take_chosen());
+        assert_eq!(None, menu.take_chosen());
+
+        let click = |kind| Event::Mouse(MouseEvent { kind, position: Point { x: 3, y: 2 } });
+        let component: &mut dyn Component<ScreenPlotter> = &mut menu;
+        assert!(component.handle_event(&click(MouseEventKind::Down(MouseButton::Left))));
+        assert!(component.handle_event(&click(MouseEventKind::Click(MouseButton::Left))));
+        assert_eq!(Some(1), menu.take_chosen());
+    }
+
+    #[test]
+    fn is_big_enough_for_its_items_and_title() {
+        let menu = create_menu(6);
+
+        assert_eq!(Dimensions { width: 11, height: 6 }, menu.preferred_size());
+    }
+}
@@ -0,0 +1,482 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::event::{Event, KeyCode, KeyEvent, MouseButton, MouseEventKind};
+use crate::layout::Rect;
+use crate::lowlevel::*;
+use crate::Component;
+use xy_utils::{Dimensions, Point};
+
+/// A single line of editable text, such as a command line.
+///
+/// The input handles the usual editing keys when it is given key events (see
+/// `Component::handle_event()`): printable characters are inserted at the
+/// cursor, `Backspace` and `Delete` remove a character, and `Left`, `Right`,
+/// `Home` and `End` move the cursor. The cursor always moves over a whole
+/// grapheme, so characters made up of several code points are edited as one.
+///
+/// `Up` and `Down` browse the history of submitted text. `Enter` and `Esc`
+/// are left to the application, which calls `submit()` or `clear()`.
+///
+/// Text that is too long to fit is scrolled so that the cursor is always
+/// visible. The cursor is drawn with the inverted paintbrush (see
+/// `Paintbrush::inverted()`).
+///
+/// ## Example
+///
+/// ```
+/// # use tui::mock::MockPlotter as MyPlotter;
+/// use tui::components::TextInput;
+/// use tui::event::{Event, KeyCode, KeyEvent};
+/// use tui::{Component, Paintbrush};
+/// use xy_utils::{Dimensions, Point};
+///
+/// let mut input = TextInput::new(
+///     Paintbrush::create_default(),
+///     Point { x: 0, y: 0 },
+///     Dimensions { width: 20, height: 1 },
+///     ":",
+/// );
+/// let input_component: &mut dyn Component<MyPlotter> = &mut input;
+/// for code in [KeyCode::Char('g'), KeyCode::Char('o'), KeyCode::Left, KeyCode::Char('l')] {
+///     input_component.handle_event(&Event::Key(KeyEvent::new(code)));
+/// }
+///
+/// assert_eq!("glo", input.submit());
+/// assert_eq!("", input.text());
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextInput {
+    /// The paintbrush that sets the color info for the input.
+    paintbrush: Paintbrush,
+
+    /// The location in the UI to render the input.
+    position: Point,
+
+    /// The size of the input. Only the first row is used.
+    size: Dimensions,
+
+    /// Text shown before the editable text (e.g. `:`).
+    prompt: String,
+
+    /// The text being edited.
+    text: String,
+
+    /// The position of the cursor, in graphemes from the start of the text.
+    cursor: usize,
+
+    /// The first grapheme of the text that is visible.
+    scroll: usize,
+
+    /// Text that has been submitted, oldest first.
+    history: Vec<String>,
+
+    /// The history entry being shown, or `None` when editing new text.
+    history_index: Option<usize>,
+
+    /// The new text that was being edited before the history was browsed.
+    draft: String,
+
+    /// True when the input has changed since it was last rendered.
+    dirty: bool,
+}
+
+impl TextInput {
+    pub fn new(paintbrush: Paintbrush, position: Point, size: Dimensions, prompt: &str) -> Self {
+        TextInput {
+            paintbrush,
+            position,
+            size,
+            prompt: prompt.to_string(),
+            text: String::new(),
+            cursor: 0,
+            scroll: 0,
+            history: vec![],
+            history_index: None,
+            draft: String::new(),
+            dirty: true,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the position of the cursor, in graphemes from the start of the
+    /// text.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Replace the text, and move the cursor to the end of it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `text`: The new text.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.cursor = self.len();
+        self.update_scroll();
+        self.dirty = true;
+    }
+
+    /// Remove all of the text, and stop browsing the history.
+    pub fn clear(&mut self) {
+        self.history_index = None;
+        self.set_text("");
+    }
+
+    /// Take the text that has been entered, and add it to the history. The
+    /// input is left empty.
+    ///
+    /// ## Returns
+    ///
+    /// The text that was entered.
+    pub fn submit(&mut self) -> String {
+        let text = std::mem::take(&mut self.text);
+        if !text.is_empty() && self.history.last() != Some(&text) {
+            self.history.push(text.clone());
+        }
+        self.clear();
+        text
+    }
+
+    /// Insert text at the cursor, and move the cursor to the end of the
+    /// inserted text.
+    ///
+    /// ## Arguments
+    ///
+    /// * `text`: The text to insert.
+    pub fn insert_str(&mut self, text: &str) {
+        let end = self.byte_offset(self.cursor) + text.len();
+        self.text.insert_str(self.byte_offset(self.cursor), text);
+        // Inserted combining characters may join the grapheme before them, so
+        // the cursor is found from the new text.
+        self.cursor = self.text.grapheme_indices(true).take_while(|(i, _)| *i < end).count();
+        self.update_scroll();
+        self.dirty = true;
+    }
+
+    /// Remove the grapheme before the cursor.
+    pub fn delete_backward(&mut self) {
+        if self.cursor > 0 {
+            self.move_cursor(self.cursor - 1);
+            self.delete_forward();
+        }
+    }
+
+    /// Remove the grapheme after the cursor.
+    pub fn delete_forward(&mut self) {
+        if self.cursor < self.len() {
+            let range = self.byte_offset(self.cursor)..self.byte_offset(self.cursor + 1);
+            self.text.replace_range(range, "");
+            self.dirty = true;
+        }
+    }
+
+    /// Move the cursor.
+    ///
+    /// ## Arguments
+    ///
+    /// * `cursor`: The new position of the cursor, in graphemes from the start
+    ///   of the text. Positions beyond the end of the text move the cursor to
+    ///   the end.
+    pub fn move_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.len());
+        self.update_scroll();
+        self.dirty = true;
+    }
+
+    /// Show the previous entry in the history.
+    pub fn history_back(&mut self) {
+        let index = match self.history_index {
+            _ if self.history.is_empty() => return,
+            None => {
+                self.draft = self.text.clone();
+                self.history.len() - 1
+            }
+            Some(index) => index.saturating_sub(1),
+        };
+        self.history_index = Some(index);
+        self.set_text(&self.history[index].clone());
+    }
+
+    /// Show the next entry in the history, or the new text that was being
+    /// edited once the end of the history is reached.
+    pub fn history_forward(&mut self) {
+        match self.history_index {
+            Some(index) if index + 1 < self.history.len() => {
+                self.history_index = Some(index + 1);
+                self.set_text(&self.history[index + 1].clone());
+            }
+            Some(_) => {
+                self.history_index = None;
+                let draft = std::mem::take(&mut self.draft);
+                self.set_text(&draft);
+            }
+            None => {}
+        }
+    }
+
+    /// Edit the text with a key press.
+    ///
+    /// ## Arguments
+    ///
+    /// * `key_event`: The key that was pressed.
+    ///
+    /// ## Returns
+    ///
+    /// True if the key was used to edit the text (or browse the history).
+    pub fn handle_key(&mut self, key_event: &KeyEvent) -> bool {
+        if key_event.ctrl || key_event.alt {
+            return false;
+        }
+
+        match key_event.code {
+            KeyCode::Char(c) => self.insert_str(c.encode_utf8(&mut [0; 4])),
+            KeyCode::Backspace => self.delete_backward(),
+            KeyCode::Delete => self.delete_forward(),
+            KeyCode::Left => self.move_cursor(self.cursor.saturating_sub(1)),
+            KeyCode::Right => self.move_cursor(self.cursor + 1),
+            KeyCode::Home => self.move_cursor(0),
+            KeyCode::End => self.move_cursor(usize::MAX),
+            KeyCode::Up => self.history_back(),
+            KeyCode::Down => self.history_forward(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Get the number of graphemes in the text.
+    fn len(&self) -> usize {
+        self.text.graphemes(true).count()
+    }
+
+    /// Get the byte offset of a grapheme in the text.
+    fn byte_offset(&self, grapheme: usize) -> usize {
+        self.text.grapheme_indices(true).nth(grapheme).map_or(self.text.len(), |(i, _)| i)
+    }
+
+    /// Get the number of graphemes of text that fit beside the prompt. There
+    /// is always room for the cursor.
+    fn visible_width(&self) -> usize {
+        self.size.width.saturating_sub(self.prompt.graphemes(true).count()).max(1)
+    }
+
+    /// Scroll the text so that the cursor is visible.
+    fn update_scroll(&mut self) {
+        let visible_width = self.visible_width();
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + visible_width {
+            self.scroll = self.cursor + 1 - visible_width;
+        }
+    }
+}
+
+impl<PlotterT: Plotter> Component<PlotterT> for TextInput {
+    fn position(&self) -> Point {
+        self.position
+    }
+
+    fn size(&self) -> Dimensions {
+        self.size
+    }
+
+    fn set_area(&mut self, area: Rect) {
+        self.position = area.position;
+        self.size = area.size;
+        self.scroll = 0;
+        self.update_scroll();
+        self.dirty = true;
+    }
+
+    /// Render the prompt and the visible part of the text, with the cursor.
+    fn render(&mut self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
+        self.dirty = false;
+        if self.size.width == 0 || self.size.height == 0 {
+            return Ok(());
+        }
+
+        let prompt: Vec<&str> = self.prompt.graphemes(true).take(self.size.width - 1).collect();
+        let visible: Vec<&str> =
+            self.text.graphemes(true).skip(self.scroll).take(self.visible_width()).collect();
+        let cursor = self.cursor - self.scroll;
+        let after = visible.get(cursor + 1..).unwrap_or_default();
+        let padding = self.size.width - prompt.len() - cursor - 1 - after.len();
+        let cursor_position =
+            Point { x: self.position.x + prompt.len() + cursor, y: self.position.y };
+
+        plotter.set_paintbrush(&self.paintbrush)?;
+        plotter.plot(self.position, prompt.concat() + &visible[..cursor].concat())?;
+        plotter.set_paintbrush(&self.paintbrush.inverted())?;
+        plotter.plot(cursor_position, visible.get(cursor).unwrap_or(&" "))?;
+        plotter.set_paintbrush(&self.paintbrush)?;
+        plotter.plot(
+            Point { x: cursor_position.x + 1, y: self.position.y },
+            after.concat() + &" ".repeat(padding),
+        )?;
+        Ok(())
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Edit the text with key presses, or move the cursor by clicking.
+    fn handle_event(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Key(key_event) => self.handle_key(key_event),
+            Event::Mouse(mouse_event)
+                if mouse_event.kind == MouseEventKind::Down(MouseButton::Left) =>
+            {
+                let prompt_width = self.prompt.graphemes(true).count();
+                self.move_cursor(self.scroll + mouse_event.position.x.saturating_sub(prompt_width));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod text_input_tests {
+    use super::*;
+    use crate::event::MouseEvent;
+    use crate::mock::ScreenPlotter;
+
+    fn create_input(width: usize) -> TextInput {
+        TextInput::new(
+            Paintbrush::create_default(),
+            Point { x: 0, y: 0 },
+            Dimensions { width, height: 1 },
+            ":",
+        )
+    }
+
+    fn press(input: &mut TextInput, keys: &[KeyCode]) -> bool {
+        let component: &mut dyn Component<ScreenPlotter> = input;
+        keys.iter().all(|code| component.handle_event(&Event::Key(KeyEvent::new(*code))))
+    }
+
+    fn type_text(input: &mut TextInput, text: &str) {
+        press(input, &text.chars().map(KeyCode::Char).collect::<Vec<_>>());
+    }
+
+    fn render(input: &mut TextInput) -> ScreenPlotter {
+        let mut plotter = ScreenPlotter::new(input.size);
+        input.render(&mut plotter).unwrap();
+        plotter
+    }
+
+    #[test]
+    fn edits_text_at_the_cursor() {
+        let mut input = create_input(20);
+        type_text(&mut input, "rle");
+        press(&mut input, &[KeyCode::Left, KeyCode::Left]);
+        type_text(&mut input, "u");
+        press(&mut input, &[KeyCode::End, KeyCode::Backspace, KeyCode::Home, KeyCode::Delete]);
+
+        assert_eq!("ul", input.text());
+        assert_eq!(0, input.cursor());
+    }
+
+    #[test]
+    fn edits_whole_graphemes() {
+        let mut input = create_input(20);
+        type_text(&mut input, "ne\u{301}e");
+        assert_eq!(3, input.cursor());
+
+        press(&mut input, &[KeyCode::Left, KeyCode::Backspace]);
+        assert_eq!("ne", input.text());
+        assert_eq!(1, input.cursor());
+    }
+
+    #[test]
+    fn leaves_other_keys_to_the_application() {
+        let mut input = create_input(20);
+
+        assert!(!press(&mut input, &[KeyCode::Enter]));
+        assert!(!press(&mut input, &[KeyCode::Escape]));
+        let component: &mut dyn Component<ScreenPlotter> = &mut input;
+        assert!(!component.handle_event(&Event::Key(KeyEvent::with_ctrl(KeyCode::Char('c')))));
+        assert_eq!("", input.text());
+    }
+
+    #[test]
+    fn browses_the_history_of_submitted_text() {
+        let mut input = create_input(20);
+        for text in ["first", "second", "second", ""] {
+            type_text(&mut input, text);
+            input.submit();
+        }
+        type_text(&mut input, "new");
+
+        press(&mut input, &[KeyCode::Up]);
+        assert_eq!("second", input.text());
+        press(&mut input, &[KeyCode::Up, KeyCode::Up]);
+        assert_eq!("first", input.text());
+        press(&mut input, &[KeyCode::Down]);
+        assert_eq!("second", input.text());
+        press(&mut input, &[KeyCode::Down]);
+        assert_eq!("new", input.text());
+    }
+
+    #[test]
+    fn scrolls_long_text_to_show_the_cursor() {
+        let mut input = create_input(6);
+        type_text(&mut input, "abcdefg");
+
+        render(&mut input).assert_styled_screen(
+            "
+            |:defg{Black on White} {}
+            ",
+        );
+
+        press(&mut input, &[KeyCode::Home, KeyCode::Right]);
+        render(&mut input).assert_styled_screen(
+            "
+            |:a{Black on White}b{}cde
+            ",
+        );
+    }
+
+    #[test]
+    fn clicking_moves_the_cursor() {
+        let mut input = create_input(10);
+        type_text(&mut input, "glider");
+        let component: &mut dyn Component<ScreenPlotter> = &mut input;
+        let click = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            position: Point { x: 3, y: 0 },
+        };
+
+        assert!(component.handle_event(&Event::Mouse(click)));
+        assert_eq!(2, input.cursor());
+    }
+}
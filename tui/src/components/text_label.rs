@@ -22,10 +22,13 @@
 
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::layout::Rect;
 use crate::lowlevel::*;
+use crate::Component;
 use xy_utils::{Dimensions, Point};
 
 /// Struct that defines a simple text label rendered to the terminal UI.
+#[derive(Clone)]
 pub struct TextLabel {
     /// The paintbrush that sets the color info for the label content.
     paintbrush: Paintbrush,
@@ -41,6 +44,9 @@ pub struct TextLabel {
 
     /// Internally calculated character string rows for the label.
     output_text_rows: Vec<String>,
+
+    /// True when the label has changed since it was last rendered.
+    dirty: bool,
 }
 
 impl TextLabel {
@@ -51,6 +57,7 @@ impl TextLabel {
             size,
             text: text.to_string(),
             output_text_rows: vec![],
+            dirty: true,
         };
 
         // Create the renderable character rows.
@@ -73,6 +80,7 @@ impl TextLabel {
     /// * `paintbrush`: the next paintbrush.
     pub fn set_paintbrush(&mut self, paintbrush: Paintbrush) {
         self.paintbrush = paintbrush;
+        self.dirty = true;
     }
 
     /// Update the text label.
@@ -85,30 +93,18 @@ impl TextLabel {
     pub fn update(&mut self, text: &str) {
         self.text = text.to_string();
         self.get_label_output_text();
-    }
-
-    /// Render the text label using the provided low level UI plotter.
-    pub fn render<PlotterT: Plotter>(&self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
-        // We may need to truncate the label text to fit inside the
-        plotter.set_paintbrush(&self.paintbrush)?;
-
-        for (i, row) in self.output_text_rows.iter().enumerate() {
-            // We need to pad the label with whitespace to overwrite any old
-            // changes.
-            plotter.plot(Point { x: self.position.x, y: self.position.y + i }, row)?;
-        }
-        Ok(())
+        self.dirty = true;
     }
 
     fn get_label_output_text(&mut self) {
         let max_cols = self.size.width;
         let max_rows = self.size.height;
-        let max_text_length = (max_cols * max_rows) as usize;
+        let max_text_length = max_cols * max_rows;
 
         // Get the rendered text as a single string. This will let us slice it
         // properly.
         let mut output_text = String::new();
-        if self.text.len() <= max_text_length {
+        if self.text.graphemes(true).count() <= max_text_length {
             output_text = self.text.clone();
         } else if max_text_length < 3 {
             for _ in 0..max_text_length {
@@ -131,7 +127,7 @@ impl TextLabel {
         let mut row_start_byte = 0;
         let mut row_end_byte = 0;
         for grapheme in output_text.graphemes(true) {
-            row_end_byte += grapheme.as_bytes().len();
+            row_end_byte += grapheme.len();
             x += 1;
 
             if x == max_cols {
@@ -145,9 +141,9 @@ impl TextLabel {
             // Make sure to pad the row to ensure that any existing content is
             // erased.
             let mut row = output_text[row_start_byte..row_end_byte].to_string();
-            let padding_size = self.size.width - row.len();
+            let padding_size = self.size.width - row.graphemes(true).count();
             for _ in 0..padding_size {
-                row.push_str(" ");
+                row.push(' ');
             }
             self.output_text_rows.push(row);
         }
@@ -166,9 +162,49 @@ impl TextLabel {
     }
 }
 
+impl<PlotterT: Plotter> Component<PlotterT> for TextLabel {
+    fn position(&self) -> Point {
+        self.position
+    }
+
+    fn size(&self) -> Dimensions {
+        self.size
+    }
+
+    fn set_area(&mut self, area: Rect) {
+        self.position = area.position;
+        self.size = area.size;
+        self.get_label_output_text();
+        self.dirty = true;
+    }
+
+    /// Render the text label using the provided low level UI plotter.
+    fn render(&mut self, plotter: &mut PlotterT) -> Result<(), std::io::Error> {
+        // We may need to truncate the label text to fit inside the
+        plotter.set_paintbrush(&self.paintbrush)?;
+
+        for (i, row) in self.output_text_rows.iter().enumerate() {
+            // We need to pad the label with whitespace to overwrite any old
+            // changes.
+            plotter.plot(Point { x: self.position.x, y: self.position.y + i }, row)?;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+}
+
 #[cfg(test)]
 mod text_label_tests {
     use super::*;
+    use crate::mock::ScreenPlotter;
 
     #[test]
     fn has_a_constructor() {
@@ -185,7 +221,7 @@ mod text_label_tests {
     #[test]
     fn can_be_rendered_with_a_lowlevel_plotter() {
         let mut plotter = mock::MockPlotter::new();
-        let label = TextLabel::new(
+        let mut label = TextLabel::new(
             Paintbrush::create_default(),
             Point { x: 1, y: 2 },
             Dimensions { width: 3, height: 1 },
@@ -231,7 +267,7 @@ mod text_label_tests {
         // there are free cells in the label, trim the text content and add an
         // ellipsis ("...").
         let mut plotter = mock::MockPlotter::new();
-        let label = TextLabel::new(
+        let mut label = TextLabel::new(
             Paintbrush::create_default(),
             Point { x: 1, y: 2 },
             Dimensions { width: 6, height: 1 },
@@ -258,7 +294,7 @@ mod text_label_tests {
         // dots, where _n_ is the number of characters that can be written in
         // the label.
         let mut plotter = mock::MockPlotter::new();
-        let label = TextLabel::new(
+        let mut label = TextLabel::new(
             Paintbrush::create_default(),
             Point { x: 1, y: 2 },
             Dimensions { width: 2, height: 1 },
@@ -316,7 +352,7 @@ mod text_label_tests {
     #[test]
     fn support_multi_lines() {
         let mut plotter = mock::MockPlotter::new();
-        let label = TextLabel::new(
+        let mut label = TextLabel::new(
             Paintbrush::create_default(),
             Point { x: 1, y: 2 },
             Dimensions { width: 3, height: 3 },
@@ -349,7 +385,7 @@ mod text_label_tests {
     #[test]
     fn trimming_works_even_on_multi_line_labels() {
         let mut plotter = mock::MockPlotter::new();
-        let label = TextLabel::new(
+        let mut label = TextLabel::new(
             Paintbrush::create_default(),
             Point { x: 1, y: 2 },
             Dimensions { width: 3, height: 2 },
@@ -367,4 +403,42 @@ mod text_label_tests {
             panic!("Incorrect third plotter command");
         }
     }
+
+    #[test]
+    fn is_dirty_until_it_is_rendered() {
+        let mut plotter = mock::MockPlotter::new();
+        let mut label = TextLabel::new(
+            Paintbrush::create_default(),
+            Point { x: 1, y: 2 },
+            Dimensions { width: 3, height: 1 },
+            "FOO",
+        );
+        label.render(&mut plotter).unwrap();
+
+        label.update("BAR");
+        let label: &mut dyn Component<mock::MockPlotter> = &mut label;
+        assert!(label.is_dirty());
+        label.render(&mut plotter).unwrap();
+        assert!(!label.is_dirty());
+    }
+
+    #[test]
+    fn pads_text_made_of_multi_byte_characters() {
+        let mut plotter = ScreenPlotter::new(Dimensions { width: 5, height: 1 });
+        plotter.plot(Point { x: 0, y: 0 }, "#####").unwrap();
+        let mut label = TextLabel::new(
+            Paintbrush::create_default(),
+            Point { x: 0, y: 0 },
+            Dimensions { width: 4, height: 1 },
+            "←→",
+        );
+
+        label.render(&mut plotter).unwrap();
+
+        plotter.assert_screen(
+            "
+            |←→  #
+            ",
+        );
+    }
 }
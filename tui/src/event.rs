@@ -0,0 +1,492 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Input events, and the loop that delivers them to an application.
+//!
+//! Events are described independently of the terminal backend. They are read
+//! from an `EventSource`, which is either the terminal
+//! (`TerminalEventSource`), or a script of events (`ScriptedEventSource`) so
+//! that applications can be driven by tests.
+//!
+//! The `EventLoop` runs an application at a fixed timestep. It delivers a
+//! `Tick` event at a steady rate, and any input events as they arrive.
+//! `KeyBindings` map key presses to the application's own actions, and a
+//! `MouseDispatcher` passes mouse events on to the components under the
+//! mouse. The terminal only reports mouse events once
+//! `DefaultPlotter::enable_mouse_capture()` has been called.
+
+mod event_loop;
+mod key_bindings;
+mod mouse_dispatcher;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crossterm::event as terminal;
+use xy_utils::{Dimensions, Point};
+
+pub use event_loop::{EventLoop, Flow};
+pub use key_bindings::KeyBindings;
+pub use mouse_dispatcher::MouseDispatcher;
+
+/// A key on the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    /// A printable character.
+    Char(char),
+    /// A function key (e.g. `F(1)` is F1).
+    F(u8),
+    Enter,
+    Escape,
+    Backspace,
+    Delete,
+    Tab,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+}
+
+/// A key press, along with the modifier keys that were held down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    /// The key that was pressed.
+    pub code: KeyCode,
+
+    /// True when the Control key was held down.
+    pub ctrl: bool,
+
+    /// True when the Alt key was held down.
+    pub alt: bool,
+}
+
+impl KeyEvent {
+    /// Create a key press without any modifier keys.
+    ///
+    /// ## Arguments
+    ///
+    /// * `code`: The key that was pressed.
+    pub fn new(code: KeyCode) -> Self {
+        KeyEvent { code, ctrl: false, alt: false }
+    }
+
+    /// Create a key press with the Control key held down.
+    ///
+    /// ## Arguments
+    ///
+    /// * `code`: The key that was pressed.
+    pub fn with_ctrl(code: KeyCode) -> Self {
+        KeyEvent { code, ctrl: true, alt: false }
+    }
+}
+
+impl From<KeyCode> for KeyEvent {
+    fn from(code: KeyCode) -> Self {
+        KeyEvent::new(code)
+    }
+}
+
+/// Key presses are shown the way they are written in help text, such as
+/// `Ctrl+C`, `q` or `Enter`.
+impl fmt::Display for KeyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.ctrl || self.alt => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Escape => write!(f, "Esc"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Delete"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+        }
+    }
+}
+
+/// A mouse button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// What the mouse did.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseEventKind {
+    /// A button was pressed.
+    Down(MouseButton),
+
+    /// A button was released.
+    Up(MouseButton),
+
+    /// The mouse moved while a button was held down.
+    Drag(MouseButton),
+
+    /// The mouse moved without any buttons held down.
+    Moved,
+
+    /// The mouse wheel was scrolled towards the user.
+    ScrollDown,
+
+    /// The mouse wheel was scrolled away from the user.
+    ScrollUp,
+
+    /// The mouse moved onto a component. Only sent by a `MouseDispatcher`.
+    Enter,
+
+    /// The mouse moved off a component. Only sent by a `MouseDispatcher`.
+    Leave,
+
+    /// A button was pressed and released over the same component. Only sent
+    /// by a `MouseDispatcher`, after the `Up` event.
+    Click(MouseButton),
+}
+
+/// Something that the mouse did, and where it was when it did it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+
+    /// The terminal cell that the mouse was over.
+    pub position: Point,
+}
+
+/// An input event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A key was pressed.
+    Key(KeyEvent),
+
+    /// The mouse was used.
+    Mouse(MouseEvent),
+
+    /// The terminal was resized to the given size.
+    Resize(Dimensions),
+
+    /// A fixed amount of time has passed (see `EventLoop`).
+    Tick,
+}
+
+/// Somewhere that events come from.
+///
+/// Event sources also tell the time, so that sources used for testing can run
+/// on a virtual clock.
+pub trait EventSource {
+    /// Wait for the next event.
+    ///
+    /// ## Arguments
+    ///
+    /// * `timeout`: The longest time to wait.
+    ///
+    /// ## Returns
+    ///
+    /// The next event, or `None` if there was no event before the timeout.
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>, std::io::Error>;
+
+    /// Get the current time.
+    fn now(&self) -> Instant;
+}
+
+/// Source of the events from the terminal.
+///
+/// Terminal events that have no equivalent `Event` (e.g. key releases, or
+/// keys such as Caps Lock) are ignored.
+#[derive(Default)]
+pub struct TerminalEventSource {}
+
+impl TerminalEventSource {
+    pub fn new() -> Self {
+        TerminalEventSource {}
+    }
+
+    /// Convert a terminal event to an `Event`, if it has an equivalent.
+    fn convert_event(event: terminal::Event) -> Option<Event> {
+        match event {
+            terminal::Event::Key(key_event) => Self::convert_key_event(key_event).map(Event::Key),
+            terminal::Event::Mouse(mouse_event) => {
+                Some(Event::Mouse(Self::convert_mouse_event(mouse_event)))
+            }
+            terminal::Event::Resize(width, height) => {
+                Some(Event::Resize(Dimensions { width: width as usize, height: height as usize }))
+            }
+            _ => None,
+        }
+    }
+
+    fn convert_key_event(key_event: terminal::KeyEvent) -> Option<KeyEvent> {
+        if key_event.kind == terminal::KeyEventKind::Release {
+            return None;
+        }
+
+        let code = match key_event.code {
+            terminal::KeyCode::Char(c) => KeyCode::Char(c),
+            terminal::KeyCode::F(n) => KeyCode::F(n),
+            terminal::KeyCode::Enter => KeyCode::Enter,
+            terminal::KeyCode::Esc => KeyCode::Escape,
+            terminal::KeyCode::Backspace => KeyCode::Backspace,
+            terminal::KeyCode::Delete => KeyCode::Delete,
+            terminal::KeyCode::Tab | terminal::KeyCode::BackTab => KeyCode::Tab,
+            terminal::KeyCode::Left => KeyCode::Left,
+            terminal::KeyCode::Right => KeyCode::Right,
+            terminal::KeyCode::Up => KeyCode::Up,
+            terminal::KeyCode::Down => KeyCode::Down,
+            terminal::KeyCode::Home => KeyCode::Home,
+            terminal::KeyCode::End => KeyCode::End,
+            terminal::KeyCode::PageUp => KeyCode::PageUp,
+            terminal::KeyCode::PageDown => KeyCode::PageDown,
+            _ => return None,
+        };
+
+        Some(KeyEvent {
+            code,
+            ctrl: key_event.modifiers.contains(terminal::KeyModifiers::CONTROL),
+            alt: key_event.modifiers.contains(terminal::KeyModifiers::ALT),
+        })
+    }
+
+    fn convert_mouse_event(mouse_event: terminal::MouseEvent) -> MouseEvent {
+        let button = |button| match button {
+            terminal::MouseButton::Left => MouseButton::Left,
+            terminal::MouseButton::Right => MouseButton::Right,
+            terminal::MouseButton::Middle => MouseButton::Middle,
+        };
+        let kind = match mouse_event.kind {
+            terminal::MouseEventKind::Down(b) => MouseEventKind::Down(button(b)),
+            terminal::MouseEventKind::Up(b) => MouseEventKind::Up(button(b)),
+            terminal::MouseEventKind::Drag(b) => MouseEventKind::Drag(button(b)),
+            terminal::MouseEventKind::Moved => MouseEventKind::Moved,
+            terminal::MouseEventKind::ScrollDown => MouseEventKind::ScrollDown,
+            terminal::MouseEventKind::ScrollUp => MouseEventKind::ScrollUp,
+        };
+
+        MouseEvent {
+            kind,
+            position: Point { x: mouse_event.column as usize, y: mouse_event.row as usize },
+        }
+    }
+}
+
+impl EventSource for TerminalEventSource {
+    #[cfg(not(tarpaulin_include))]
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>, std::io::Error> {
+        // Events without an equivalent are skipped, but only until the
+        // timeout.
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if !terminal::poll(remaining)? {
+                return Ok(None);
+            }
+            if let Some(event) = Self::convert_event(terminal::read()?) {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Source of a script of events, for testing.
+///
+/// The scripted source runs on a virtual clock, which starts when the source
+/// is created and only moves forward when the source is waited on. Events can
+/// be delivered as soon as they are asked for, or at a given time.
+///
+/// ## Example
+///
+/// ```
+/// use std::time::Duration;
+/// use tui::event::{Event, EventSource, KeyCode, KeyEvent, ScriptedEventSource};
+///
+/// let mut source = ScriptedEventSource::new();
+/// source.push(Event::Key(KeyEvent::new(KeyCode::Enter)));
+/// source.push_at(Duration::from_secs(1), Event::Key(KeyEvent::new(KeyCode::Escape)));
+///
+/// let start = source.now();
+/// assert_eq!(Some(Event::Key(KeyEvent::new(KeyCode::Enter))), source.next_event(Duration::from_secs(5)).unwrap());
+/// assert_eq!(None, source.next_event(Duration::from_millis(500)).unwrap());
+/// assert_eq!(Some(Event::Key(KeyEvent::new(KeyCode::Escape))), source.next_event(Duration::from_secs(5)).unwrap());
+/// assert_eq!(Duration::from_secs(1), source.now() - start);
+/// ```
+pub struct ScriptedEventSource {
+    start: Instant,
+    elapsed: Duration,
+
+    /// The scripted events, along with the time that they happen (relative
+    /// to the start of the clock).
+    events: VecDeque<(Duration, Event)>,
+}
+
+impl Default for ScriptedEventSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScriptedEventSource {
+    pub fn new() -> Self {
+        ScriptedEventSource {
+            start: Instant::now(),
+            elapsed: Duration::ZERO,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Add an event to the script, to be delivered as soon as the events
+    /// before it have been delivered.
+    pub fn push(&mut self, event: Event) {
+        let time = self.events.back().map_or(Duration::ZERO, |(time, _)| *time);
+        self.events.push_back((time, event));
+    }
+
+    /// Add an event to the script, to be delivered at a given time. Events
+    /// are always delivered in the order that they are added, so the time
+    /// should not be before the time of the last event.
+    ///
+    /// ## Arguments
+    ///
+    /// * `time`: The time to deliver the event, relative to the creation of
+    ///   the source.
+    /// * `event`: The event.
+    pub fn push_at(&mut self, time: Duration, event: Event) {
+        self.events.push_back((time, event));
+    }
+
+    /// Check whether every scripted event has been delivered.
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl EventSource for ScriptedEventSource {
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>, std::io::Error> {
+        let deadline = self.elapsed + timeout;
+        match self.events.front() {
+            Some((time, _)) if *time <= deadline => {
+                self.elapsed = self.elapsed.max(*time);
+                Ok(self.events.pop_front().map(|(_, event)| event))
+            }
+            _ => {
+                self.elapsed = deadline;
+                Ok(None)
+            }
+        }
+    }
+
+    fn now(&self) -> Instant {
+        self.start + self.elapsed
+    }
+}
+
+#[cfg(test)]
+mod event_tests {
+    use super::*;
+
+    #[test]
+    fn key_presses_are_shown_as_they_are_written_in_help() {
+        assert_eq!("Ctrl+C", KeyEvent::with_ctrl(KeyCode::Char('c')).to_string());
+        assert_eq!("q", KeyEvent::new(KeyCode::Char('q')).to_string());
+        assert_eq!("Space", KeyEvent::new(KeyCode::Char(' ')).to_string());
+        assert_eq!("Esc", KeyEvent::new(KeyCode::Escape).to_string());
+        assert_eq!("Alt+F4", KeyEvent { code: KeyCode::F(4), ctrl: false, alt: true }.to_string());
+    }
+
+    #[test]
+    fn converts_terminal_key_events() {
+        let event = terminal::Event::Key(terminal::KeyEvent::new(
+            terminal::KeyCode::Char('c'),
+            terminal::KeyModifiers::CONTROL,
+        ));
+
+        assert_eq!(
+            Some(Event::Key(KeyEvent::with_ctrl(KeyCode::Char('c')))),
+            TerminalEventSource::convert_event(event)
+        );
+    }
+
+    #[test]
+    fn ignores_terminal_events_without_an_equivalent() {
+        let caps_lock = terminal::Event::Key(terminal::KeyEvent::from(terminal::KeyCode::CapsLock));
+
+        assert_eq!(None, TerminalEventSource::convert_event(caps_lock));
+        assert_eq!(None, TerminalEventSource::convert_event(terminal::Event::FocusGained));
+    }
+
+    #[test]
+    fn converts_terminal_mouse_and_resize_events() {
+        let click = terminal::Event::Mouse(terminal::MouseEvent {
+            kind: terminal::MouseEventKind::Down(terminal::MouseButton::Left),
+            column: 3,
+            row: 4,
+            modifiers: terminal::KeyModifiers::NONE,
+        });
+
+        assert_eq!(
+            Some(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                position: Point { x: 3, y: 4 }
+            })),
+            TerminalEventSource::convert_event(click)
+        );
+        assert_eq!(
+            Some(Event::Resize(Dimensions { width: 80, height: 24 })),
+            TerminalEventSource::convert_event(terminal::Event::Resize(80, 24))
+        );
+    }
+
+    #[test]
+    fn scripted_events_wait_for_their_time() {
+        let mut source = ScriptedEventSource::new();
+        source.push_at(Duration::from_secs(2), Event::Tick);
+        let start = source.now();
+
+        assert_eq!(None, source.next_event(Duration::from_secs(1)).unwrap());
+        assert_eq!(Duration::from_secs(1), source.now() - start);
+        assert_eq!(Some(Event::Tick), source.next_event(Duration::from_secs(5)).unwrap());
+        assert_eq!(Duration::from_secs(2), source.now() - start);
+        assert!(source.is_finished());
+    }
+}
@@ -0,0 +1,251 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::cell::Cell;
+use std::time::Duration;
+
+use super::{Event, EventSource};
+
+/// The number of ticks that the event loop can fall behind by before it gives
+/// up on catching up, and skips the missed ticks.
+const MAX_MISSED_TICKS: u32 = 5;
+
+/// What an application wants the event loop to do after handling an event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flow {
+    /// Keep running.
+    Continue,
+
+    /// Stop the event loop.
+    Exit,
+}
+
+/// Runs an application at a fixed timestep.
+///
+/// `Event::Tick` is delivered at a steady rate, whatever the time taken to
+/// handle each event. If the application is slower than the tick rate, ticks
+/// are delivered back to back until it catches up (or, if it falls too far
+/// behind, the missed ticks are skipped). Input events are delivered between
+/// ticks as they arrive, and at least one waiting input event is delivered
+/// after every tick, so the application stays responsive even if it is slow.
+///
+/// ## Example
+///
+/// ```
+/// use std::time::Duration;
+/// use tui::event::{Event, EventLoop, Flow, KeyCode, KeyEvent, ScriptedEventSource};
+///
+/// let mut source = ScriptedEventSource::new();
+/// source.push_at(Duration::from_millis(350), Event::Key(KeyEvent::new(KeyCode::Char('q'))));
+///
+/// let mut ticks = 0;
+/// EventLoop::new(Duration::from_millis(100))
+///     .run(&mut source, |event| match event {
+///         Event::Tick => {
+///             ticks += 1;
+///             Flow::Continue
+///         }
+///         _ => Flow::Exit,
+///     })
+///     .unwrap();
+///
+/// assert_eq!(3, ticks);
+/// ```
+pub struct EventLoop {
+    tick_interval: Cell<Duration>,
+}
+
+impl EventLoop {
+    /// Create an event loop.
+    ///
+    /// ## Arguments
+    ///
+    /// * `tick_interval`: The time between ticks.
+    pub fn new(tick_interval: Duration) -> Self {
+        EventLoop { tick_interval: Cell::new(tick_interval) }
+    }
+
+    /// Change the time between ticks. This can be called while the loop is
+    /// running (e.g. by the event handler), and the next tick is due one new
+    /// interval after the last one.
+    ///
+    /// ## Arguments
+    ///
+    /// * `tick_interval`: The new time between ticks.
+    pub fn set_tick_interval(&self, tick_interval: Duration) {
+        self.tick_interval.set(tick_interval);
+    }
+
+    /// Run the event loop until the application asks it to exit.
+    ///
+    /// ## Arguments
+    ///
+    /// * `source`: Where input events come from.
+    /// * `handler`: Called with each event. Returns whether the loop should
+    ///   keep running.
+    ///
+    /// ## Returns
+    ///
+    /// An error if the events could not be read.
+    pub fn run<SourceT: EventSource>(
+        &self,
+        source: &mut SourceT,
+        mut handler: impl FnMut(Event) -> Flow,
+    ) -> Result<(), std::io::Error> {
+        let mut last_tick = source.now();
+        loop {
+            let tick_interval = self.tick_interval.get();
+            let now = source.now();
+            if now >= last_tick + tick_interval {
+                last_tick += tick_interval;
+                if now > last_tick + tick_interval * MAX_MISSED_TICKS {
+                    last_tick = now;
+                }
+                if handler(Event::Tick) == Flow::Exit {
+                    return Ok(());
+                }
+            }
+
+            // Wait for input until the next tick is due.
+            let next_tick = last_tick + self.tick_interval.get();
+            let timeout = next_tick.saturating_duration_since(source.now());
+            if let Some(event) = source.next_event(timeout)? {
+                if handler(event) == Flow::Exit {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod event_loop_tests {
+    use super::*;
+    use crate::event::{KeyCode, KeyEvent, ScriptedEventSource};
+    use std::time::Instant;
+
+    /// Event source that takes a long time to deliver each event, like a
+    /// slow application would.
+    struct SlowEventSource {
+        source: ScriptedEventSource,
+        delay: Duration,
+        extra: Duration,
+    }
+
+    impl EventSource for SlowEventSource {
+        fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>, std::io::Error> {
+            self.extra += self.delay;
+            self.source.next_event(timeout)
+        }
+
+        fn now(&self) -> Instant {
+            self.source.now() + self.extra
+        }
+    }
+
+    fn run_until_exit<SourceT: EventSource>(source: &mut SourceT) -> Vec<Event> {
+        let mut events = vec![];
+        EventLoop::new(Duration::from_millis(100))
+            .run(source, |event| {
+                events.push(event);
+                if event == Event::Key(KeyEvent::new(KeyCode::Escape)) {
+                    Flow::Exit
+                } else {
+                    Flow::Continue
+                }
+            })
+            .unwrap();
+        events
+    }
+
+    #[test]
+    fn delivers_input_between_ticks() {
+        let mut source = ScriptedEventSource::new();
+        let a = Event::Key(KeyEvent::new(KeyCode::Char('a')));
+        source.push_at(Duration::from_millis(150), a);
+        source.push_at(Duration::from_millis(250), Event::Key(KeyEvent::new(KeyCode::Escape)));
+
+        let events = run_until_exit(&mut source);
+
+        assert_eq!(
+            vec![Event::Tick, a, Event::Tick, Event::Key(KeyEvent::new(KeyCode::Escape))],
+            events
+        );
+    }
+
+    #[test]
+    fn delivers_input_to_slow_applications() {
+        let mut source = SlowEventSource {
+            source: ScriptedEventSource::new(),
+            delay: Duration::from_millis(150),
+            extra: Duration::ZERO,
+        };
+        source.source.push(Event::Key(KeyEvent::new(KeyCode::Escape)));
+
+        let events = run_until_exit(&mut source);
+
+        assert_eq!(vec![Event::Key(KeyEvent::new(KeyCode::Escape))], events);
+    }
+
+    #[test]
+    fn skips_ticks_when_it_falls_far_behind() {
+        let mut source = SlowEventSource {
+            source: ScriptedEventSource::new(),
+            delay: Duration::from_secs(10),
+            extra: Duration::ZERO,
+        };
+        source
+            .source
+            .push_at(Duration::from_millis(500), Event::Key(KeyEvent::new(KeyCode::Escape)));
+
+        let events = run_until_exit(&mut source);
+
+        // Without skipping, a hundred ticks would be delivered for every
+        // input event that is read.
+        let ticks = events.iter().filter(|event| **event == Event::Tick).count();
+        assert!(ticks <= 6, "{} ticks were delivered", ticks);
+    }
+
+    #[test]
+    fn the_tick_interval_can_be_changed_while_running() {
+        let mut source = ScriptedEventSource::new();
+        source.push_at(Duration::from_millis(1100), Event::Key(KeyEvent::new(KeyCode::Escape)));
+
+        let event_loop = EventLoop::new(Duration::from_millis(100));
+        let mut ticks = 0;
+        event_loop
+            .run(&mut source, |event| {
+                if event != Event::Tick {
+                    return Flow::Exit;
+                }
+                ticks += 1;
+                if ticks == 2 {
+                    event_loop.set_tick_interval(Duration::from_millis(400));
+                }
+                Flow::Continue
+            })
+            .unwrap();
+
+        // Two ticks 100ms apart, then two more 400ms apart.
+        assert_eq!(4, ticks);
+    }
+}
@@ -0,0 +1,114 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use super::KeyEvent;
+
+/// A map from key presses to an application's actions.
+///
+/// Each binding has a description, so that the bindings can be listed in
+/// help text. Bindings are listed in the order that they were added.
+///
+/// ## Example
+///
+/// ```
+/// use tui::event::{KeyBindings, KeyCode, KeyEvent};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Action {
+///     Quit,
+///     Pause,
+/// }
+///
+/// let mut bindings = KeyBindings::new();
+/// bindings.bind(KeyEvent::with_ctrl(KeyCode::Char('c')), Action::Quit, "Quit");
+/// bindings.bind(KeyEvent::new(KeyCode::Char(' ')), Action::Pause, "Pause");
+///
+/// assert_eq!(Some(&Action::Pause), bindings.action(&KeyEvent::new(KeyCode::Char(' '))));
+/// assert_eq!(None, bindings.action(&KeyEvent::new(KeyCode::Char('c'))));
+/// ```
+pub struct KeyBindings<ActionT> {
+    bindings: Vec<(KeyEvent, ActionT, String)>,
+}
+
+impl<ActionT> Default for KeyBindings<ActionT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<ActionT> KeyBindings<ActionT> {
+    pub fn new() -> Self {
+        KeyBindings { bindings: vec![] }
+    }
+
+    /// Bind a key press to an action. If the key press is already bound, the
+    /// old binding is replaced.
+    ///
+    /// ## Arguments
+    ///
+    /// * `key`: The key press.
+    /// * `action`: The action to take when the key is pressed.
+    /// * `description`: A description of the action, for help text.
+    pub fn bind(&mut self, key: KeyEvent, action: ActionT, description: &str) {
+        self.bindings.retain(|(bound_key, _, _)| *bound_key != key);
+        self.bindings.push((key, action, description.to_string()));
+    }
+
+    /// Get the action that a key press is bound to, if any.
+    pub fn action(&self, key: &KeyEvent) -> Option<&ActionT> {
+        self.bindings.iter().find(|(bound_key, _, _)| bound_key == key).map(|(_, action, _)| action)
+    }
+
+    /// Iterate over the bindings, as key presses, actions and descriptions.
+    pub fn iter(&self) -> impl Iterator<Item = (&KeyEvent, &ActionT, &str)> {
+        self.bindings.iter().map(|(key, action, description)| (key, action, description.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod key_bindings_tests {
+    use super::*;
+    use crate::event::KeyCode;
+
+    #[test]
+    fn rebinding_a_key_replaces_its_action() {
+        let mut bindings = KeyBindings::new();
+        bindings.bind(KeyEvent::new(KeyCode::Char('q')), 1, "One");
+        bindings.bind(KeyEvent::new(KeyCode::Char('x')), 2, "Two");
+        bindings.bind(KeyEvent::new(KeyCode::Char('q')), 3, "Three");
+
+        assert_eq!(Some(&3), bindings.action(&KeyEvent::new(KeyCode::Char('q'))));
+        assert_eq!(
+            vec!["Two", "Three"],
+            bindings.iter().map(|(_, _, description)| description).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn modifier_keys_are_part_of_the_binding() {
+        let mut bindings = KeyBindings::new();
+        bindings.bind(KeyEvent::with_ctrl(KeyCode::Char('c')), "quit", "Quit");
+
+        assert_eq!(Some(&"quit"), bindings.action(&KeyEvent::with_ctrl(KeyCode::Char('c'))));
+        assert_eq!(None, bindings.action(&KeyEvent::new(KeyCode::Char('c'))));
+    }
+}
@@ -0,0 +1,336 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use xy_utils::Point;
+
+use super::{Event, MouseButton, MouseEvent, MouseEventKind};
+use crate::lowlevel::Plotter;
+use crate::Component;
+
+/// Passes mouse events on to the components under the mouse.
+///
+/// Each component is given mouse events (through `Component::handle_event()`)
+/// with positions relative to its own top-left corner. As well as the events
+/// read from the terminal, the dispatcher tells components when the mouse
+/// moves onto them (`Enter`) or off them (`Leave`), and when a button is both
+/// pressed and released over them (`Click`).
+///
+/// Once a button is pressed over a component, the drags and release of that
+/// button are sent to the same component, even if the mouse has moved off it.
+/// Their positions are clamped to the nearest edge of the component.
+///
+/// The dispatcher remembers components by their index in the list that it is
+/// given, so components should be given in the same order every time.
+///
+/// ## Example
+///
+/// ```
+/// # use tui::mock::MockPlotter as MyPlotter;
+/// use tui::components::Canvas;
+/// use tui::event::{MouseButton, MouseDispatcher, MouseEvent, MouseEventKind};
+/// use tui::Component;
+/// use xy_utils::{Dimensions, Point};
+///
+/// let mut canvas = Canvas::new(Point { x: 10, y: 5 }, Dimensions { width: 4, height: 4 });
+/// canvas.enable_mouse_input();
+///
+/// let mut dispatcher = MouseDispatcher::new();
+/// let mut components: Vec<&mut dyn Component<MyPlotter>> = vec![&mut canvas];
+/// let press = MouseEvent {
+///     kind: MouseEventKind::Down(MouseButton::Left),
+///     position: Point { x: 11, y: 7 },
+/// };
+/// assert!(dispatcher.dispatch(&press, &mut components));
+///
+/// let events = canvas.take_mouse_events();
+/// assert_eq!(MouseEventKind::Enter, events[0].kind);
+/// assert_eq!(Point { x: 1, y: 2 }, events[1].position);
+/// ```
+#[derive(Default)]
+pub struct MouseDispatcher {
+    /// The index of the component that the mouse is over.
+    hovered: Option<usize>,
+
+    /// The index of the component that a button was pressed over, and the
+    /// button.
+    pressed: Option<(usize, MouseButton)>,
+}
+
+impl MouseDispatcher {
+    pub fn new() -> Self {
+        MouseDispatcher { hovered: None, pressed: None }
+    }
+
+    /// Pass a mouse event on to the components that it affects.
+    ///
+    /// ## Arguments
+    ///
+    /// * `event`: The mouse event, with its position in the terminal.
+    /// * `components`: The components that could receive the event. Where
+    ///   components overlap, the first one in the list is treated as being on
+    ///   top.
+    ///
+    /// ## Returns
+    ///
+    /// True if a component used the event.
+    pub fn dispatch<PlotterT: Plotter>(
+        &mut self,
+        event: &MouseEvent,
+        components: &mut [&mut dyn Component<PlotterT>],
+    ) -> bool {
+        let under_mouse =
+            components.iter().position(|component| component.area().contains(event.position));
+
+        if under_mouse != self.hovered {
+            if let Some(component) = self.hovered.and_then(|index| components.get_mut(index)) {
+                Self::send(*component, MouseEventKind::Leave, event.position);
+            }
+            if let Some(index) = under_mouse {
+                Self::send(components[index], MouseEventKind::Enter, event.position);
+            }
+            self.hovered = under_mouse;
+        }
+
+        let pressed = match event.kind {
+            MouseEventKind::Down(button) => {
+                self.pressed = under_mouse.map(|index| (index, button));
+                None
+            }
+            MouseEventKind::Drag(_) => self.pressed,
+            MouseEventKind::Up(_) => self.pressed.take(),
+            _ => None,
+        }
+        .filter(|(index, _)| *index < components.len());
+
+        match pressed {
+            Some((index, button)) => {
+                let mut used = Self::send(components[index], event.kind, event.position);
+                if event.kind == MouseEventKind::Up(button) && under_mouse == Some(index) {
+                    used |= Self::send(
+                        components[index],
+                        MouseEventKind::Click(button),
+                        event.position,
+                    );
+                }
+                used
+            }
+            None => under_mouse
+                .is_some_and(|index| Self::send(components[index], event.kind, event.position)),
+        }
+    }
+
+    /// Send a mouse event to a component, relative to its top-left corner.
+    fn send<PlotterT: Plotter>(
+        component: &mut dyn Component<PlotterT>,
+        kind: MouseEventKind,
+        position: Point,
+    ) -> bool {
+        let area = component.area();
+        let position = Point {
+            x: position.x.saturating_sub(area.position.x).min(area.size.width.saturating_sub(1)),
+            y: position.y.saturating_sub(area.position.y).min(area.size.height.saturating_sub(1)),
+        };
+        component.handle_event(&Event::Mouse(MouseEvent { kind, position }))
+    }
+}
+
+#[cfg(test)]
+mod mouse_dispatcher_tests {
+    use super::*;
+    use crate::layout::Rect;
+    use crate::mock::MockPlotter;
+    use xy_utils::Dimensions;
+
+    /// Component that records the mouse events that it is given.
+    struct MouseRecorder {
+        area: Rect,
+        events: Vec<MouseEvent>,
+    }
+
+    impl MouseRecorder {
+        fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+            MouseRecorder {
+                area: Rect::new(Point { x, y }, Dimensions { width, height }),
+                events: vec![],
+            }
+        }
+
+        fn take(&mut self) -> Vec<(MouseEventKind, usize, usize)> {
+            self.events
+                .drain(..)
+                .map(|event| (event.kind, event.position.x, event.position.y))
+                .collect()
+        }
+    }
+
+    impl Component<MockPlotter> for MouseRecorder {
+        fn position(&self) -> Point {
+            self.area.position
+        }
+
+        fn size(&self) -> Dimensions {
+            self.area.size
+        }
+
+        fn set_area(&mut self, area: Rect) {
+            self.area = area;
+        }
+
+        fn render(&mut self, _plotter: &mut MockPlotter) -> Result<(), std::io::Error> {
+            Ok(())
+        }
+
+        fn is_dirty(&self) -> bool {
+            false
+        }
+
+        fn mark_dirty(&mut self) {}
+
+        fn handle_event(&mut self, event: &Event) -> bool {
+            match event {
+                Event::Mouse(mouse_event) => {
+                    self.events.push(*mouse_event);
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    fn dispatch(
+        dispatcher: &mut MouseDispatcher,
+        kind: MouseEventKind,
+        x: usize,
+        y: usize,
+        recorders: &mut [&mut MouseRecorder],
+    ) -> bool {
+        let mut components: Vec<&mut dyn Component<MockPlotter>> = recorders
+            .iter_mut()
+            .map(|recorder| &mut **recorder as &mut dyn Component<MockPlotter>)
+            .collect();
+        dispatcher.dispatch(&MouseEvent { kind, position: Point { x, y } }, &mut components)
+    }
+
+    #[test]
+    fn sends_events_relative_to_the_top_component_under_the_mouse() {
+        let mut dispatcher = MouseDispatcher::new();
+        let mut top = MouseRecorder::new(2, 2, 2, 2);
+        let mut bottom = MouseRecorder::new(0, 0, 10, 10);
+
+        let used =
+            dispatch(&mut dispatcher, MouseEventKind::ScrollUp, 3, 2, &mut [&mut top, &mut bottom]);
+
+        assert!(used);
+        assert_eq!(
+            vec![(MouseEventKind::Enter, 1, 0), (MouseEventKind::ScrollUp, 1, 0)],
+            top.take()
+        );
+        assert!(bottom.take().is_empty());
+    }
+
+    #[test]
+    fn events_outside_every_component_are_not_used() {
+        let mut dispatcher = MouseDispatcher::new();
+        let mut recorder = MouseRecorder::new(0, 0, 2, 2);
+
+        assert!(!dispatch(&mut dispatcher, MouseEventKind::Moved, 2, 0, &mut [&mut recorder]));
+        assert!(recorder.take().is_empty());
+    }
+
+    #[test]
+    fn tells_components_when_the_mouse_moves_on_and_off_them() {
+        let mut dispatcher = MouseDispatcher::new();
+        let mut left = MouseRecorder::new(0, 0, 2, 1);
+        let mut right = MouseRecorder::new(2, 0, 2, 1);
+
+        dispatch(&mut dispatcher, MouseEventKind::Moved, 0, 0, &mut [&mut left, &mut right]);
+        dispatch(&mut dispatcher, MouseEventKind::Moved, 1, 0, &mut [&mut left, &mut right]);
+        dispatch(&mut dispatcher, MouseEventKind::Moved, 2, 0, &mut [&mut left, &mut right]);
+        dispatch(&mut dispatcher, MouseEventKind::Moved, 5, 0, &mut [&mut left, &mut right]);
+
+        assert_eq!(
+            vec![
+                (MouseEventKind::Enter, 0, 0),
+                (MouseEventKind::Moved, 0, 0),
+                (MouseEventKind::Moved, 1, 0),
+                (MouseEventKind::Leave, 1, 0),
+            ],
+            left.take()
+        );
+        assert_eq!(
+            vec![
+                (MouseEventKind::Enter, 0, 0),
+                (MouseEventKind::Moved, 0, 0),
+                (MouseEventKind::Leave, 1, 0),
+            ],
+            right.take()
+        );
+    }
+
+    #[test]
+    fn clicks_need_the_button_to_be_released_over_the_same_component() {
+        let mut dispatcher = MouseDispatcher::new();
+        let mut left = MouseRecorder::new(0, 0, 2, 1);
+        let mut right = MouseRecorder::new(2, 0, 2, 1);
+        let down = MouseEventKind::Down(MouseButton::Left);
+        let up = MouseEventKind::Up(MouseButton::Left);
+
+        dispatch(&mut dispatcher, down, 1, 0, &mut [&mut left, &mut right]);
+        dispatch(&mut dispatcher, up, 0, 0, &mut [&mut left, &mut right]);
+        assert_eq!(
+            vec![
+                (MouseEventKind::Enter, 1, 0),
+                (down, 1, 0),
+                (up, 0, 0),
+                (MouseEventKind::Click(MouseButton::Left), 0, 0),
+            ],
+            left.take()
+        );
+
+        dispatch(&mut dispatcher, down, 1, 0, &mut [&mut left, &mut right]);
+        dispatch(&mut dispatcher, up, 2, 0, &mut [&mut left, &mut right]);
+        assert_eq!(vec![(down, 1, 0), (MouseEventKind::Leave, 1, 0), (up, 1, 0)], left.take());
+        assert_eq!(vec![(MouseEventKind::Enter, 0, 0)], right.take());
+    }
+
+    #[test]
+    fn drags_stay_with_the_component_that_the_button_was_pressed_over() {
+        let mut dispatcher = MouseDispatcher::new();
+        let mut left = MouseRecorder::new(0, 0, 2, 2);
+        let mut right = MouseRecorder::new(2, 0, 2, 2);
+        let drag = MouseEventKind::Drag(MouseButton::Right);
+
+        dispatch(
+            &mut dispatcher,
+            MouseEventKind::Down(MouseButton::Right),
+            0,
+            0,
+            &mut [&mut left, &mut right],
+        );
+        left.take();
+        dispatch(&mut dispatcher, drag, 1, 1, &mut [&mut left, &mut right]);
+        dispatch(&mut dispatcher, drag, 3, 1, &mut [&mut left, &mut right]);
+
+        assert_eq!(vec![(drag, 1, 1), (MouseEventKind::Leave, 1, 1), (drag, 1, 1)], left.take());
+        assert_eq!(vec![(MouseEventKind::Enter, 1, 1)], right.take());
+    }
+}
@@ -0,0 +1,435 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Layouts divide an area of the terminal between components.
+//!
+//! A `Layout` splits a `Rect` into a row (`Direction::Horizontal`) or a column
+//! (`Direction::Vertical`) of smaller rects, sized by a list of constraints.
+//! Layouts are nested by splitting one of the resulting rects with another
+//! layout. Since layouts only calculate positions and sizes, they can simply
+//! be split again to recalculate the areas when the terminal is resized.
+//!
+//! ## Example
+//!
+//! ```
+//! use tui::layout::{Constraint, Layout, Padding, Rect};
+//! use xy_utils::{Dimensions, Point};
+//!
+//! let screen = Rect::new(Point { x: 0, y: 0 }, Dimensions { width: 80, height: 24 });
+//!
+//! // A title bar, a main area, and a status bar.
+//! let rows = Layout::vertical(vec![Constraint::Fixed(1), Constraint::Fill(1), Constraint::Fixed(1)])
+//!     .split(screen);
+//!
+//! // The main area is split into a sidebar and a padded content area.
+//! let columns = Layout::horizontal(vec![Constraint::Percentage(25), Constraint::Fill(1)])
+//!     .with_padding(Padding::uniform(1))
+//!     .split(rows[1]);
+//!
+//! assert_eq!(Rect::new(Point { x: 1, y: 2 }, Dimensions { width: 19, height: 20 }), columns[0]);
+//! assert_eq!(Rect::new(Point { x: 20, y: 2 }, Dimensions { width: 59, height: 20 }), columns[1]);
+//! ```
+
+use xy_utils::{Dimensions, Point};
+
+/// A rectangular area of the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    /// The top-left corner of the area.
+    pub position: Point,
+
+    /// The size of the area.
+    pub size: Dimensions,
+}
+
+impl Rect {
+    pub fn new(position: Point, size: Dimensions) -> Self {
+        Rect { position, size }
+    }
+
+    /// Check whether a point is inside the rect.
+    pub fn contains(&self, point: Point) -> bool {
+        (self.position.x..self.position.x + self.size.width).contains(&point.x)
+            && (self.position.y..self.position.y + self.size.height).contains(&point.y)
+    }
+
+    /// Check whether two rects share any cells.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.position.x < other.position.x + other.size.width
+            && other.position.x < self.position.x + self.size.width
+            && self.position.y < other.position.y + other.size.height
+            && other.position.y < self.position.y + self.size.height
+    }
+
+    /// Get an area in the middle of the rect.
+    ///
+    /// ## Arguments
+    ///
+    /// * `size`: The size of the area. It is shrunk to fit inside the rect if
+    ///   it is too large.
+    pub fn centered(&self, size: Dimensions) -> Rect {
+        let size = Dimensions {
+            width: size.width.min(self.size.width),
+            height: size.height.min(self.size.height),
+        };
+        Rect {
+            position: Point {
+                x: self.position.x + (self.size.width - size.width) / 2,
+                y: self.position.y + (self.size.height - size.height) / 2,
+            },
+            size,
+        }
+    }
+
+    /// Get the area that is left inside the rect once padding is removed.
+    ///
+    /// ## Arguments
+    ///
+    /// * `padding`: The padding to remove from each edge of the rect. If the
+    ///   padding is larger than the rect, the inner area is empty.
+    pub fn inner(&self, padding: Padding) -> Rect {
+        let left = padding.left.min(self.size.width);
+        let top = padding.top.min(self.size.height);
+        Rect {
+            position: Point { x: self.position.x + left, y: self.position.y + top },
+            size: Dimensions {
+                width: self.size.width.saturating_sub(padding.left + padding.right),
+                height: self.size.height.saturating_sub(padding.top + padding.bottom),
+            },
+        }
+    }
+}
+
+/// Empty space left around the edges of an area.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Padding {
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+    pub left: usize,
+}
+
+impl Padding {
+    /// Create padding that is the same on every edge.
+    pub fn uniform(padding: usize) -> Self {
+        Padding { top: padding, right: padding, bottom: padding, left: padding }
+    }
+}
+
+/// The direction that a layout places its areas in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Areas are placed left to right.
+    Horizontal,
+
+    /// Areas are placed top to bottom.
+    Vertical,
+}
+
+/// How much space an area of a layout takes up, in the layout's direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    /// A fixed number of cells.
+    Fixed(usize),
+
+    /// A percentage of the space available to the layout.
+    Percentage(usize),
+
+    /// A share of the space that is left over once the fixed and percentage
+    /// areas have been sized. The value is the weight of the share, so an
+    /// area with `Fill(2)` is twice the size of an area with `Fill(1)`.
+    Fill(usize),
+}
+
+/// Splits an area into a row or column of smaller areas.
+///
+/// Fixed and percentage areas are sized first, in order. If they need more
+/// space than is available, the areas at the end are shrunk (possibly to
+/// nothing). Whatever space remains is shared between the fill areas.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    direction: Direction,
+    constraints: Vec<Constraint>,
+    padding: Padding,
+}
+
+impl Layout {
+    /// Create a layout.
+    ///
+    /// ## Arguments
+    ///
+    /// * `direction`: The direction to place the areas in.
+    /// * `constraints`: One constraint for each area of the layout.
+    pub fn new(direction: Direction, constraints: Vec<Constraint>) -> Self {
+        Layout { direction, constraints, padding: Padding::default() }
+    }
+
+    /// Create a layout that places its areas left to right.
+    pub fn horizontal(constraints: Vec<Constraint>) -> Self {
+        Self::new(Direction::Horizontal, constraints)
+    }
+
+    /// Create a layout that places its areas top to bottom.
+    pub fn vertical(constraints: Vec<Constraint>) -> Self {
+        Self::new(Direction::Vertical, constraints)
+    }
+
+    /// Leave some empty space around the edges of the split area.
+    pub fn with_padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Split an area.
+    ///
+    /// ## Arguments
+    ///
+    /// * `area`: The area to split.
+    ///
+    /// ## Returns
+    ///
+    /// One rect for each of the layout's constraints, in the same order.
+    pub fn split(&self, area: Rect) -> Vec<Rect> {
+        let area = area.inner(self.padding);
+        let available = match self.direction {
+            Direction::Horizontal => area.size.width,
+            Direction::Vertical => area.size.height,
+        };
+
+        // Size the fixed and percentage areas.
+        let mut remaining = available;
+        let mut lengths: Vec<usize> = self
+            .constraints
+            .iter()
+            .map(|constraint| {
+                let wanted = match constraint {
+                    Constraint::Fixed(length) => *length,
+                    Constraint::Percentage(percentage) => available * percentage / 100,
+                    Constraint::Fill(_) => 0,
+                };
+                let length = wanted.min(remaining);
+                remaining -= length;
+                length
+            })
+            .collect();
+
+        // Share out what is left between the fill areas. Any cells that can't
+        // be shared evenly go to the first fill areas.
+        let total_weight: usize = self.fill_weights().sum();
+        let mut leftover = remaining;
+        for (length, constraint) in lengths.iter_mut().zip(&self.constraints) {
+            if let Constraint::Fill(weight) = constraint {
+                *length = (remaining * weight).checked_div(total_weight).unwrap_or(0);
+                leftover -= *length;
+            }
+        }
+        for (length, constraint) in lengths.iter_mut().zip(&self.constraints) {
+            if leftover > 0 && matches!(constraint, Constraint::Fill(weight) if *weight > 0) {
+                *length += 1;
+                leftover -= 1;
+            }
+        }
+
+        let mut offset = 0;
+        lengths
+            .into_iter()
+            .map(|length| {
+                let rect = match self.direction {
+                    Direction::Horizontal => Rect::new(
+                        Point { x: area.position.x + offset, ..area.position },
+                        Dimensions { width: length, ..area.size },
+                    ),
+                    Direction::Vertical => Rect::new(
+                        Point { y: area.position.y + offset, ..area.position },
+                        Dimensions { height: length, ..area.size },
+                    ),
+                };
+                offset += length;
+                rect
+            })
+            .collect()
+    }
+
+    fn fill_weights(&self) -> impl Iterator<Item = usize> + '_ {
+        self.constraints.iter().filter_map(|constraint| match constraint {
+            Constraint::Fill(weight) => Some(*weight),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod layout_tests {
+    use super::*;
+
+    fn screen(width: usize, height: usize) -> Rect {
+        Rect::new(Point { x: 0, y: 0 }, Dimensions { width, height })
+    }
+
+    /// Get the lengths of the split areas in the layout's direction.
+    fn lengths(layout: &Layout, area: Rect) -> Vec<usize> {
+        layout
+            .split(area)
+            .iter()
+            .map(|rect| match layout.direction {
+                Direction::Horizontal => rect.size.width,
+                Direction::Vertical => rect.size.height,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn places_areas_side_by_side() {
+        let layout = Layout::horizontal(vec![Constraint::Fixed(3), Constraint::Fixed(4)]);
+
+        let rects =
+            layout.split(Rect::new(Point { x: 2, y: 1 }, Dimensions { width: 10, height: 5 }));
+
+        assert_eq!(
+            vec![
+                Rect::new(Point { x: 2, y: 1 }, Dimensions { width: 3, height: 5 }),
+                Rect::new(Point { x: 5, y: 1 }, Dimensions { width: 4, height: 5 }),
+            ],
+            rects
+        );
+    }
+
+    #[test]
+    fn places_areas_above_and_below() {
+        let layout = Layout::vertical(vec![Constraint::Fixed(1), Constraint::Fill(1)]);
+
+        let rects = layout.split(screen(10, 5));
+
+        assert_eq!(
+            vec![
+                Rect::new(Point { x: 0, y: 0 }, Dimensions { width: 10, height: 1 }),
+                Rect::new(Point { x: 0, y: 1 }, Dimensions { width: 10, height: 4 }),
+            ],
+            rects
+        );
+    }
+
+    #[test]
+    fn sizes_percentages_from_the_available_space() {
+        let layout = Layout::horizontal(vec![
+            Constraint::Percentage(50),
+            Constraint::Percentage(25),
+            Constraint::Fill(1),
+        ]);
+
+        assert_eq!(vec![5, 2, 4], lengths(&layout, screen(11, 1)));
+    }
+
+    #[test]
+    fn shares_the_remaining_space_between_fill_areas_by_weight() {
+        let layout = Layout::horizontal(vec![
+            Constraint::Fill(1),
+            Constraint::Fixed(2),
+            Constraint::Fill(2),
+        ]);
+
+        assert_eq!(vec![3, 2, 6], lengths(&layout, screen(11, 1)));
+        assert_eq!(vec![4, 2, 6], lengths(&layout, screen(12, 1)));
+    }
+
+    #[test]
+    fn shrinks_the_last_areas_when_there_is_not_enough_space() {
+        let layout = Layout::vertical(vec![
+            Constraint::Fixed(3),
+            Constraint::Fill(1),
+            Constraint::Fixed(3),
+            Constraint::Fixed(3),
+        ]);
+
+        assert_eq!(vec![3, 0, 2, 0], lengths(&layout, screen(1, 5)));
+    }
+
+    #[test]
+    fn leaves_padding_around_the_areas() {
+        let layout = Layout::horizontal(vec![Constraint::Fill(1)]).with_padding(Padding {
+            top: 1,
+            right: 2,
+            bottom: 3,
+            left: 4,
+        });
+
+        assert_eq!(
+            vec![Rect::new(Point { x: 4, y: 1 }, Dimensions { width: 4, height: 6 })],
+            layout.split(screen(10, 10))
+        );
+    }
+
+    #[test]
+    fn padding_larger_than_the_area_leaves_nothing() {
+        let inner = screen(3, 3).inner(Padding::uniform(2));
+
+        assert_eq!(Dimensions { width: 0, height: 0 }, inner.size);
+    }
+
+    #[test]
+    fn can_be_nested() {
+        let rows =
+            Layout::vertical(vec![Constraint::Fixed(2), Constraint::Fill(1)]).split(screen(8, 6));
+        let columns =
+            Layout::horizontal(vec![Constraint::Fill(1), Constraint::Fill(1)]).split(rows[1]);
+
+        assert_eq!(
+            vec![
+                Rect::new(Point { x: 0, y: 2 }, Dimensions { width: 4, height: 4 }),
+                Rect::new(Point { x: 4, y: 2 }, Dimensions { width: 4, height: 4 }),
+            ],
+            columns
+        );
+    }
+
+    #[test]
+    fn can_be_recalculated_for_a_new_size() {
+        let layout = Layout::vertical(vec![Constraint::Fixed(1), Constraint::Fill(1)]);
+
+        assert_eq!(vec![1, 9], lengths(&layout, screen(10, 10)));
+        assert_eq!(vec![1, 19], lengths(&layout, screen(10, 20)));
+    }
+
+    #[test]
+    fn centres_areas_and_shrinks_them_to_fit() {
+        let area = Rect::new(Point { x: 2, y: 1 }, Dimensions { width: 10, height: 5 });
+
+        assert_eq!(
+            Rect::new(Point { x: 5, y: 2 }, Dimensions { width: 4, height: 3 }),
+            area.centered(Dimensions { width: 4, height: 3 })
+        );
+        assert_eq!(area, area.centered(Dimensions { width: 20, height: 20 }));
+    }
+
+    #[test]
+    fn finds_overlapping_areas() {
+        let area = Rect::new(Point { x: 2, y: 2 }, Dimensions { width: 2, height: 2 });
+        let overlapping = Rect::new(Point { x: 3, y: 0 }, Dimensions { width: 5, height: 3 });
+        let beside = Rect::new(Point { x: 4, y: 2 }, Dimensions { width: 1, height: 1 });
+
+        assert!(area.intersects(&overlapping));
+        assert!(overlapping.intersects(&area));
+        assert!(!area.intersects(&beside));
+        assert!(area.contains(Point { x: 3, y: 3 }));
+        assert!(!area.contains(Point { x: 4, y: 3 }));
+    }
+}
@@ -49,11 +49,29 @@
 //! plotter.flush().unwrap();
 //! ```
 //!
+//! Every plot is sent to the terminal by the `DefaultPlotter`, even if the
+//! terminal already shows the same content. Wrapping it in a `BufferedPlotter`
+//! means that only the parts of the screen that have changed are sent when
+//! the plotter is flushed:
+//!
+//! ```no_run
+//! use tui::{BufferedPlotter, DefaultPlotter};
+//!
+//! let mut plotter = BufferedPlotter::new(DefaultPlotter::create_from_stdout());
+//! ```
+//!
+//! The `DefaultPlotter` detects the colors that the terminal can show from the
+//! environment (`NO_COLOR`, `COLORTERM` and `TERM`), and converts RGB colors to
+//! the nearest color in the terminal's palette. The detected `ColorSupport` can
+//! be overridden with `DefaultPlotter::set_color_support()`.
+//!
 //! ## Components
 //!
 //! Components are objects that provide abstractions for commonly used TUI widgets.
+//! Every component implements the `Component` trait, which is used to render
+//! it, and to find out whether it has changed since it was last rendered.
 //!
-//! There are currently four main types of widget:
+//! These are the main types of widget:
 //!
 //! ### Canvas
 //!
@@ -61,7 +79,7 @@
 //! point and size:
 //!
 //! ```no_run
-//! use tui::{DefaultPlotter, Paintbrush, Plotter};
+//! use tui::{Component, DefaultPlotter, Paintbrush, Plotter};
 //! use tui::components::Canvas;
 //! use xy_utils::{Dimensions, Point};
 //!
@@ -79,12 +97,12 @@
 //! A basic text output box:
 //!
 //! ```no_run
-//! use tui::{DefaultPlotter, Paintbrush, Plotter};
+//! use tui::{Component, DefaultPlotter, Paintbrush, Plotter};
 //! use tui::components::TextLabel;
 //! use xy_utils::{Dimensions, Point};
 //!
 //! let mut plotter = DefaultPlotter::create_from_stdout();
-//! let label = TextLabel::new(
+//! let mut label = TextLabel::new(
 //!     Paintbrush::create_default(),
 //!     Point { x: 1, y: 2 },
 //!     Dimensions { width: 3, height: 1 },
@@ -99,12 +117,12 @@
 //! result in it being truncated.
 //!
 //! ```no_run
-//! # use tui::{DefaultPlotter, Paintbrush, Plotter};
+//! # use tui::{Component, DefaultPlotter, Paintbrush, Plotter};
 //! # use tui::components::TextLabel;
 //! # use xy_utils::{Dimensions, Point};
 //
 //! let mut plotter = DefaultPlotter::create_from_stdout();
-//! let label = TextLabel::new(
+//! let mut label = TextLabel::new(
 //!     Paintbrush::create_default(),
 //!     Point { x: 1, y: 2 },
 //!     Dimensions { width: 6, height: 1 },
@@ -126,11 +144,60 @@
 //!
 //! An extremely simple widget that draws a border around a portion of the
 //! terminal. Can be used to surround any other widget with a border.
+//!
+//! ### Chart
+//!
+//! A scrolling time-series chart with auto-scaling axes. The first series is
+//! drawn as a bar chart, and any further series are drawn as overlays.
+//!
+//! ### Dialog, Menu and Help
+//!
+//! Bordered boxes with a title that are drawn over the rest of the user
+//! interface: a message with a row of buttons, a scrolling list to choose an
+//! item from, and a list of keys with what they do. Draw them last with
+//! `render_layers()`, which redraws any component they cover, and use
+//! `redraw_background()` to uncover the components again when they close.
+//!
+//! ### FileBrowser
+//!
+//! A scrolling list of the files in a directory, with a thumbnail preview of
+//! the selected file drawn on a `Canvas`. The application reads the files,
+//! and gives the browser the preview to draw.
+//!
+//! ## Layouts
+//!
+//! Rather than positioning every component by hand, the `layout` module can
+//! split the terminal into rows and columns of areas, which are then given to
+//! components with `Component::set_area()`.
+//!
+//! ## Events
+//!
+//! The `event` module turns terminal input into keyboard, mouse, resize and
+//! tick events. An `EventLoop` delivers ticks at a fixed rate, with input in
+//! between, and a `KeyBindings` map turns key presses into an application's
+//! actions. Events are read from an `EventSource`, which is the terminal for a
+//! real application, or a `ScriptedEventSource` with a virtual clock in tests.
+//!
+//! Mouse events are only read once `DefaultPlotter::enable_mouse_capture()`
+//! has been called, so that the terminal can still be used to select text in
+//! applications that do not use the mouse. A
+//! `MouseDispatcher` passes mouse events to the components under the mouse,
+//! relative to each component's top-left corner, and tells components when
+//! the mouse moves onto or off them and when they are clicked.
 
+mod buffered_plotter;
+mod color_support;
+mod component;
 pub mod components;
+pub mod event;
+pub mod layout;
 mod lowlevel;
+mod screen_plotter;
 
 // Re-export the publicly interesting types so that the user doesn't have to
 // navigate the individual sub-modules.
 
+pub use buffered_plotter::BufferedPlotter;
+pub use color_support::ColorSupport;
+pub use component::{redraw_background, render_layers, Component};
 pub use lowlevel::{mock, Color, DefaultPlotter, Paintbrush, Plotter};
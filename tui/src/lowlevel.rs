@@ -23,7 +23,7 @@
 use std::fmt::Display;
 use std::io::Write;
 
-use crossterm;
+use crate::ColorSupport;
 use xy_utils::{Dimensions, Point};
 
 /// Enumeration of colors that can be applied to the plotters paintbrush.
@@ -119,7 +119,7 @@ pub enum Color {
 ///     bold: true
 /// };
 /// ```
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Paintbrush {
     /// Foreground color.
     pub fg: Color,
@@ -140,6 +140,18 @@ impl Paintbrush {
     pub fn create_default() -> Paintbrush {
         Paintbrush { fg: Color::Unset, bg: Color::Unset, bold: false }
     }
+
+    /// Get the paintbrush with its foreground and background colors swapped,
+    /// for highlighting (e.g. a cursor or a selected item). Unset colors are
+    /// treated as black on white, so that the highlight is always visible.
+    pub fn inverted(&self) -> Paintbrush {
+        let or = |color: &Color, unset| if *color == Color::Unset { unset } else { color.clone() };
+        Paintbrush {
+            fg: or(&self.bg, Color::Black),
+            bg: or(&self.fg, Color::White),
+            bold: self.bold,
+        }
+    }
 }
 
 /// The Plotter trait defines the implementation skeleton for an object that can
@@ -185,12 +197,12 @@ impl Paintbrush {
 ///
 /// ```
 /// # use tui::mock::MockPlotter as MyPlotter;
-/// use tui::{Plotter, Paintbrush, Color};
+/// use tui::{Component, Plotter, Paintbrush, Color};
 /// use tui::components::TextLabel;
 /// use xy_utils::{Dimensions, Point};
 ///
 /// let mut plotter = MyPlotter::new();
-/// let label = TextLabel::new(
+/// let mut label = TextLabel::new(
 ///     Paintbrush::create_default(),
 ///     Point { x: 1, y: 2 },
 ///     Dimensions {
@@ -264,9 +276,9 @@ pub trait Plotter {
     /// ## Arguments
     ///
     /// * `location`: Where to start printing the content. Content is printed
-    ///               along the X-axis (i.e. left-to-right).
+    ///   along the X-axis (i.e. left-to-right).
     /// * `content`: Content to plot This can be any value implementing the
-    ///              std::fmt::Display trait.
+    ///   std::fmt::Display trait.
     fn plot<T: Display>(
         &mut self,
         location: Point,
@@ -288,6 +300,13 @@ pub trait Plotter {
 /// which the user can create with the `DefaultPlotter::create_from_stdout()`
 /// function.
 ///
+/// ## Colors
+///
+/// The colors that the terminal can show are detected from the environment
+/// when the plotter is created (see `ColorSupport::detect()`), and can be
+/// overridden with `set_color_support()`. RGB colors are converted to the
+/// nearest color that the terminal can show.
+///
 /// ## Examples
 ///
 /// ### Example 1: Standard usage:
@@ -305,6 +324,18 @@ pub trait Plotter {
 pub struct DefaultPlotter<OutputStream: Write> {
     /// Output stream (e.g. stdout)
     outstream: OutputStream,
+
+    /// Whether the terminal was already in raw mode before the plotter was
+    /// created, in which case it is left in raw mode when the plotter is
+    /// dropped.
+    was_raw_mode_enabled: bool,
+
+    /// The colors that the terminal can show.
+    color_support: ColorSupport,
+
+    /// Whether mouse events are being captured, in which case capturing is
+    /// stopped when the plotter is dropped.
+    is_mouse_captured: bool,
 }
 
 impl<OutputStream: Write> Plotter for DefaultPlotter<OutputStream> {
@@ -317,10 +348,18 @@ impl<OutputStream: Write> Plotter for DefaultPlotter<OutputStream> {
     #[cfg(not(tarpaulin_include))]
     fn set_paintbrush(&mut self, pb: &Paintbrush) -> Result<&mut Self, std::io::Error> {
         use crossterm::style::*;
+
+        // Without colors, a background is shown by swapping the terminal's
+        // default colors.
+        if self.color_support == ColorSupport::None {
+            let reverse =
+                if pb.bg == self::Color::Unset { Attribute::NoReverse } else { Attribute::Reverse };
+            crossterm::queue!(self.outstream, SetAttribute(reverse))?;
+        }
         crossterm::queue!(
             self.outstream,
-            SetForegroundColor(Self::convert_color_to_crossterm_val(&pb.fg)),
-            SetBackgroundColor(Self::convert_color_to_crossterm_val(&pb.bg)),
+            SetForegroundColor(self.color_support.convert(&pb.fg)),
+            SetBackgroundColor(self.color_support.convert(&pb.bg)),
             //SetAttribute(if pb.bold { Attribute::Bold } else { Attribute::NoBold })
         )?;
 
@@ -351,19 +390,54 @@ impl<OutputStream: Write> Plotter for DefaultPlotter<OutputStream> {
 impl<OutputStream: Write> Drop for DefaultPlotter<OutputStream> {
     #[cfg(not(tarpaulin_include))]
     fn drop(&mut self) {
-        // Must leave alternate screen mode.
+        // Must leave alternate screen mode, and stop capturing the mouse.
+        if self.is_mouse_captured {
+            crossterm::execute!(self.outstream, crossterm::event::DisableMouseCapture).unwrap();
+        }
         crossterm::execute!(self.outstream, crossterm::terminal::LeaveAlternateScreen).unwrap();
-        crossterm::terminal::disable_raw_mode().unwrap();
+        if !self.was_raw_mode_enabled {
+            crossterm::terminal::disable_raw_mode().unwrap();
+        }
     }
 }
 
 impl<OutputStream: Write> DefaultPlotter<OutputStream> {
     pub fn new(outstream: OutputStream) -> DefaultPlotter<OutputStream> {
-        let mut result = DefaultPlotter { outstream };
+        let was_raw_mode_enabled = crossterm::terminal::is_raw_mode_enabled().unwrap_or(false);
+        let color_support = ColorSupport::detect();
+        let mut result = DefaultPlotter {
+            outstream,
+            was_raw_mode_enabled,
+            color_support,
+            is_mouse_captured: false,
+        };
         result.reset();
         result
     }
 
+    pub fn color_support(&self) -> ColorSupport {
+        self.color_support
+    }
+
+    /// Override the colors that the terminal was detected to show.
+    ///
+    /// ## Arguments
+    ///
+    /// * `color_support`: The colors that the terminal can show.
+    pub fn set_color_support(&mut self, color_support: ColorSupport) {
+        self.color_support = color_support;
+    }
+
+    /// Capture mouse events, so that they can be read as `tui::event::Event`s.
+    /// The terminal's own handling of the mouse (such as selecting text) is
+    /// unavailable until the plotter is dropped.
+    #[cfg(not(tarpaulin_include))]
+    pub fn enable_mouse_capture(&mut self) -> Result<&mut Self, std::io::Error> {
+        crossterm::execute!(self.outstream, crossterm::event::EnableMouseCapture)?;
+        self.is_mouse_captured = true;
+        Ok(self)
+    }
+
     /// Internal function to reset the terminal before initializing the UI.
     fn reset(&mut self) {
         // This terminal command does three things:
@@ -389,37 +463,12 @@ impl<OutputStream: Write> DefaultPlotter<OutputStream> {
         self.set_paintbrush(&Paintbrush::create_default()).unwrap();
         let mut blanker = String::with_capacity(self.get_plot_area().width);
         for _ in 0..self.get_plot_area().width {
-            blanker.push_str(" ");
+            blanker.push(' ');
         }
         for i in 0..self.get_plot_area().height {
             self.plot(Point { x: 0, y: i }, &blanker).unwrap();
         }
     }
-
-    /// Helper function to convert a local crate color to a crossterm version.
-    #[cfg(not(tarpaulin_include))]
-    fn convert_color_to_crossterm_val(internal_color: &Color) -> crossterm::style::Color {
-        match internal_color {
-            Color::Unset => crossterm::style::Color::Reset,
-            Color::Rgb(r, g, b) => crossterm::style::Color::Rgb { r: *r, g: *g, b: *b },
-            Color::Black => crossterm::style::Color::Black,
-            Color::Blue => crossterm::style::Color::Blue,
-            Color::Cyan => crossterm::style::Color::Cyan,
-            Color::DarkBlue => crossterm::style::Color::DarkBlue,
-            Color::DarkCyan => crossterm::style::Color::DarkCyan,
-            Color::DarkGreen => crossterm::style::Color::DarkGreen,
-            Color::DarkGrey => crossterm::style::Color::DarkGrey,
-            Color::DarkMagenta => crossterm::style::Color::DarkMagenta,
-            Color::DarkRed => crossterm::style::Color::DarkRed,
-            Color::DarkYellow => crossterm::style::Color::DarkYellow,
-            Color::Green => crossterm::style::Color::Green,
-            Color::Grey => crossterm::style::Color::Grey,
-            Color::Magenta => crossterm::style::Color::Magenta,
-            Color::Red => crossterm::style::Color::Red,
-            Color::White => crossterm::style::Color::White,
-            Color::Yellow => crossterm::style::Color::Yellow,
-        }
-    }
 }
 
 impl DefaultPlotter<std::io::Stdout> {
@@ -434,6 +483,8 @@ impl DefaultPlotter<std::io::Stdout> {
 pub mod mock {
     use super::*;
 
+    pub use crate::screen_plotter::ScreenPlotter;
+
     /// Enumeration that holds the possible commands executed by the mock
     /// plotter.
     #[derive(Debug)]
@@ -480,6 +531,12 @@ pub mod mock {
         }
     }
 
+    impl Default for MockPlotter {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     impl MockPlotter {
         pub fn new() -> MockPlotter {
             MockPlotter { plot_area: Dimensions { height: 20, width: 20 }, command_list: vec![] }
@@ -497,7 +554,7 @@ mod paintbrush_tests {
 
         assert_eq!(Color::Unset, pb.fg);
         assert_eq!(Color::Unset, pb.bg);
-        assert_eq!(false, pb.bold);
+        assert!(!pb.bold);
     }
 }
 
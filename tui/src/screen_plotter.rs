@@ -0,0 +1,324 @@
+// MIT License
+//
+// Copyright (c) 2022 Karl Nicoll
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fmt::{Display, Write};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::lowlevel::*;
+use xy_utils::{Dimensions, Point};
+
+/// The character that marks the start of each row in an expected screen.
+const MARGIN: char = '|';
+
+/// A plotter that draws onto a virtual screen, for testing.
+///
+/// Unlike the `MockPlotter`, which records each command, the screen plotter
+/// applies the commands, so tests can check what the screen looks like rather
+/// than how it was drawn. Each grapheme is assumed to take up a single cell,
+/// and anything plotted outside of the plot area is clipped.
+///
+/// ## Snapshots
+///
+/// `assert_screen()` compares the screen with a "snapshot" of the expected
+/// text. Each row of the snapshot starts after a `|` margin, so that the
+/// snapshot can be indented along with the test code. Trailing spaces are
+/// ignored.
+///
+/// ```
+/// use tui::mock::ScreenPlotter;
+/// use tui::Plotter;
+/// use xy_utils::{Dimensions, Point};
+///
+/// let mut plotter = ScreenPlotter::new(Dimensions { width: 8, height: 3 });
+/// plotter.plot(Point { x: 1, y: 1 }, "FOO").unwrap();
+///
+/// plotter.assert_screen(
+///     "
+///     |
+///     | FOO
+///     |
+///     ",
+/// );
+/// ```
+pub struct ScreenPlotter {
+    size: Dimensions,
+    paintbrush: Paintbrush,
+
+    /// The grapheme and paintbrush of each cell, row by row.
+    cells: Vec<(String, Paintbrush)>,
+
+    /// The number of times the plotter has been flushed.
+    pub flush_count: usize,
+}
+
+impl ScreenPlotter {
+    /// Create a blank screen.
+    ///
+    /// ## Arguments
+    ///
+    /// * `size`: The size of the screen.
+    pub fn new(size: Dimensions) -> Self {
+        ScreenPlotter {
+            size,
+            paintbrush: Paintbrush::create_default(),
+            cells: vec![(" ".to_string(), Paintbrush::create_default()); size.total_area()],
+            flush_count: 0,
+        }
+    }
+
+    /// Get the grapheme and paintbrush of a single cell of the screen.
+    pub fn cell(&self, point: Point) -> (&str, &Paintbrush) {
+        let (grapheme, paintbrush) = &self.cells[point.y * self.size.width + point.x];
+        (grapheme, paintbrush)
+    }
+
+    /// Get the screen as text. Each row is a line, without trailing spaces.
+    pub fn text(&self) -> String {
+        self.rows()
+            .map(|row| row.map(|(grapheme, _)| grapheme.as_str()).collect::<String>())
+            .map(|row| row.trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Get the screen as text, with the styles of the cells.
+    ///
+    /// A style is written in braces before the cells that use it, such as
+    /// `{Red}`, `{Red on Blue}` or `{Red bold}`. The style of each row starts
+    /// as the default paintbrush, and `{}` switches back to the default
+    /// paintbrush. Rows that end with a style are closed with `{}`, so that
+    /// styled trailing spaces are not lost.
+    pub fn styled_text(&self) -> String {
+        let mut lines = vec![];
+        for row in self.rows() {
+            let mut line = String::new();
+            let mut current = Paintbrush::create_default();
+
+            // Trailing spaces are trimmed, unless they are styled.
+            let mut trimmed_len = 0;
+            let mut trimmed_style = Paintbrush::create_default();
+            for (grapheme, paintbrush) in row {
+                if *paintbrush != current {
+                    write_style(&mut line, paintbrush);
+                    current = paintbrush.clone();
+                }
+                line.push_str(grapheme);
+                if grapheme != " " || current != Paintbrush::create_default() {
+                    trimmed_len = line.len();
+                    trimmed_style = current.clone();
+                }
+            }
+            line.truncate(trimmed_len);
+            if trimmed_style != Paintbrush::create_default() {
+                write_style(&mut line, &Paintbrush::create_default());
+            }
+            lines.push(line);
+        }
+
+        lines.join("\n")
+    }
+
+    /// Check that the screen shows the expected text.
+    ///
+    /// ## Arguments
+    ///
+    /// * `expected`: The expected rows of the screen, each starting after a
+    ///   `|` margin. Lines without a margin are ignored, and trailing spaces
+    ///   are ignored.
+    ///
+    /// ## Panics
+    ///
+    /// If the screen doesn't match the expected text. The message shows the
+    /// actual screen in the same format, so that it can be copied into the
+    /// test.
+    pub fn assert_screen(&self, expected: &str) {
+        assert_snapshot(&self.text(), expected);
+    }
+
+    /// Check that the screen shows the expected styled text (see
+    /// `styled_text()`).
+    pub fn assert_styled_screen(&self, expected: &str) {
+        assert_snapshot(&self.styled_text(), expected);
+    }
+
+    fn rows(&self) -> impl Iterator<Item = std::slice::Iter<'_, (String, Paintbrush)>> {
+        self.cells.chunks(self.size.width.max(1)).take(self.size.height).map(|row| row.iter())
+    }
+}
+
+impl Plotter for ScreenPlotter {
+    fn get_plot_area(&self) -> Dimensions {
+        self.size
+    }
+
+    fn set_paintbrush(&mut self, pb: &Paintbrush) -> Result<&mut Self, std::io::Error> {
+        self.paintbrush = pb.clone();
+        Ok(self)
+    }
+
+    fn plot<T: Display>(
+        &mut self,
+        location: Point,
+        content: T,
+    ) -> Result<&mut Self, std::io::Error> {
+        if location.y >= self.size.height {
+            return Ok(self);
+        }
+
+        let row_start = location.y * self.size.width;
+        let content = content.to_string();
+        for (x, grapheme) in (location.x..self.size.width).zip(content.graphemes(true)) {
+            self.cells[row_start + x] = (grapheme.to_string(), self.paintbrush.clone());
+        }
+        Ok(self)
+    }
+
+    fn flush(&mut self) -> Result<&mut Self, std::io::Error> {
+        self.flush_count += 1;
+        Ok(self)
+    }
+}
+
+/// Write the style of a paintbrush, in the format used by `styled_text()`.
+fn write_style(output: &mut String, paintbrush: &Paintbrush) {
+    let mut parts = vec![];
+    if paintbrush.fg != Color::Unset || paintbrush.bg != Color::Unset {
+        parts.push(format!("{:?}", paintbrush.fg));
+    }
+    if paintbrush.bg != Color::Unset {
+        parts.push(format!("on {:?}", paintbrush.bg));
+    }
+    if paintbrush.bold {
+        parts.push("bold".to_string());
+    }
+    write!(output, "{{{}}}", parts.join(" ")).unwrap();
+}
+
+/// Compare screen text with a snapshot of the expected text.
+fn assert_snapshot(actual: &str, expected: &str) {
+    let expected: Vec<&str> = expected
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix(MARGIN))
+        .map(str::trim_end)
+        .collect();
+    let actual: Vec<&str> = actual.split('\n').collect();
+
+    if actual != expected {
+        let format_rows = |rows: &[&str]| {
+            rows.iter().map(|row| format!("    {}{}\n", MARGIN, row)).collect::<String>()
+        };
+        panic!(
+            "The screen does not match the snapshot.\n\nExpected:\n{}\nActual:\n{}",
+            format_rows(&expected),
+            format_rows(&actual)
+        );
+    }
+}
+
+#[cfg(test)]
+mod screen_plotter_tests {
+    use super::*;
+
+    #[test]
+    fn starts_blank() {
+        let plotter = ScreenPlotter::new(Dimensions { width: 3, height: 2 });
+
+        assert_eq!("\n", plotter.text());
+    }
+
+    #[test]
+    fn applies_plotted_content() {
+        let mut plotter = ScreenPlotter::new(Dimensions { width: 6, height: 2 });
+        plotter.plot(Point { x: 0, y: 0 }, "FOOBAR").unwrap();
+        plotter.plot(Point { x: 3, y: 0 }, "QUX").unwrap();
+        plotter.plot(Point { x: 1, y: 1 }, "┌─┐").unwrap();
+
+        assert_eq!("FOOQUX\n ┌─┐", plotter.text());
+    }
+
+    #[test]
+    fn clips_content_outside_of_the_screen() {
+        let mut plotter = ScreenPlotter::new(Dimensions { width: 4, height: 1 });
+        plotter.plot(Point { x: 2, y: 0 }, "FOO").unwrap();
+        plotter.plot(Point { x: 0, y: 1 }, "BAR").unwrap();
+
+        assert_eq!("  FO", plotter.text());
+    }
+
+    #[test]
+    fn records_the_paintbrush_of_each_cell() {
+        let mut plotter = ScreenPlotter::new(Dimensions { width: 4, height: 1 });
+        let red = Paintbrush { fg: Color::Red, ..Paintbrush::create_default() };
+        plotter.set_paintbrush(&red).unwrap();
+        plotter.plot(Point { x: 1, y: 0 }, "A").unwrap();
+
+        assert_eq!(("A", &red), plotter.cell(Point { x: 1, y: 0 }));
+        assert_eq!((" ", &Paintbrush::create_default()), plotter.cell(Point { x: 0, y: 0 }));
+    }
+
+    #[test]
+    fn writes_styles_where_they_change() {
+        let mut plotter = ScreenPlotter::new(Dimensions { width: 8, height: 2 });
+        plotter
+            .set_paintbrush(&Paintbrush { fg: Color::Red, bg: Color::Unset, bold: false })
+            .unwrap();
+        plotter.plot(Point { x: 0, y: 0 }, "AB").unwrap();
+        plotter.set_paintbrush(&Paintbrush::create_default()).unwrap();
+        plotter.plot(Point { x: 2, y: 0 }, "C").unwrap();
+        plotter
+            .set_paintbrush(&Paintbrush { fg: Color::Unset, bg: Color::Blue, bold: true })
+            .unwrap();
+        plotter.plot(Point { x: 1, y: 1 }, " ").unwrap();
+
+        plotter.assert_styled_screen(
+            "
+            |{Red}AB{}C
+            | {Unset on Blue bold} {}
+            ",
+        );
+    }
+
+    #[test]
+    fn can_be_compared_with_a_snapshot() {
+        let mut plotter = ScreenPlotter::new(Dimensions { width: 5, height: 3 });
+        plotter.plot(Point { x: 0, y: 0 }, "┌───┐").unwrap();
+        plotter.plot(Point { x: 0, y: 1 }, "│ X │").unwrap();
+        plotter.plot(Point { x: 0, y: 2 }, "└───┘").unwrap();
+
+        plotter.assert_screen(
+            "
+            |┌───┐
+            |│ X │
+            |└───┘
+            ",
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the snapshot")]
+    fn panics_when_the_screen_does_not_match_the_snapshot() {
+        let plotter = ScreenPlotter::new(Dimensions { width: 5, height: 1 });
+
+        plotter.assert_screen("|FOO");
+    }
+}
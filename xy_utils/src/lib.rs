@@ -74,7 +74,7 @@ impl Dimensions {
     /// assert_eq!(24, d.total_area());
     /// ```
     pub fn total_area(&self) -> usize {
-        self.height as usize * self.width as usize
+        self.height * self.width
     }
 
     /// Is the height of the grid defined?
@@ -208,16 +208,16 @@ mod dimensions_tests {
     fn empty_dimensions_string_results_in_empty_dimensions_object() {
         let dimensions_str = "";
         let dimensions = Dimensions::from_str(dimensions_str).unwrap();
-        assert_eq!(false, dimensions.is_width_defined());
-        assert_eq!(false, dimensions.is_height_defined());
+        assert!(!dimensions.is_width_defined());
+        assert!(!dimensions.is_height_defined());
     }
 
     #[test]
     fn calculated_dimensions_string_results_in_empty_dimensions_object() {
         let dimensions_str = "calculated";
         let dimensions = Dimensions::from_str(dimensions_str).unwrap();
-        assert_eq!(false, dimensions.is_width_defined());
-        assert_eq!(false, dimensions.is_height_defined());
+        assert!(!dimensions.is_width_defined());
+        assert!(!dimensions.is_height_defined());
     }
 
     #[test]